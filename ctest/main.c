@@ -156,9 +156,297 @@ test_encrypt_vguess_large_str(void)
 	assert(strcmp(filename_orig, filename) == 0);
 }
 
+static void
+test_encrypt_null_argument(void)
+{
+	int encryption_result = snailcrypt_ez_encrypt(NULL, "2022-11-19T17:00:00+0100", "", "", encrypted_callback);
+	assert(encryption_result == SNAILCRYPT_ERROR_NULL_POINTER);
+	assert(snailcrypt_last_error_message());
+	assert(strstr(snailcrypt_last_error_message(), "plaintext"));
+}
+
+static void
+test_encrypt_null_callback(void)
+{
+	int encryption_result = snailcrypt_ez_encrypt("hello world", "2022-11-19T17:00:00+0100", "", "", NULL);
+	assert(encryption_result == SNAILCRYPT_ERROR_NULL_POINTER);
+	assert(snailcrypt_last_error_message());
+}
+
+static void
+test_encrypt_invalid_utf8(void)
+{
+	int encryption_result = snailcrypt_ez_encrypt("\xff\xfe", "2022-11-19T17:00:00+0100", "", "", encrypted_callback);
+	assert(encryption_result == SNAILCRYPT_ERROR_INVALID_UTF8);
+	assert(snailcrypt_last_error_message());
+}
+
+static void
+test_encrypt_invalid_lockdate(void)
+{
+	int encryption_result = snailcrypt_ez_encrypt("hello world", "someday", "", "", encrypted_callback);
+	assert(encryption_result == SNAILCRYPT_ERROR_INVALID_LOCKDATE);
+	assert(snailcrypt_last_error_message());
+}
+
+static void
+test_decrypt_invalid_cipher(void)
+{
+	int decryption_result = snailcrypt_ez_decrypt("", decrypted_callback);
+	assert(decryption_result == SNAILCRYPT_ERROR_DECRYPTION);
+	assert(snailcrypt_last_error_message());
+
+	decryption_result = snailcrypt_ez_decrypt("9:aGVsbG8=", decrypted_callback);
+	assert(decryption_result == SNAILCRYPT_ERROR_DECRYPTION);
+	assert(strstr(snailcrypt_last_error_message(), "Unknown client version"));
+
+	decryption_result = snailcrypt_ez_decrypt(NULL, decrypted_callback);
+	assert(decryption_result == SNAILCRYPT_ERROR_NULL_POINTER);
+}
+
+static int
+inspected_callback(uint32_t version, const char *lockdate, const char *hint_new, const char *filename_new)
+{
+	return (int) version;
+}
+
+static int
+inspected_with_data_callback(void *user_data, uint32_t version, const char *lockdate, const char *hint_new, const char *filename_new)
+{
+	*(uint32_t *) user_data = version;
+
+	return 0;
+}
+
+static void
+test_client_handle_failure(void)
+{
+	SnailcryptConfig config;
+	memset(&config, 0, sizeof(config));
+	config.api_url = "http://127.0.0.1:1";
+	config.connect_timeout_ms = 1000;
+	config.timeout_ms = 1000;
+
+	SnailcryptClient *client = snailcrypt_client_new(&config);
+	assert(client);
+
+	int encryption_result = snailcrypt_client_encrypt(client, "hello world", "2022-11-19T17:00:00+0100", "", "", encrypted_callback);
+	assert(encryption_result == SNAILCRYPT_ERROR_ENCRYPTION);
+	assert(snailcrypt_last_error_message());
+
+	int inspection_result = snailcrypt_client_inspect(client, "9:aGVsbG8=", inspected_callback);
+	assert(inspection_result == SNAILCRYPT_ERROR_DECRYPTION);
+
+	uint32_t version = 0;
+	inspection_result = snailcrypt_client_inspect_with_data(client, "3:Mjk5OS0xMS0xOVQxNzowMDowMCswMTAw:cGF5bG9hZA==:QSBoaW50:cmVwb3J0LnBkZg==", inspected_with_data_callback, &version);
+	assert(inspection_result == 0);
+	assert(version == 3);
+
+	/* The countdown falls back to the local clock if the key server cannot be reached */
+	int64_t seconds = -1;
+	assert(snailcrypt_client_seconds_until_unlock(client, "3:Mjk5OS0xMS0xOVQxNzowMDowMCswMTAw:cGF5bG9hZA==:QSBoaW50:cmVwb3J0LnBkZg==", &seconds) == SNAILCRYPT_WARNING_LOCAL_CLOCK);
+	assert(seconds > 0);
+	assert(snailcrypt_client_seconds_until_unlock(client, "9:aGVsbG8=", &seconds) == SNAILCRYPT_ERROR_DECRYPTION);
+
+	snailcrypt_client_free(client);
+
+	int decryption_result = snailcrypt_client_decrypt(NULL, "1:aGVsbG8=", decrypted_callback);
+	assert(decryption_result == SNAILCRYPT_ERROR_NULL_POINTER);
+}
+
+static void
+test_buffer_and_file_failure(void)
+{
+	SnailcryptClient *client = snailcrypt_client_new(NULL);
+	assert(client);
+
+	const uint8_t binary[] = { 0xff, 0x00, 0xfe };
+	SnailcryptBuffer cipher_buffer = { NULL, 0 };
+	int encryption_result = snailcrypt_client_encrypt_buffer(client, binary, sizeof(binary), "2022-11-19T17:00:00+0100", NULL, NULL, &cipher_buffer);
+	assert(encryption_result == SNAILCRYPT_ERROR_INVALID_UTF8);
+	assert(cipher_buffer.data == NULL);
+
+	snailcrypt_buffer_free(&cipher_buffer);
+	snailcrypt_buffer_free(NULL);
+	snailcrypt_client_free(client);
+
+	encryption_result = snailcrypt_encrypt_file("/nonexistent/snailcrypt.txt", "/nonexistent/snailcrypt.txt.snailcrypt", "2022-11-19T17:00:00+0100", NULL);
+	assert(encryption_result == SNAILCRYPT_ERROR_IO);
+	assert(snailcrypt_last_error_message());
+
+	int decryption_result = snailcrypt_decrypt_file("/nonexistent/snailcrypt.txt.snailcrypt", "/tmp");
+	assert(decryption_result == SNAILCRYPT_ERROR_IO);
+}
+
+struct result {
+	int called;
+	int code;
+	char cipher[4096];
+	char plaintext[4096];
+};
+
+static int
+encrypted_with_data_callback(void *user_data, const char *cipher_new)
+{
+	struct result *result = user_data;
+
+	result->called++;
+	strncpy(result->cipher, cipher_new, sizeof(result->cipher) - 1);
+
+	return 0;
+}
+
+static int
+decrypted_with_data_callback(void *user_data, const char *plaintext_new, const char *hint_new, const char *filename_new)
+{
+	struct result *result = user_data;
+
+	result->called++;
+	strncpy(result->plaintext, plaintext_new, sizeof(result->plaintext) - 1);
+
+	return 0;
+}
+
+static void
+encrypted_async_callback(void *user_data, int32_t code, const char *cipher_new, const char *error_message)
+{
+	struct result *result = user_data;
+
+	result->called++;
+	result->code = code;
+	if (code == SNAILCRYPT_OK)
+		strncpy(result->cipher, cipher_new, sizeof(result->cipher) - 1);
+	else
+		assert(error_message);
+}
+
+static void
+test_user_data_failure(void)
+{
+	struct result result;
+	memset(&result, 0, sizeof(result));
+
+	int encryption_result = snailcrypt_ez_encrypt_with_data("hello world", "someday", "", "", encrypted_with_data_callback, &result);
+	assert(encryption_result == SNAILCRYPT_ERROR_INVALID_LOCKDATE);
+	assert(result.called == 0);
+}
+
+static void
+test_async_failure(void)
+{
+	struct result result;
+	memset(&result, 0, sizeof(result));
+
+	SnailcryptConfig config;
+	memset(&config, 0, sizeof(config));
+	config.api_url = "http://127.0.0.1:1";
+	config.connect_timeout_ms = 1000;
+
+	SnailcryptClient *client = snailcrypt_client_new(&config);
+	assert(client);
+
+	int encryption_result = snailcrypt_client_encrypt_async(client, "hello world", "2022-11-19T17:00:00+0100", "", "", encrypted_async_callback, &result);
+	assert(encryption_result == SNAILCRYPT_OK);
+
+	/**************************************************************************
+	 * Releasing the handle waits for the pending request
+	 */
+	snailcrypt_client_free(client);
+
+	assert(result.called == 1);
+	assert(result.code == SNAILCRYPT_ERROR_ENCRYPTION);
+}
+
+static void
+test_lockdate_policy_failure(void)
+{
+	SnailcryptConfig config;
+	memset(&config, 0, sizeof(config));
+	config.api_url = "http://127.0.0.1:1";
+	config.connect_timeout_ms = 1000;
+	config.timeout_ms = 1000;
+	config.reject_past_lockdates = true;
+
+	SnailcryptClient *client = snailcrypt_client_new(&config);
+	assert(client);
+
+	/**************************************************************************
+	 * The lockdate has already passed, so nothing is sent to the key server
+	 */
+	int encryption_result = snailcrypt_client_encrypt(client, "hello world", "2022-11-19T17:00:00+0100", "", "", encrypted_callback);
+	assert(encryption_result == SNAILCRYPT_ERROR_LOCKDATE_POLICY);
+	assert(strstr(snailcrypt_last_error_message(), "already passed"));
+
+	snailcrypt_client_free(client);
+}
+
+static void
+test_encrypt_vguess_user_data(void)
+{
+	struct result result;
+	memset(&result, 0, sizeof(result));
+
+	int encryption_result = snailcrypt_ez_encrypt_with_data("hello world", "2022-11-19T17:00:00+0100", "", "", encrypted_with_data_callback, &result);
+	assert(encryption_result == 0);
+
+	int decryption_result = snailcrypt_ez_decrypt_with_data(result.cipher, decrypted_with_data_callback, &result);
+	assert(decryption_result == 0);
+	assert(result.called == 2);
+
+	assert(strcmp("hello world", result.plaintext) == 0);
+}
+
+static void
+test_inspect(void)
+{
+	const char *locked_cipher = "3:Mjk5OS0xMS0xOVQxNzowMDowMCswMTAw:cGF5bG9hZA==:QSBoaW50:cmVwb3J0LnBkZg==";
+	const char *released_cipher = "3:MjAyMi0xMS0xOVQxNzowMDowMCswMTAw:cGF5bG9hZA==:QSBoaW50:cmVwb3J0LnBkZg==";
+
+	SnailcryptInspectInfo info;
+	memset(&info, 0, sizeof(info));
+
+	int inspection_result = snailcrypt_inspect(locked_cipher, &info);
+	assert(inspection_result == SNAILCRYPT_OK);
+	assert(info.version == 3);
+	assert(info.lockdate_unix == 32500022400LL);
+	assert(strcmp(info.lockdate, "2999-11-19T17:00:00+01:00") == 0);
+	assert(strcmp(info.hint, "A hint") == 0);
+	assert(strcmp(info.filename, "report.pdf") == 0);
+	assert(info.locked);
+	snailcrypt_inspect_info_free(&info);
+	assert(info.hint == NULL);
+
+	inspection_result = snailcrypt_inspect(released_cipher, &info);
+	assert(inspection_result == SNAILCRYPT_OK);
+	assert(!info.locked);
+	snailcrypt_inspect_info_free(&info);
+
+	int64_t seconds = -1;
+	assert(snailcrypt_seconds_until_unlock(locked_cipher, &seconds) == SNAILCRYPT_OK);
+	assert(seconds > 0);
+	assert(snailcrypt_seconds_until_unlock(released_cipher, &seconds) == SNAILCRYPT_OK);
+	assert(seconds == 0);
+
+	assert(snailcrypt_inspect("9:aGVsbG8=", &info) == SNAILCRYPT_ERROR_DECRYPTION);
+	assert(snailcrypt_inspect(locked_cipher, NULL) == SNAILCRYPT_ERROR_NULL_POINTER);
+}
+
 int main(void) {
+	test_encrypt_null_argument();
+	test_encrypt_null_callback();
+	test_encrypt_invalid_utf8();
+	test_encrypt_invalid_lockdate();
+	test_decrypt_invalid_cipher();
+	test_client_handle_failure();
+	test_buffer_and_file_failure();
+	test_user_data_failure();
+	test_async_failure();
+	test_lockdate_policy_failure();
+	test_inspect();
+
 	test_encrypt_vguess_small_str();
 	test_encrypt_vguess_large_str();
+	test_encrypt_vguess_user_data();
 
 	/**************************************************************************
 	 * Free "cipher" if set
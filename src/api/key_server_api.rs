@@ -0,0 +1,428 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    config::Config,
+    format::DATETIME_FORMAT,
+    transport::{
+        self,
+        Transport,
+    },
+};
+
+use std::{
+    error,
+    fmt,
+    sync::Arc,
+    time::Duration,
+};
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+    Utc,
+};
+use serde::{
+    de::{
+        self,
+        DeserializeOwned,
+        IgnoredAny,
+    },
+    Deserialize,
+    Deserializer,
+    Serialize,
+};
+use serde_json::Value;
+
+/// The endpoint issuing the keys of lockdates.
+const KEYS_ENDPOINT: &str = "/keys";
+/// The endpoint describing the capabilities of the key server.
+const METADATA_ENDPOINT: &str = "/metadata";
+/// The endpoint registering revocable messages.
+const MESSAGES_ENDPOINT: &str = "/messages";
+/// The endpoint revoking messages.
+const MESSAGES_REVOKE_ENDPOINT: &str = "/messages/revoke";
+/// The endpoint telling the status of messages.
+const MESSAGES_STATUS_ENDPOINT: &str = "/messages/status";
+/// The endpoint releasing the keys of messages.
+const MESSAGES_KEY_ENDPOINT: &str = "/messages/key";
+
+/// The error code of failures without an answer of the key server.
+pub const API_ERROR_UNREACHABLE: &str = "unreachable";
+/// The error code of requests which cannot be serialized.
+pub const API_ERROR_INVALID_REQUEST: &str = "invalid_request";
+/// The error code of answers of the key server which cannot be parsed.
+pub const API_ERROR_INVALID_RESPONSE: &str = "invalid_response";
+
+/// The request for the keys of a lockdate.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyRequest {
+    pub lock_date: String,
+}
+
+impl KeyRequest {
+    pub fn new(lockdate: DateTime<FixedOffset>) -> KeyRequest {
+        KeyRequest {
+            lock_date: lockdate.format(DATETIME_FORMAT).to_string(),
+        }
+    }
+}
+
+/// The keys of a lockdate. The private key is only present once the lockdate has passed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyResponse {
+    pub public_key: String,
+    #[serde(default)]
+    pub private_key: Option<String>,
+}
+
+/// The request registering a revocable message until a lockdate.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageRequest {
+    pub lock_date: String,
+}
+
+impl MessageRequest {
+    pub fn new(lockdate: DateTime<FixedOffset>) -> MessageRequest {
+        MessageRequest {
+            lock_date: lockdate.format(DATETIME_FORMAT).to_string(),
+        }
+    }
+}
+
+/// A registered message. The revocation token is needed to revoke it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageRegistrationResponse {
+    pub message_id: String,
+    pub public_key: String,
+    pub revocation_token: String,
+}
+
+/// The request revoking a message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageRevocationRequest {
+    pub message_id: String,
+    pub revocation_token: String,
+}
+
+/// A request concerning a single message, e.g. for its status or its key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageIdRequest {
+    pub message_id: String,
+}
+
+/// The status of a message, e.g. "pending", "released" or "revoked".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageStatusResponse {
+    pub status: String,
+}
+
+/// The key of a message. The private key is only present once the message has been released.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageKeyResponse {
+    pub status: String,
+    #[serde(default)]
+    pub private_key: Option<String>,
+}
+
+/// The metadata of the key server. Only the API version is required. Attributes of an unexpected type are left
+/// unknown instead of failing the whole metadata.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MetadataResponse {
+    pub api_version: u32,
+    #[serde(default, deserialize_with = "deserialize_lenient_vec")]
+    pub key_algorithms: Vec<KeyAlgorithmMetadata>,
+    /// The slot granularity of the lockdates in seconds.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub lockdate_granularity: Option<i64>,
+    /// The maximum time between now and a lockdate in seconds.
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub max_horizon: Option<i64>,
+    #[serde(default, deserialize_with = "deserialize_lenient")]
+    pub server_time: Option<String>,
+}
+
+/// A key algorithm within the metadata of the key server.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyAlgorithmMetadata {
+    pub name: String,
+    /// The key sizes in bits. Empty if the key server does not tell.
+    #[serde(default, deserialize_with = "deserialize_lenient_vec")]
+    pub sizes: Vec<usize>,
+}
+
+/// An error of a request to the key server. Errors reported by the key server keep its code and message. Failures
+/// without a usable answer use the codes `API_ERROR_UNREACHABLE`, `API_ERROR_INVALID_REQUEST` and
+/// `API_ERROR_INVALID_RESPONSE`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiError {
+    /// The error code. The key server sends it either as number or as string, it is always kept as string.
+    #[serde(deserialize_with = "deserialize_code")]
+    pub code: String,
+    #[serde(default)]
+    pub message: String,
+    /// The HTTP status of the answer. `None` if there is no answer or the transport does not supply the status.
+    #[serde(skip)]
+    pub status: Option<u16>,
+}
+
+impl ApiError {
+    fn new(code: &str, message: String, status: Option<u16>) -> ApiError {
+        ApiError {
+            code: String::from(code),
+            message,
+            status,
+        }
+    }
+
+    /// True if the key server has not been reached at all.
+    pub fn is_unreachable(&self) -> bool {
+        self.code == API_ERROR_UNREACHABLE
+    }
+}
+
+/// This method will print the message of the error.
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.message.is_empty() {
+            return write!(f, "Error: the key server rejected the request with code {}", self.code);
+        }
+
+        write!(f, "{}", self.message)
+    }
+}
+
+impl error::Error for ApiError {}
+
+/// Accept an error code given either as number or as string.
+fn deserialize_code<'de, D>(deserializer: D) -> Result<String, D::Error>
+    where D: Deserializer<'de> {
+    return match Value::deserialize(deserializer)? {
+        Value::String(code) => Ok(code),
+        Value::Number(code) => Ok(code.to_string()),
+        code => Err(de::Error::custom(format!("invalid error code {}", code))),
+    };
+}
+
+/// Accept an attribute of an unexpected type as missing.
+fn deserialize_lenient<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where D: Deserializer<'de>,
+          T: DeserializeOwned {
+    Ok(serde_json::from_value(Value::deserialize(deserializer)?).ok())
+}
+
+/// Accept a list skipping its elements of an unexpected type. Anything but a list is taken as an empty one.
+fn deserialize_lenient_vec<'de, D, T>(deserializer: D) -> Result<Vec<T>, D::Error>
+    where D: Deserializer<'de>,
+          T: DeserializeOwned {
+    Ok(match Value::deserialize(deserializer)? {
+        Value::Array(values) => values
+            .into_iter()
+            .filter_map(|value| serde_json::from_value(value).ok())
+            .collect(),
+        _ => Vec::new(),
+    })
+}
+
+/// Typed access to the REST API of the key server. The clients use it internally, but it may be used directly as
+/// well, e.g. to fetch the public key of a lockdate.
+///
+/// Every failure is mapped to an `ApiError`: an error status, an answer carrying an error code or an answer of an
+/// unexpected shape. Transports which do not supply the status are handled by the error code alone.
+pub struct KeyServerApi {
+    config: Arc<dyn Config>,
+}
+
+impl KeyServerApi {
+    pub fn new(config: Arc<dyn Config>) -> KeyServerApi {
+        KeyServerApi { config }
+    }
+
+    pub fn get_config(&self) -> &Arc<dyn Config> {
+        &self.config
+    }
+
+    /// Send a request to an endpoint of the key server using HTTP POST and parse the answer.
+    pub fn post<I, O>(&self, endpoint: &str, input: &I) -> Result<O, ApiError>
+        where I: Serialize,
+              O: DeserializeOwned {
+        /***********************************************************************
+         * Serialize the request
+         */
+        let input_string: String = serde_json::to_string(input)
+            .map_err(|error| ApiError::new(API_ERROR_INVALID_REQUEST,
+                                           format!("Error: unable to serialize the request: {}", error),
+                                           None))?;
+
+        /***********************************************************************
+         * Perform HTTP POST using the transport of the configuration
+         */
+        let response = self.get_transport()?
+            .post_with_status(self.get_url(endpoint).as_str(),
+                              input_string.as_str(),
+                              self.config.get_connect_timeout(),
+                              self.config.get_timeout())
+            .map_err(unreachable_error)?;
+
+        parse_response(&response.body, response.status)
+    }
+
+    /// Send a request to an endpoint of the key server using HTTP GET and parse the answer. The transport does not
+    /// supply the status of GET requests, so errors are recognized by their error code alone.
+    pub fn get<O>(&self, endpoint: &str) -> Result<O, ApiError>
+        where O: DeserializeOwned {
+        let output_vector: Vec<u8> = self.get_transport()?
+            .get(self.get_url(endpoint).as_str(),
+                 self.config.get_connect_timeout(),
+                 self.config.get_timeout())
+            .map_err(unreachable_error)?;
+
+        parse_response(&output_vector, None)
+    }
+
+    /// Get the transport reaching the key server, i.e. the transport of the configuration or the default transport.
+    pub fn get_transport(&self) -> Result<Arc<dyn Transport>, ApiError> {
+        self.config
+            .get_transport()
+            .or_else(transport::default_transport)
+            .ok_or_else(|| ApiError::new(API_ERROR_UNREACHABLE,
+                                         String::from("Error: no transport to reach the key server is configured"),
+                                         None))
+    }
+
+    fn get_url(&self, endpoint: &str) -> String {
+        self.config.get_api_url().to_string() + endpoint
+    }
+
+    /// Request the keys of a lockdate.
+    pub fn request_keys(&self, request: &KeyRequest) -> Result<KeyResponse, ApiError> {
+        self.post(KEYS_ENDPOINT, request)
+    }
+
+    /// Get the PEM encoded public key of a lockdate.
+    pub fn get_public_key_pem(&self, lockdate: DateTime<FixedOffset>) -> Result<String, ApiError> {
+        self.request_keys(&KeyRequest::new(lockdate))
+            .map(|response| unquote_pem(response.public_key.as_str()))
+    }
+
+    /// Get the PEM encoded private key of a lockdate. `None` if the key server has not released it yet.
+    pub fn get_private_key_pem(&self, lockdate: DateTime<FixedOffset>) -> Result<Option<String>, ApiError> {
+        self.request_keys(&KeyRequest::new(lockdate))
+            .map(|response| response.private_key
+                 .map(|private_key| unquote_pem(private_key.as_str())))
+    }
+
+    /// Get the metadata of the key server. Returns `None` if the key server does not supply metadata and an error only
+    /// if it cannot be reached at all.
+    pub fn get_metadata(&self) -> Result<Option<MetadataResponse>, ApiError> {
+        match self.get(METADATA_ENDPOINT) {
+            Ok(metadata) => Ok(Some(metadata)),
+            Err(error) if error.is_unreachable() => Err(error),
+            /* An unknown endpoint answers with anything else */
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Get the time of the key server from the `Date` header of its answer. The timeouts are passed explicitly, as
+    /// reading a clock should not wait as long as an encryption may.
+    pub fn get_server_date(&self, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<DateTime<Utc>, ApiError> {
+        self.get_transport()?
+            .get_date(self.config.get_api_url(), connect_timeout, timeout)
+            .map_err(unreachable_error)
+    }
+
+    /// Register a revocable message until a lockdate.
+    pub fn register_message(&self, request: &MessageRequest) -> Result<MessageRegistrationResponse, ApiError> {
+        self.post(MESSAGES_ENDPOINT, request)
+    }
+
+    /// Revoke a message, so the key server never releases its key.
+    pub fn revoke_message(&self, request: &MessageRevocationRequest) -> Result<(), ApiError> {
+        self.post::<_, IgnoredAny>(MESSAGES_REVOKE_ENDPOINT, request)
+            .map(|_| ())
+    }
+
+    /// Get the status of a message.
+    pub fn get_message_status(&self, request: &MessageIdRequest) -> Result<MessageStatusResponse, ApiError> {
+        self.post(MESSAGES_STATUS_ENDPOINT, request)
+    }
+
+    /// Get the key of a message. The private key is only present once the message has been released.
+    pub fn get_message_key(&self, request: &MessageIdRequest) -> Result<MessageKeyResponse, ApiError> {
+        self.post(MESSAGES_KEY_ENDPOINT, request)
+    }
+}
+
+fn unreachable_error(error: String) -> ApiError {
+    ApiError::new(API_ERROR_UNREACHABLE,
+                  format!("Error: unable to reach the key server: {}", error),
+                  None)
+}
+
+/// Parse the answer of the key server. An error status or an error code is turned into an `ApiError`.
+fn parse_response<O>(body: &[u8], status: Option<u16>) -> Result<O, ApiError>
+    where O: DeserializeOwned {
+    /***************************************************************************
+     * Error: the key server answered with an error status or an error code
+     */
+    let output_object: Result<Value, _> = serde_json::from_slice(body);
+
+    if let Some(status) = status.filter(|status| !(200..300).contains(status)) {
+        return Err(output_object
+            .ok()
+            .and_then(|output_object| serde_json::from_value::<ApiError>(output_object).ok())
+            .map(|api_error| ApiError { status: Some(status), ..api_error })
+            .unwrap_or_else(|| ApiError::new(status.to_string().as_str(),
+                                             format!("Error: the key server answered with status {}", status),
+                                             Some(status))));
+    }
+
+    let output_object: Value = output_object
+        .map_err(|error| ApiError::new(API_ERROR_INVALID_RESPONSE,
+                                       format!("Error: unable to parse the response of the key server: {}", error),
+                                       status))?;
+
+    if !output_object["code"].is_null() {
+        return Err(serde_json::from_value::<ApiError>(output_object)
+            .map(|api_error| ApiError { status, ..api_error })
+            .unwrap_or_else(|error| ApiError::new(API_ERROR_INVALID_RESPONSE,
+                                                  format!("Error: unable to parse the error of the key server: {}",
+                                                          error),
+                                                  status)));
+    }
+
+    /***************************************************************************
+     * Parse the answer
+     */
+    serde_json::from_value(output_object)
+        .map_err(|error| ApiError::new(API_ERROR_INVALID_RESPONSE,
+                                       format!("Error: unable to parse the response of the key server: {}", error),
+                                       status))
+}
+
+/// The key server may wrap keys in single quotes, which are no part of the PEM.
+fn unquote_pem(pem: &str) -> String {
+    pem.replace('\'', "")
+}
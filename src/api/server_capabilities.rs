@@ -0,0 +1,242 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    api::{
+        ApiError,
+        KeyServerApi,
+        MetadataResponse,
+    },
+    format::DATETIME_FORMAT,
+    lockdate::LockdatePolicy,
+};
+
+use chrono::{
+    DateTime,
+    Duration,
+    Utc,
+};
+use serde_json::Value;
+
+/// The API version spoken by key servers without a metadata endpoint.
+const FALLBACK_API_VERSION: u32 = 1;
+/// The newest API version of the key server the clients are able to speak.
+pub const SUPPORTED_API_VERSION: u32 = 1;
+
+/// A key algorithm offered by a key server.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerKeyAlgorithm {
+    /// The name of the algorithm, e.g. "RSA".
+    pub name: String,
+    /// The key sizes in bits. Empty if the key server does not tell.
+    pub sizes: Vec<usize>,
+}
+
+/// The capabilities of a key server. They are discovered using the metadata endpoint of the key server. Key servers
+/// without this endpoint get the fallback capabilities, which only assume the API version 1 and leave everything else
+/// unknown.
+///
+/// The clients discover the capabilities on first use and cache them. They round lockdates to the granularity of the
+/// key server, reject lockdates beyond its horizon and reject keys it does not advertise.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServerCapabilities {
+    discovered: bool,
+    api_version: u32,
+    key_algorithms: Vec<ServerKeyAlgorithm>,
+    lockdate_granularity: Option<Duration>,
+    max_horizon: Option<Duration>,
+    server_time: Option<DateTime<Utc>>,
+}
+
+impl ServerCapabilities {
+    /// The capabilities assumed for a key server without a metadata endpoint.
+    pub fn fallback() -> ServerCapabilities {
+        ServerCapabilities {
+            discovered: false,
+            api_version: FALLBACK_API_VERSION,
+            key_algorithms: Vec::new(),
+            lockdate_granularity: None,
+            max_horizon: None,
+            server_time: None,
+        }
+    }
+
+    /// Query the metadata of a key server. Returns the fallback capabilities if the key server does not supply
+    /// metadata, and an error only if it cannot be reached at all.
+    pub fn discover(key_server_api: &KeyServerApi) -> Result<ServerCapabilities, ApiError> {
+        Ok(key_server_api
+            .get_metadata()?
+            .map(ServerCapabilities::from_metadata)
+            .unwrap_or_else(ServerCapabilities::fallback))
+    }
+
+    /// Parse the response of the metadata endpoint. Returns `None` if it is no metadata. Unknown or invalid attributes
+    /// are left unknown.
+    pub fn from_json(output_object: &Value) -> Option<ServerCapabilities> {
+        serde_json::from_value::<MetadataResponse>(output_object.clone())
+            .ok()
+            .map(ServerCapabilities::from_metadata)
+    }
+
+    /// Get the capabilities described by the metadata of the key server.
+    pub fn from_metadata(metadata: MetadataResponse) -> ServerCapabilities {
+        let server_time = metadata.server_time
+            .and_then(|server_time| DateTime::parse_from_rfc3339(server_time.as_str())
+                      .or_else(|_| DateTime::parse_from_str(server_time.as_str(), DATETIME_FORMAT))
+                      .ok())
+            .map(|server_time| server_time.to_utc());
+
+        ServerCapabilities {
+            discovered: true,
+            api_version: metadata.api_version,
+            key_algorithms: metadata.key_algorithms
+                .into_iter()
+                .map(|key_algorithm| ServerKeyAlgorithm {
+                    name: key_algorithm.name,
+                    sizes: key_algorithm.sizes,
+                })
+                .collect(),
+            lockdate_granularity: positive_seconds(metadata.lockdate_granularity),
+            max_horizon: positive_seconds(metadata.max_horizon),
+            server_time,
+        }
+    }
+
+    /// True if the key server supplied metadata, false if these are the fallback capabilities.
+    pub fn is_discovered(&self) -> bool {
+        self.discovered
+    }
+
+    pub fn get_api_version(&self) -> u32 {
+        self.api_version
+    }
+
+    /// The key algorithms offered by the key server. Empty if unknown.
+    pub fn get_key_algorithms(&self) -> &[ServerKeyAlgorithm] {
+        &self.key_algorithms
+    }
+
+    /// The slot granularity of the lockdates. `None` if unknown.
+    pub fn get_lockdate_granularity(&self) -> Option<Duration> {
+        self.lockdate_granularity
+    }
+
+    /// The maximum time between now and a lockdate. `None` if unknown or unlimited.
+    pub fn get_max_horizon(&self) -> Option<Duration> {
+        self.max_horizon
+    }
+
+    /// The time of the key server when it answered the discovery. `None` if unknown.
+    pub fn get_server_time(&self) -> Option<DateTime<Utc>> {
+        self.server_time
+    }
+
+    /// Check that the clients speak the API version of the key server. Key servers announcing a newer version may have
+    /// changed their endpoints, so they are not accessed at all.
+    pub fn check_api_version(&self) -> Result<(), String> {
+        if self.api_version == 0 || self.api_version > SUPPORTED_API_VERSION {
+            return Err(format!("Error: the key server speaks the API version {}, but only the versions up to {} are supported",
+                               self.api_version,
+                               SUPPORTED_API_VERSION));
+        }
+
+        Ok(())
+    }
+
+    /// Combine a lockdate policy with the limits of the key server. Lockdates are rounded to the least common multiple
+    /// of both granularities, so they fall on a slot of either, and the nearer horizon wins. Returns `None` if neither
+    /// the policy nor the key server restrict lockdates.
+    pub fn restrict_policy(&self, lockdate_policy: Option<&LockdatePolicy>) -> Option<LockdatePolicy> {
+        if lockdate_policy.is_none() && self.lockdate_granularity.is_none() && self.max_horizon.is_none() {
+            return None;
+        }
+
+        let mut restricted_policy = lockdate_policy
+            .cloned()
+            .unwrap_or_default();
+        restricted_policy.set_granularity(lcm_option(restricted_policy.get_granularity(), self.lockdate_granularity));
+        restricted_policy.set_max_horizon(min_option(restricted_policy.get_max_horizon(), self.max_horizon));
+
+        Some(restricted_policy)
+    }
+
+    /// Check a key received from the key server against the advertised key algorithms. Every key passes if they are
+    /// unknown.
+    pub fn check_key(&self, algorithm: &str, size: usize) -> Result<(), String> {
+        if self.key_algorithms.is_empty() {
+            return Ok(());
+        }
+
+        let key_algorithm = self.key_algorithms
+            .iter()
+            .find(|key_algorithm| key_algorithm.name.eq_ignore_ascii_case(algorithm))
+            .ok_or_else(|| format!("Error: the key server does not offer {} keys", algorithm))?;
+
+        if !key_algorithm.sizes.is_empty() && !key_algorithm.sizes.contains(&size) {
+            return Err(format!("Error: the key server sent a {} key of {} bits, which it does not advertise",
+                               algorithm,
+                               size));
+        }
+
+        Ok(())
+    }
+}
+
+/// Turn a positive number of seconds into a duration.
+fn positive_seconds(seconds: Option<i64>) -> Option<Duration> {
+    seconds
+        .filter(|seconds| *seconds > 0)
+        .and_then(Duration::try_seconds)
+}
+
+/// The least common multiple of two granularities in whole seconds. The coarser one is used if the multiple does not
+/// fit into a duration.
+fn lcm_option(first: Option<Duration>, second: Option<Duration>) -> Option<Duration> {
+    match (first, second) {
+        (Some(first), Some(second)) if first.num_seconds() > 0 && second.num_seconds() > 0 => {
+            let (first_seconds, second_seconds) = (first.num_seconds(), second.num_seconds());
+
+            (first_seconds / gcd(first_seconds, second_seconds))
+                .checked_mul(second_seconds)
+                .and_then(Duration::try_seconds)
+                .or(Some(first.max(second)))
+        },
+        (first, second) => first.max(second),
+    }
+}
+
+fn gcd(mut first: i64, mut second: i64) -> i64 {
+    while second != 0 {
+        (first, second) = (second, first % second);
+    }
+
+    first
+}
+
+fn min_option(first: Option<Duration>, second: Option<Duration>) -> Option<Duration> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(first.min(second)),
+        (first, second) => first.or(second),
+    }
+}
@@ -0,0 +1,285 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+//! A command line tool to encrypt, decrypt and inspect snailcrypt cipher texts.
+//!
+//! ```text
+//! snailcrypt-cli encrypt --lockdate <LOCKDATE> [--hint <HINT>] [--filename <NAME>] [OPTIONS] [FILE]
+//! snailcrypt-cli decrypt [OPTIONS] [FILE]
+//! snailcrypt-cli inspect [FILE]
+//! ```
+//!
+//! The input is read from FILE or from the standard input. The result is written to the standard output. A passphrase
+//! is read from the file given by "--passphrase-file" or from the environment variable SNAILCRYPT_PASSPHRASE.
+
+// Explicit returns are the style of the code base, see lib.rs.
+#![allow(clippy::needless_return)]
+
+use snailcrypt::{
+	client::{
+		Client,
+		ClientEncryptArg,
+	},
+	config::DefaultConfig,
+	factory,
+	format,
+	lockdate::{
+		Lockdate,
+		LockdatePolicy,
+	},
+};
+
+use std::{
+	env,
+	fs,
+	io::{
+		self,
+		Read,
+		Write,
+	},
+	path::Path,
+	process,
+	sync::Arc,
+};
+
+use chrono::{
+	DateTime,
+	Duration,
+	FixedOffset,
+};
+
+const USAGE: &str = "Usage:
+  snailcrypt-cli encrypt --lockdate <LOCKDATE> [--hint <HINT>] [--filename <NAME>] [OPTIONS] [FILE]
+  snailcrypt-cli decrypt [OPTIONS] [FILE]
+  snailcrypt-cli inspect [FILE]
+
+The lockdate is either RFC 3339, e.g. 2030-01-01T12:00:00+01:00 or 2030-01-01T11:00:00Z, a Unix timestamp
+prefixed by @, e.g. @1893499200, a duration from now, e.g. +3d12h, or a day with an optional time and time zone,
+e.g. \"tomorrow 09:00 Europe/Vienna\".
+A file is encrypted together with its name. Use --filename to name binary content read from the standard input.
+
+Options:
+  --api-url <URL>           The key server to use.
+  --passphrase-file <FILE>  Read the passphrase from FILE. SNAILCRYPT_PASSPHRASE is used otherwise.
+  -h, --help                Print this help.";
+
+/// The parsed command line.
+#[derive(Default)]
+struct Arguments {
+	command: String,
+	input: Option<String>,
+	lockdate: Option<String>,
+	hint: String,
+	filename: Option<String>,
+	api_url: Option<String>,
+	passphrase_file: Option<String>,
+}
+
+fn parse_arguments(args: &[String]) -> Result<Arguments, String> {
+	let mut arguments = Arguments::default();
+	let mut iter = args.iter();
+
+	arguments.command = iter.next()
+		.ok_or_else(|| String::from("A command is needed."))?
+		.clone();
+
+	while let Some(arg) = iter.next() {
+		let mut value = |name: &str| iter.next()
+			.cloned()
+			.ok_or_else(|| format!("The option \"{}\" needs a value.", name));
+
+		match arg.as_str() {
+			"--lockdate" => arguments.lockdate = Some(value(arg)?),
+			"--hint" => arguments.hint = value(arg)?,
+			"--filename" => arguments.filename = Some(value(arg)?),
+			"--api-url" => arguments.api_url = Some(value(arg)?),
+			"--passphrase-file" => arguments.passphrase_file = Some(value(arg)?),
+			_ if arg.starts_with("--") => return Err(format!("Unknown option \"{}\".", arg)),
+			_ if arguments.input.is_none() => arguments.input = Some(arg.clone()),
+			_ => return Err(format!("Unexpected argument \"{}\".", arg)),
+		}
+	}
+
+	Ok(arguments)
+}
+
+fn read_input(input: &Option<String>) -> Result<Vec<u8>, String> {
+	let mut data: Vec<u8> = Vec::new();
+
+	match input {
+		Some(path) => data = fs::read(path)
+			.map_err(|error| format!("Unable to read \"{}\": {}", path, error))?,
+		None => {
+			io::stdin()
+				.read_to_end(&mut data)
+				.map_err(|error| format!("Unable to read the standard input: {}", error))?;
+		},
+	}
+
+	Ok(data)
+}
+
+fn read_passphrase(arguments: &Arguments) -> Result<String, String> {
+	if let Some(path) = &arguments.passphrase_file {
+		let passphrase = fs::read_to_string(path)
+			.map_err(|error| format!("Unable to read \"{}\": {}", path, error))?;
+		return Ok(String::from(passphrase.trim_end_matches(['\r', '\n'])));
+	}
+
+	return Ok(env::var("SNAILCRYPT_PASSPHRASE").unwrap_or_default());
+}
+
+fn parse_lockdate(client: &Arc<dyn Client>, lockdate: &str) -> Result<DateTime<FixedOffset>, String> {
+	return Lockdate::parse_with_clock(lockdate, client.get_clock().as_ref())
+		.map(|lockdate| lockdate.get_datetime());
+}
+
+fn create_client(arguments: &Arguments) -> Arc<dyn Client> {
+	let mut config = DefaultConfig::new();
+	if let Some(api_url) = &arguments.api_url {
+		config.set_api_url(api_url);
+	}
+
+	/**************************************************************************
+	 * A lockdate in the past would produce a cipher text anybody can decrypt
+	 */
+	let mut lockdate_policy = LockdatePolicy::new();
+	lockdate_policy.set_min_lead_time(Some(Duration::zero()));
+	config.set_lockdate_policy(Some(lockdate_policy));
+
+	let client_factory = factory::ClientFactory::new(factory::AnalyzerFactory::new().create(),
+													 Arc::new(config));
+	return client_factory.create();
+}
+
+fn encrypt(arguments: &Arguments) -> Result<Vec<u8>, String> {
+	let client = create_client(arguments);
+	let lockdate = parse_lockdate(&client,
+								  arguments.lockdate
+								  .as_deref()
+								  .ok_or_else(|| String::from("The option \"--lockdate\" is needed."))?)?;
+	let data = read_input(&arguments.input)?;
+
+	/**************************************************************************
+	 * Files are embedded using base64 together with their name. Anything else
+	 * must be text.
+	 */
+	let filename = match (&arguments.filename, &arguments.input) {
+		(Some(filename), _) => filename.clone(),
+		(None, Some(path)) => String::from(Path::new(path)
+										   .file_name()
+										   .and_then(|filename| filename.to_str())
+										   .unwrap_or("")),
+		(None, None) => String::from(""),
+	};
+	let plaintext = if filename.is_empty() {
+		String::from_utf8(data)
+			.map_err(|_| String::from("The input is not valid UTF-8. Use \"--filename\" to encrypt binary content."))?
+	} else {
+		base64::encode(data)
+	};
+
+	let cipher = client.encrypt(&ClientEncryptArg {
+		plaintext,
+		lockdate,
+		hint: arguments.hint.clone(),
+		filename,
+		passphrase: read_passphrase(arguments)?,
+		escape_recipients: Vec::new(),
+	})?;
+
+	Ok(format!("{}\n", cipher).into_bytes())
+}
+
+fn decrypt(arguments: &Arguments) -> Result<Vec<u8>, String> {
+	let cipher = String::from_utf8(read_input(&arguments.input)?)
+		.map_err(|_| String::from("The cipher text is not valid UTF-8."))?;
+
+	let result = create_client(arguments)
+		.decrypt_with_passphrase(cipher.trim(), read_passphrase(arguments)?.as_str())
+		.map_err(|failure| failure.error_message)?;
+
+	if result.filename.is_empty() {
+		return Ok(result.plaintext.into_bytes());
+	}
+
+	base64::decode(result.plaintext)
+		.map_err(|error| error.to_string())
+}
+
+fn inspect(arguments: &Arguments) -> Result<Vec<u8>, String> {
+	let cipher = String::from_utf8(read_input(&arguments.input)?)
+		.map_err(|_| String::from("The cipher text is not valid UTF-8."))?;
+
+	let result = format::inspect(cipher.trim())?;
+
+	let mut output = format!("version: {}\nlockdate: {}\n", result.version, result.lockdate.to_rfc3339());
+	if !result.hint.is_empty() {
+		output.push_str(format!("hint: {}\n", result.hint).as_str());
+	}
+	if !result.filename.is_empty() {
+		output.push_str(format!("filename: {}\n", result.filename).as_str());
+	}
+	if result.passphrase_required {
+		output.push_str("passphrase: required\n");
+	}
+	for share in &result.shares {
+		output.push_str(format!("share: {} {}\n", share.api_url, share.lockdate.to_rfc3339()).as_str());
+	}
+	for section in &result.sections {
+		output.push_str(format!("section: {} {}\n", section.lockdate.to_rfc3339(), section.hint).as_str());
+	}
+
+	Ok(output.into_bytes())
+}
+
+fn main() {
+	let args: Vec<String> = env::args().skip(1).collect();
+
+	if args.is_empty() || args.iter().any(|arg| arg == "-h" || arg == "--help") {
+		println!("{}", USAGE);
+		return;
+	}
+
+	let result = parse_arguments(&args)
+		.and_then(|arguments| match arguments.command.as_str() {
+			"encrypt" => encrypt(&arguments),
+			"decrypt" => decrypt(&arguments),
+			"inspect" => inspect(&arguments),
+			command => Err(format!("Unknown command \"{}\".\n\n{}", command, USAGE)),
+		});
+
+	match result {
+		Ok(output) => {
+			if let Err(error) = io::stdout().write_all(&output) {
+				eprintln!("snailcrypt-cli: {}", error);
+				process::exit(1);
+			}
+		},
+		Err(error) => {
+			eprintln!("snailcrypt-cli: {}", error);
+			process::exit(1);
+		},
+	}
+}
@@ -0,0 +1,55 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::client::{
+	ClientEncryptArg,
+	ClientDecryptResultSuccess,
+	ClientDecryptResultFailure,
+	ClientInspectResult,
+};
+
+use async_trait::async_trait;
+
+/// The asynchronous counterpart of `Client`. The requests to the key server do not block the calling thread, so many
+/// of them can run concurrently within a single async runtime.
+#[async_trait]
+pub trait AsyncClient: Send + Sync {
+    /// Encrypt a plain text.
+    async fn encrypt(&self, args: &ClientEncryptArg) -> Result<String, String>;
+
+    /// Decrypt a cipher text.
+    async fn decrypt(&self, ciphertext: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure>;
+
+    /// Decrypt a cipher text which may be protected by a passphrase. Cipher texts without a passphrase are decrypted as if using `decrypt`.
+    async fn decrypt_with_passphrase(&self, ciphertext: &str, passphrase: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure>;
+
+    /// Decrypt a cipher text before its lockdate using the PEM encoded RSA private key of an early-unlock recipient.
+    async fn decrypt_with_escape_key(&self, ciphertext: &str, private_key_pem: &str, passphrase: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure>;
+
+    /// Extract everything which is readable without the key of the lockdate from a cipher text.
+    async fn inspect(&self, ciphertext: &str) -> Result<ClientInspectResult, String>;
+}
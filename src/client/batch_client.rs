@@ -0,0 +1,326 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+	api::KeyServerApi,
+	client::{
+		Client,
+		ClientEncryptArg,
+		ClientDecryptResultSuccess,
+		ClientDecryptResultFailure,
+		ClientDecryptErrorKind,
+	},
+	config::{
+		Config,
+		OverrideConfig,
+	},
+	factory::ClientFactory,
+	format,
+	transport::{
+		Transport,
+		TransportResponse,
+		TransportStats,
+	},
+	util::Analyzer,
+};
+
+use std::{
+	collections::HashMap,
+	panic::{
+		self,
+		AssertUnwindSafe,
+	},
+	sync::{
+		atomic::{
+			AtomicUsize,
+			Ordering,
+		},
+		Arc,
+		Mutex,
+		OnceLock,
+	},
+	thread,
+	time::Duration,
+};
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+    Utc,
+};
+
+/// The response of a request. It is set by the first thread sending the request.
+type SharedResponse = Arc<OnceLock<Result<TransportResponse, String>>>;
+
+/// A transport sending every distinct request only once. Concurrent identical requests wait for the first one and
+/// share its response.
+struct CoalescingTransport {
+	inner: Arc<dyn Transport>,
+	responses: Mutex<HashMap<(String, String), SharedResponse>>,
+}
+
+impl CoalescingTransport {
+	fn new(inner: Arc<dyn Transport>) -> CoalescingTransport {
+		CoalescingTransport {
+			inner,
+			responses: Mutex::new(HashMap::new()),
+		}
+	}
+}
+
+impl Transport for CoalescingTransport {
+	fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<Vec<u8>, String> {
+		self.post_with_status(url, body, connect_timeout, timeout)
+			.map(|response| response.body)
+	}
+
+	fn post_with_status(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<TransportResponse, String> {
+		let response = Arc::clone(self.responses
+			.lock()
+			.unwrap()
+			.entry((String::from(url), String::from(body)))
+			.or_default());
+
+		response
+			.get_or_init(|| self.inner.post_with_status(url, body, connect_timeout, timeout))
+			.clone()
+	}
+
+	fn get(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<Vec<u8>, String> {
+		self.inner.get(url, connect_timeout, timeout)
+	}
+
+	fn get_date(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<DateTime<Utc>, String> {
+		self.inner.get_date(url, connect_timeout, timeout)
+	}
+
+	fn get_stats(&self) -> Option<TransportStats> {
+		self.inner.get_stats()
+	}
+}
+
+/// Run the operation of a single item. A panic, e.g. of a legacy client on a malformed cipher text, only fails this
+/// item instead of the whole batch.
+fn catch_panic<T, F, P>(operation: F, on_panic: P) -> T
+	where F: FnOnce() -> T,
+		  P: FnOnce(String) -> T {
+	panic::catch_unwind(AssertUnwindSafe(operation))
+		.unwrap_or_else(|payload| on_panic(payload
+			.downcast_ref::<String>()
+			.cloned()
+			.or_else(|| payload.downcast_ref::<&str>().map(|message| String::from(*message)))
+			.unwrap_or_else(|| String::from("The item could not be processed."))))
+}
+
+/// This object encrypts and decrypts many items at once. Items sharing a lockdate share the request for its key, so
+/// every key is fetched only once per batch. The items are processed in parallel and every item gets a result of its
+/// own, so a single bad item does not fail the whole batch.
+pub struct BatchClient {
+	analyzer: Arc<dyn Analyzer>,
+	config: Arc<dyn Config>,
+	thread_count: usize,
+}
+
+impl BatchClient {
+	pub fn new(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>) -> BatchClient {
+		BatchClient {
+			analyzer,
+			config,
+			thread_count: thread::available_parallelism()
+				.map(|count| count.get())
+				.unwrap_or(1),
+		}
+	}
+
+	/// Set the number of threads processing the items of a batch. The default is the available parallelism.
+	pub fn set_thread_count(&mut self, thread_count: usize) {
+		self.thread_count = thread_count.max(1);
+	}
+
+	pub fn get_thread_count(&self) -> usize {
+		self.thread_count
+	}
+
+	/// Encrypt several plain texts. The results are in the order of the arguments.
+	pub fn encrypt_batch(&self, args: &[ClientEncryptArg]) -> Vec<Result<String, String>> {
+		let lockdates: Vec<Option<DateTime<FixedOffset>>> = args
+			.iter()
+			.map(|arg| Some(arg.lockdate))
+			.collect();
+
+		self.run(&lockdates, |client, index| catch_panic(|| client.encrypt(&args[index]), Err))
+	}
+
+	/// Decrypt several cipher texts. The results are in the order of the cipher texts.
+	pub fn decrypt_batch<S: AsRef<str> + Sync>(&self, ciphertexts: &[S])
+		-> Vec<Result<ClientDecryptResultSuccess, ClientDecryptResultFailure>> {
+		self.decrypt_batch_with_passphrase(ciphertexts, "")
+	}
+
+	/// Decrypt several cipher texts using a passphrase for protected ones. The results are in the order of the cipher
+	/// texts.
+	pub fn decrypt_batch_with_passphrase<S: AsRef<str> + Sync>(&self, ciphertexts: &[S], passphrase: &str)
+		-> Vec<Result<ClientDecryptResultSuccess, ClientDecryptResultFailure>> {
+		let inspect_results: Vec<Result<DateTime<FixedOffset>, String>> = ciphertexts
+			.iter()
+			.map(|ciphertext| format::inspect(ciphertext.as_ref())
+				 .map(|inspect_result| inspect_result.lockdate))
+			.collect();
+		let lockdates: Vec<Option<DateTime<FixedOffset>>> = inspect_results
+			.iter()
+			.map(|inspect_result| inspect_result.clone().ok())
+			.collect();
+
+		self.run(&lockdates, |client, index| {
+			/******************************************************************
+			 * Report invalid cipher texts without passing them to a client,
+			 * as the legacy clients panic on them
+			 */
+			if let Err(error_message) = &inspect_results[index] {
+				return Err(ClientDecryptResultFailure {
+					error_message: error_message.clone(),
+					hint: String::from(""),
+					filename: String::from(""),
+					kind: ClientDecryptErrorKind::Generic,
+				});
+			}
+
+			catch_panic(|| client.decrypt_with_passphrase(ciphertexts[index].as_ref(), passphrase),
+						|error_message| Err(ClientDecryptResultFailure {
+							error_message,
+							hint: String::from(""),
+							filename: String::from(""),
+							kind: ClientDecryptErrorKind::Generic,
+						}))
+		})
+	}
+
+	/// Process every item using the threads of the batch. The items are grouped by their lockdate, so the first item
+	/// of every lockdate fetches its key while the remaining items of other lockdates keep the threads busy.
+	fn run<T, F>(&self, lockdates: &[Option<DateTime<FixedOffset>>], operation: F) -> Vec<T>
+		where F: Fn(&dyn Client, usize) -> T + Sync,
+			  T: Send {
+		/**********************************************************************
+		 * Without a transport every request fails within the key server API
+		 * anyway, so there is nothing to coalesce
+		 */
+		let config: Arc<dyn Config> = match KeyServerApi::new(Arc::clone(&self.config)).get_transport() {
+			Ok(transport) => Arc::new(OverrideConfig::new_with_transport(Arc::clone(&self.config),
+														   Arc::new(CoalescingTransport::new(transport)))),
+			Err(_) => Arc::clone(&self.config),
+		};
+		let client = ClientFactory::new(Arc::clone(&self.analyzer), config).create();
+
+		/**********************************************************************
+		 * Order the items by their lockdate, interleaving the groups
+		 */
+		let mut groups: Vec<Vec<usize>> = Vec::new();
+		let mut group_indices: HashMap<Option<DateTime<FixedOffset>>, usize> = HashMap::new();
+		for (index, lockdate) in lockdates.iter().enumerate() {
+			let group_index = *group_indices
+				.entry(*lockdate)
+				.or_insert_with(|| {
+					groups.push(Vec::new());
+					groups.len() - 1
+				});
+			groups[group_index].push(index);
+		}
+
+		let mut order: Vec<usize> = Vec::with_capacity(lockdates.len());
+		let longest_group = groups.iter().map(|group| group.len()).max().unwrap_or(0);
+		for position in 0..longest_group {
+			for group in groups.iter() {
+				if let Some(index) = group.get(position) {
+					order.push(*index);
+				}
+			}
+		}
+
+		/**********************************************************************
+		 * A single thread processes the items on the calling thread, e.g. on
+		 * WebAssembly which cannot spawn threads
+		 */
+		if self.thread_count <= 1 {
+			let mut results: Vec<Option<T>> = (0..order.len()).map(|_| None).collect();
+			for index in order {
+				results[index] = Some(operation(client.as_ref(), index));
+			}
+
+			return results
+				.into_iter()
+				.flatten()
+				.collect();
+		}
+
+		/**********************************************************************
+		 * Let the threads take the items one after another
+		 */
+		let next = AtomicUsize::new(0);
+		let mut results: Vec<(usize, T)> = thread::scope(|scope| {
+			let handles: Vec<thread::ScopedJoinHandle<Vec<(usize, T)>>> = (0..self.thread_count.min(order.len()))
+				.map(|_| scope.spawn(|| {
+					let mut thread_results: Vec<(usize, T)> = Vec::new();
+
+					loop {
+						let position = next.fetch_add(1, Ordering::SeqCst);
+						let index = match order.get(position) {
+							Some(index) => *index,
+							None => break,
+						};
+
+						thread_results.push((index, operation(client.as_ref(), index)));
+					}
+
+					thread_results
+				}))
+				.collect();
+
+			handles
+				.into_iter()
+				.flat_map(|handle| handle.join().unwrap())
+				.collect()
+		});
+
+		results.sort_by_key(|(index, _)| *index);
+
+		results
+			.into_iter()
+			.map(|(_, result)| result)
+			.collect()
+	}
+
+	/// Get the analyzer.
+	pub fn get_analyzer(&self) -> &Arc<dyn Analyzer> {
+		&self.analyzer
+	}
+
+	/// Get the configuration.
+	pub fn get_config(&self) -> &Arc<dyn Config> {
+		&self.config
+	}
+}
@@ -22,9 +22,25 @@
   SOFTWARE.
 *******************************************************************************/
 
+use crate::{
+	clock::{
+		self,
+		Clock,
+	},
+	format::{
+		self,
+		ClientInspectResult,
+		ClientVersion,
+	},
+	lockdate::{
+		Lockdate,
+		LockdatePolicyError,
+	},
+};
+
 use std::{
 	fmt,
-	string,
+	sync::Arc,
 };
 
 use chrono::{
@@ -32,20 +48,78 @@ use chrono::{
     FixedOffset,
 };
 
-/// Enumeration for the available client versions. This can be used to identify the client object you are using.
-#[derive(Debug, PartialEq, Eq)]
-pub enum ClientVersion {
-    V1,
-    V2,
-	V3,
+/// Enumeration for the kinds of failures a decryption can end with. This allows distinguishing failures which need a different reaction by the caller.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ClientDecryptErrorKind {
+	/// Any failure without a more specific kind.
+	Generic,
+	/// The cipher text is protected by a passphrase but none has been supplied.
+	PassphraseRequired,
+	/// The supplied passphrase does not match the one used for the encryption.
+	WrongPassphrase,
+	/// The key server has not released the key of the lockdate yet.
+	NotYetReleased,
+	/// The sender has revoked the message on the key server. The cipher text can never be decrypted.
+	Revoked,
+}
+
+/// Enumeration for the states of a message registered on a key server.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ClientMessageStatus {
+	/// The lockdate of the message has not passed yet. The message can still be revoked.
+	Pending,
+	/// The key of the message has been released.
+	Released,
+	/// The message has been revoked and its key will never be released.
+	Revoked,
+}
+
+/// A message registered on a key server. The key server holds a key pair for every message and releases its private key at the lockdate unless the message has been revoked before.
+#[derive(Debug, Clone)]
+pub struct ClientMessageRegistration {
+	pub message_id: String,
+	pub lockdate: DateTime<FixedOffset>,
+	/// The PEM encoded public key of the message.
+	pub public_key: String,
+	/// The secret needed to revoke the message. Keep it with the sender only.
+	pub revocation_token: String,
 }
 
 /// Input parameter structure to encrypt something using a client object.
+#[derive(Clone)]
 pub struct ClientEncryptArg {
 	pub plaintext: String,
 	pub lockdate: DateTime<FixedOffset>,
 	pub hint: String,
 	pub filename: String,
+	/// An optional passphrase. If it is not empty, the cipher text can only be decrypted using both the passphrase and the released key of the lockdate.
+	pub passphrase: String,
+	/// PEM encoded RSA public keys of early-unlock recipients, e.g. the sender's own key or an organizational escrow key. Their private keys are able to decrypt the cipher text before the lockdate.
+	pub escape_recipients: Vec<String>,
+}
+
+impl ClientEncryptArg {
+	/// Create the arguments to encrypt a plain text without hint, filename, passphrase or early-unlock recipients. The lockdate is parsed using `Lockdate`, e.g. `2030-01-01T12:00:00Z`, `+3d12h` or `tomorrow 09:00 Europe/Vienna`. Relative lockdates are resolved against the current local time.
+	pub fn new(plaintext: &str, lockdate: &str) -> Result<ClientEncryptArg, String> {
+		Ok(ClientEncryptArg::new_with_lockdate(plaintext, Lockdate::parse(lockdate)?.get_datetime()))
+	}
+
+	/// Create the arguments like `new`, but resolve relative lockdates against `now`.
+	pub fn new_at(plaintext: &str, lockdate: &str, now: DateTime<FixedOffset>) -> Result<ClientEncryptArg, String> {
+		Ok(ClientEncryptArg::new_with_lockdate(plaintext, Lockdate::parse_at(lockdate, now)?.get_datetime()))
+	}
+
+	/// Create the arguments to encrypt a plain text until an already parsed lockdate.
+	pub fn new_with_lockdate(plaintext: &str, lockdate: DateTime<FixedOffset>) -> ClientEncryptArg {
+		ClientEncryptArg {
+			plaintext: String::from(plaintext),
+			lockdate,
+			hint: String::from(""),
+			filename: String::from(""),
+			passphrase: String::from(""),
+			escape_recipients: Vec::new(),
+		}
+	}
 }
 
 /// Result parameter structure on success after decrypting something using a client object.
@@ -56,10 +130,9 @@ pub struct ClientDecryptResultSuccess {
 }
 
 /// This method will just print the plain text for a decryption result.
-impl string::ToString for ClientDecryptResultSuccess {
-	fn to_string(&self) -> String {
-    	return self.plaintext
-    		.clone();
+impl fmt::Display for ClientDecryptResultSuccess {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.plaintext)
 	}
 }
 
@@ -78,13 +151,13 @@ pub struct ClientDecryptResultFailure {
 	pub error_message: String,
 	pub hint: String,
 	pub filename: String,
+	pub kind: ClientDecryptErrorKind,
 }
 
 /// This method will just print the error message.
-impl string::ToString for ClientDecryptResultFailure {
-	fn to_string(&self) -> String {
-    	return self.error_message
-    		.clone();
+impl fmt::Display for ClientDecryptResultFailure {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.error_message)
 	}
 }
 
@@ -94,23 +167,15 @@ impl fmt::Debug for ClientDecryptResultFailure {
     	f.debug_struct("ClientDecryptResultFailure")
     		.field("error_message", &self.error_message)
     		.field("hint", &self.hint)
+    		.field("kind", &self.kind)
     		.finish()
 	}
 }
 
-/// This method enables stringifying the client version easily.
-impl fmt::Display for ClientVersion {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		match self {
-			ClientVersion::V1 => write!(f, "1"),
-			ClientVersion::V2 => write!(f, "2"),
-			ClientVersion::V3 => write!(f, "3"),
-		}        
-    }
-}
-
 /// This is the main trait of this library. It will provide everything needed to perform an encryption or an decryption using the services available on snailcrypt.com.
-pub trait Client {
+///
+/// Clients are `Send + Sync`, so a single client behind an `Arc` can serve concurrent requests of several threads.
+pub trait Client: Send + Sync {
     /// Encrypt a plain text.
     fn encrypt(&self, args: &ClientEncryptArg) 
     	-> 
@@ -123,6 +188,44 @@ pub trait Client {
     	) 
     	->
     	Result<ClientDecryptResultSuccess, ClientDecryptResultFailure>;
+
+    /// Decrypt a cipher text which may be protected by a passphrase. Cipher texts without a passphrase are decrypted as if using `decrypt`. By default the passphrase is ignored, as the versions before 4 do not support passphrases.
+    fn decrypt_with_passphrase(
+    	&self,
+    	ciphertext: &str,
+    	_passphrase: &str
+    	)
+    	->
+    	Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+    	self.decrypt(ciphertext)
+    }
+
+    /// Decrypt a cipher text before its lockdate using the PEM encoded RSA private key of an early-unlock recipient. A passphrase is still needed if the cipher text is protected by one. By default this fails, as the versions before 4 do not support early-unlock recipients.
+    fn decrypt_with_escape_key(
+    	&self,
+    	_ciphertext: &str,
+    	_private_key_pem: &str,
+    	_passphrase: &str
+    	)
+    	->
+    	Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+    	Err(ClientDecryptResultFailure {
+    		error_message: format!("Client version {} does not support early-unlock recipients.", self.get_client_version()),
+    		hint: String::from(""),
+    		filename: String::from(""),
+    		kind: ClientDecryptErrorKind::Generic,
+    	})
+    }
+
+    /// Extract everything which is readable without the key of the lockdate from a cipher text. By default the version is taken from the cipher text, see `format::inspect`.
+    fn inspect(
+    	&self,
+    	ciphertext: &str
+    	)
+    	->
+    	Result<ClientInspectResult, String> {
+    	format::inspect(ciphertext)
+    }
     
     /// Extract the lockdate from a cipher text.
     fn lockdate_from_snailcrypt_cipher(
@@ -132,6 +235,18 @@ pub trait Client {
     	->
     	Result<DateTime<FixedOffset>, String>;
         
+    /// Check a lockdate against the lockdate policy of the configuration and the limits of the key server before encrypting until it. Returns the lockdate the cipher text will actually be locked until, which may be rounded up to the slot granularity of the key server. Clients without a policy accept every lockdate.
+    fn check_lockdate(&self, lockdate: DateTime<FixedOffset>)
+    	->
+    	Result<DateTime<FixedOffset>, LockdatePolicyError> {
+    	Ok(lockdate)
+    }
+
+    /// Get the clock relative lockdates like `+3d12h` are resolved against, i.e. the clock of the configuration.
+    fn get_clock(&self) -> Arc<dyn Clock> {
+    	clock::default_clock()
+    }
+
     /// Get the supported date time format of this client.
     fn get_datetime_format(&self) -> &str;
     
@@ -0,0 +1,326 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+	api::KeyServerApi,
+	client::{
+		AsyncClient,
+		Client,
+		ClientEncryptArg,
+		ClientDecryptResultSuccess,
+		ClientDecryptResultFailure,
+		ClientInspectResult,
+	},
+	config::{
+		Config,
+		OverrideConfig,
+	},
+	factory::ClientFactory,
+	transport::{
+		self,
+		AsyncTransport,
+		Transport,
+		TransportResponse,
+	},
+	util::Analyzer,
+};
+
+use std::{
+	collections::HashMap,
+	panic,
+	sync::{
+		Arc,
+		Mutex,
+	},
+	time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{
+	DateTime,
+	Utc,
+};
+use futures::future;
+
+/// The kinds of requests to the key server an `ExchangeTransport` records.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum ExchangeMethod {
+	Post,
+	Get,
+	GetDate,
+}
+
+/// A request to the key server recorded by an `ExchangeTransport`.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct ExchangeRequest {
+	method: ExchangeMethod,
+	url: String,
+	body: String,
+	connect_timeout: Option<Duration>,
+	timeout: Option<Duration>,
+}
+
+/// A response of the key server fetched for an `ExchangeTransport`.
+#[derive(Clone)]
+enum ExchangeResponse {
+	Response(TransportResponse),
+	Date(DateTime<Utc>),
+}
+
+/// A transport answering the requests of a synchronous client using the responses fetched asynchronously. Requests
+/// without a response yet are recorded and fail, so they can be fetched before the client runs again.
+struct ExchangeTransport {
+	responses: Mutex<HashMap<ExchangeRequest, Result<ExchangeResponse, String>>>,
+	pending: Mutex<Vec<ExchangeRequest>>,
+}
+
+impl ExchangeTransport {
+	fn new() -> ExchangeTransport {
+		ExchangeTransport {
+			responses: Mutex::new(HashMap::new()),
+			pending: Mutex::new(Vec::new()),
+		}
+	}
+
+	fn take_pending(&self) -> Vec<ExchangeRequest> {
+		let mut pending = self.pending.lock().unwrap();
+		std::mem::take(&mut *pending)
+	}
+
+	fn add_response(&self, request: ExchangeRequest, response: Result<ExchangeResponse, String>) {
+		self.responses.lock().unwrap().insert(request, response);
+	}
+
+	/// Get the fetched response of a request or record the request to be fetched.
+	fn exchange(&self, method: ExchangeMethod, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<ExchangeResponse, String> {
+		let request = ExchangeRequest {
+			method,
+			url: String::from(url),
+			body: String::from(body),
+			connect_timeout,
+			timeout,
+		};
+
+		if let Some(response) = self.responses.lock().unwrap().get(&request) {
+			return response.clone();
+		}
+
+		let mut pending = self.pending.lock().unwrap();
+		if !pending.contains(&request) {
+			pending.push(request);
+		}
+
+		Err(String::from("The response of the key server has not been fetched yet."))
+	}
+
+	/// Get the response of a request sending a body.
+	fn exchange_response(&self, method: ExchangeMethod, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<TransportResponse, String> {
+		match self.exchange(method, url, body, connect_timeout, timeout)? {
+			ExchangeResponse::Response(response) => Ok(response),
+			ExchangeResponse::Date(_) => Err(String::from("The key server returned a date instead of a body.")),
+		}
+	}
+}
+
+impl Transport for ExchangeTransport {
+	fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<Vec<u8>, String> {
+		Ok(self.post_with_status(url, body, connect_timeout, timeout)?.body)
+	}
+
+	fn post_with_status(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<TransportResponse, String> {
+		self.exchange_response(ExchangeMethod::Post, url, body, connect_timeout, timeout)
+	}
+
+	fn get(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<Vec<u8>, String> {
+		Ok(self.exchange_response(ExchangeMethod::Get, url, "", connect_timeout, timeout)?.body)
+	}
+
+	fn get_date(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+		-> Result<DateTime<Utc>, String> {
+		match self.exchange(ExchangeMethod::GetDate, url, "", connect_timeout, timeout)? {
+			ExchangeResponse::Date(date) => Ok(date),
+			ExchangeResponse::Response(_) => Err(String::from("The key server returned a body instead of a date.")),
+		}
+	}
+}
+
+/// Fetch the response of a single request using an asynchronous transport.
+async fn fetch_request(transport: &dyn AsyncTransport, request: &ExchangeRequest) -> Result<ExchangeResponse, String> {
+	let url = request.url.as_str();
+
+	match request.method {
+		ExchangeMethod::Post => transport
+			.post_with_status(url, request.body.as_str(), request.connect_timeout, request.timeout)
+			.await
+			.map(ExchangeResponse::Response),
+		ExchangeMethod::Get => transport
+			.get(url, request.connect_timeout, request.timeout)
+			.await
+			.map(|body| ExchangeResponse::Response(TransportResponse {
+				status: None,
+				body,
+			})),
+		ExchangeMethod::GetDate => transport
+			.get_date(url, request.connect_timeout, request.timeout)
+			.await
+			.map(ExchangeResponse::Date),
+	}
+}
+
+/// The default implementation of an asynchronous client. It supports every version of the synchronous clients and
+/// shares their implementation.
+///
+/// An operation runs the synchronous client without network access. The requests to the key server it needs are
+/// fetched concurrently using the asynchronous transport of the configuration and the operation runs again using their
+/// responses. Every run happens on the blocking thread pool of tokio, so the cryptography does not stall the tasks of
+/// the runtime.
+///
+/// Every further run repeats the work done before the missing responses were needed. An operation needs one run per
+/// round of requests, e.g. an encryption one to fetch the public key and one to encrypt. The key server is discovered
+/// in a round of its own before an operation accessing it runs, as the capabilities decide on the requests of the
+/// operation, e.g. the lockdate a public key is requested for. The cryptography mostly follows the last request, so the repeated runs are cheap, but a decryption of a
+/// passphrase protected cipher text may derive the passphrase key once per run.
+pub struct DefaultAsyncClient {
+	analyzer: Arc<dyn Analyzer>,
+	config: Arc<dyn Config>,
+}
+
+impl DefaultAsyncClient {
+	pub fn new(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>) -> DefaultAsyncClient {
+		DefaultAsyncClient {
+			analyzer,
+			config,
+		}
+	}
+
+	/// Run an operation of a synchronous client until every request to the key server it needs has been answered. The
+	/// metadata of the key server is fetched first if the operation accesses the key server.
+	async fn run<T, F>(&self, discover: bool, operation: F) -> T
+		where F: Fn(&dyn Client) -> T + Send + Sync + 'static,
+			  T: Send + 'static {
+		let exchange = Arc::new(ExchangeTransport::new());
+		let config: Arc<dyn Config> = Arc::new(OverrideConfig::new_with_transport(Arc::clone(&self.config),
+																				  exchange.clone()));
+
+		/**********************************************************************
+		 * Without the metadata the client would fall back on the default
+		 * capabilities and request keys for lockdates which are not rounded
+		 * to the slots of the key server
+		 */
+		if discover {
+			let _ = KeyServerApi::new(Arc::clone(&config)).get_metadata();
+			self.fetch(&exchange, exchange.take_pending()).await;
+		}
+
+		let client = ClientFactory::new(Arc::clone(&self.analyzer), config).create();
+		let operation = Arc::new(operation);
+
+		loop {
+			let round_client = Arc::clone(&client);
+			let round_operation = Arc::clone(&operation);
+			let result = tokio::task::spawn_blocking(move || round_operation(round_client.as_ref()))
+				.await
+				.unwrap_or_else(|error| panic::resume_unwind(error.into_panic()));
+
+			let pending = exchange.take_pending();
+			if pending.is_empty() {
+				return result;
+			}
+			drop(result);
+
+			self.fetch(&exchange, pending).await;
+		}
+	}
+
+	/// Fetch the responses of several requests concurrently.
+	async fn fetch(&self, exchange: &ExchangeTransport, requests: Vec<ExchangeRequest>) {
+		let transport = match self.config.get_async_transport().or_else(transport::default_async_transport) {
+			Some(transport) => transport,
+			None => {
+				for request in requests {
+					exchange.add_response(request, Err(String::from("no asynchronous transport is configured")));
+				}
+				return;
+			},
+		};
+
+		let responses = future::join_all(requests
+			.iter()
+			.map(|request| fetch_request(transport.as_ref(), request)))
+			.await;
+
+		for (request, response) in requests.into_iter().zip(responses) {
+			exchange.add_response(request, response);
+		}
+	}
+
+	/// Get the analyzer.
+	pub fn get_analyzer(&self) -> &Arc<dyn Analyzer> {
+		&self.analyzer
+	}
+
+	/// Get the configuration.
+	pub fn get_config(&self) -> &Arc<dyn Config> {
+		&self.config
+	}
+}
+
+#[async_trait]
+impl AsyncClient for DefaultAsyncClient {
+	async fn encrypt(&self, args: &ClientEncryptArg) -> Result<String, String> {
+		let args = args.clone();
+		self.run(true, move |client| client.encrypt(&args)).await
+	}
+
+	async fn decrypt(&self, ciphertext: &str)
+		-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+		let ciphertext = String::from(ciphertext);
+		self.run(true, move |client| client.decrypt(ciphertext.as_str())).await
+	}
+
+	async fn decrypt_with_passphrase(&self, ciphertext: &str, passphrase: &str)
+		-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+		let ciphertext = String::from(ciphertext);
+		let passphrase = String::from(passphrase);
+		self.run(true, move |client| client.decrypt_with_passphrase(ciphertext.as_str(), passphrase.as_str())).await
+	}
+
+	async fn decrypt_with_escape_key(&self, ciphertext: &str, private_key_pem: &str, passphrase: &str)
+		-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+		let ciphertext = String::from(ciphertext);
+		let private_key_pem = String::from(private_key_pem);
+		let passphrase = String::from(passphrase);
+		self.run(true, move |client| client.decrypt_with_escape_key(ciphertext.as_str(), private_key_pem.as_str(), passphrase.as_str())).await
+	}
+
+	async fn inspect(&self, ciphertext: &str) -> Result<ClientInspectResult, String> {
+		let ciphertext = String::from(ciphertext);
+		self.run(false, move |client| client.inspect(ciphertext.as_str())).await
+	}
+}
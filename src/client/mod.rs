@@ -22,18 +22,43 @@
   SOFTWARE.
 *******************************************************************************/
 
+#[cfg(feature = "async")]
+mod async_client;
+mod batch_client;
+#[allow(clippy::module_inception)]
 mod client;
+#[cfg(feature = "async")]
+mod default_async_client;
 mod v1_client;
 mod v2_client;
 mod v3_client;
+mod v4_client;
+mod v5_client;
+mod v6_client;
 mod version_selector_client;
 
-pub use client::ClientVersion;
+pub use crate::format::ClientVersion;
 pub use client::ClientEncryptArg;
 pub use client::ClientDecryptResultSuccess;
 pub use client::ClientDecryptResultFailure;
+pub use client::ClientDecryptErrorKind;
+pub use client::ClientMessageStatus;
+pub use client::ClientMessageRegistration;
+pub use crate::format::ClientInspectResult;
+pub use crate::format::ClientInspectShare;
 pub use client::Client;
+pub use batch_client::BatchClient;
+#[cfg(feature = "async")]
+pub use async_client::AsyncClient;
+#[cfg(feature = "async")]
+pub use default_async_client::DefaultAsyncClient;
 pub use v1_client::V1Client;
 pub use v2_client::V2Client;
 pub use v3_client::V3Client;
+pub use v4_client::V4Client;
+pub use v5_client::V5Client;
+pub use v5_client::V5EncryptArg;
+pub use v5_client::V5EncryptShareArg;
+pub use v6_client::V6Client;
+pub use v6_client::V6SectionResult;
 pub use version_selector_client::VersionSelectorClient;
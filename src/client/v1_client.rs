@@ -25,227 +25,300 @@
 extern crate chrono;
 
 use crate::{
+	api::{
+		KeyServerApi,
+		ServerCapabilities,
+	},
 	client::{
 		Client,
 		ClientVersion,
 		ClientEncryptArg,
 		ClientDecryptResultSuccess,
 		ClientDecryptResultFailure,
+		ClientDecryptErrorKind,
+		ClientInspectResult,
+	},
+	clock::{
+		self,
+		Clock,
 	},
 	config::Config,
+	crypto::{
+		self,
+		CryptoBackend,
+		PrivateKey,
+		PublicKey,
+	},
+	format,
+	lockdate::LockdatePolicyError,
 	util::Analyzer,
 };
 
 use std::{
-    rc::Rc,
-    io::Read,
+    fs,
+    sync::{
+        Arc,
+        OnceLock,
+    },
+    path::PathBuf,
+    thread,
 };
 
 use chrono::{
     DateTime,
     FixedOffset,
 };
-use curl::easy::Easy;
-use serde_json::Value;
-use openssl::{
-    rsa::{
-        Rsa,
-        Padding,
-    },
-    pkey::{
-        Public,
-        Private,
-    }
-};
 
 const PLAINTEXT_CHUNK_SIZE: i32 = 126;
+/// The minimum number of chunks processed in parallel. Fewer chunks are processed on the calling thread, as starting
+/// threads would take longer than the RSA operations.
+const PARALLEL_CHUNK_THRESHOLD: usize = 64;
+
+/// Process the chunks of a plain or cipher text using up to `thread_count` threads. `chunk_operation` is called with
+/// every input chunk and the output chunk at the same position. As every chunk keeps its position, the output does not
+/// depend on the number of threads.
+fn process_chunks<F>(input: &[u8], input_chunk_size: usize, output: &mut [u8], output_chunk_size: usize, thread_count: usize,
+                     chunk_operation: F)
+    -> Result<(), String>
+    where F: Fn(&[u8], &mut [u8]) -> Result<(), String> + Sync {
+    let process_range = |input_range: &[u8], output_range: &mut [u8]| -> Result<(), String> {
+        for (input_chunk, output_chunk) in input_range
+            .chunks(input_chunk_size)
+            .zip(output_range.chunks_mut(output_chunk_size)) {
+            chunk_operation(input_chunk, output_chunk)?;
+        }
+
+        Ok(())
+    };
+
+    let chunk_count = input.len().div_ceil(input_chunk_size);
+    if thread_count <= 1 || chunk_count < PARALLEL_CHUNK_THRESHOLD {
+        return process_range(input, output);
+    }
+
+    /***************************************************************************
+     * Every thread processes a contiguous range of chunks
+     */
+    let chunks_per_thread = chunk_count.div_ceil(thread_count);
+    let process_range = &process_range;
+
+    thread::scope(|scope| {
+        let handles: Vec<thread::ScopedJoinHandle<Result<(), String>>> = input
+            .chunks(input_chunk_size * chunks_per_thread)
+            .zip(output.chunks_mut(output_chunk_size * chunks_per_thread))
+            .map(|(input_range, output_range)| scope.spawn(move || process_range(input_range, output_range)))
+            .collect();
+
+        handles
+            .into_iter()
+            .try_for_each(|handle| handle
+                 .join()
+                 .unwrap_or_else(|_| Err(String::from("Error: a thread processing the chunks has panicked"))))
+    })
+}
+
+/// Encrypt a plain text chunk by chunk. Every chunk of `PLAINTEXT_CHUNK_SIZE` bytes is encrypted into a block of the
+/// key size.
+fn encrypt_chunks(public_key: &dyn PublicKey, plaintext: &[u8], thread_count: usize) -> Result<Vec<u8>, String> {
+    let ciphertext_chunk_size: usize = public_key.size();
+
+    let mut cipher_vector: Vec<u8> = vec![0; plaintext.len().div_ceil(PLAINTEXT_CHUNK_SIZE as usize) * ciphertext_chunk_size];
+
+    process_chunks(plaintext,
+                   PLAINTEXT_CHUNK_SIZE as usize,
+                   cipher_vector.as_mut_slice(),
+                   ciphertext_chunk_size,
+                   thread_count,
+                   |plaintext_chunk, cipher_chunk| {
+                       let encrypted_chunk = public_key.encrypt(plaintext_chunk)?;
+                       if encrypted_chunk.len() != cipher_chunk.len() {
+                           return Err(String::from("Error: the encrypted chunk does not match the key size"));
+                       }
+
+                       cipher_chunk.copy_from_slice(&encrypted_chunk);
+                       Ok(())
+                   })?;
+
+    Ok(cipher_vector)
+}
+
+/// Decrypt a cipher text chunk by chunk into a buffer of `plaintext_vector_len` bytes. The plain text of every block
+/// starts at a multiple of `PLAINTEXT_CHUNK_SIZE`, the remaining bytes stay zero.
+fn decrypt_chunks(private_key: &dyn PrivateKey, cipher_vector: &[u8], plaintext_vector_len: usize, thread_count: usize)
+    -> Result<Vec<u8>, String> {
+    let mut plaintext_vector: Vec<u8> = vec![0; plaintext_vector_len];
+
+    process_chunks(cipher_vector,
+                   private_key.size(),
+                   plaintext_vector.as_mut_slice(),
+                   PLAINTEXT_CHUNK_SIZE as usize,
+                   thread_count,
+                   |cipher_chunk, plaintext_chunk| {
+                       let decrypted_chunk = private_key.decrypt(cipher_chunk)?;
+                       if decrypted_chunk.len() > plaintext_chunk.len() {
+                           return Err(String::from("Cipher is invalid. A decrypted chunk is too large."));
+                       }
+
+                       plaintext_chunk[.. decrypted_chunk.len()].copy_from_slice(&decrypted_chunk);
+                       Ok(())
+                   })?;
+
+    Ok(plaintext_vector)
+}
 
 /// This object implements the version 1 of snailcrypt strings. The version 1 allows encrypting an arbitary string until a specified date.
 #[allow(unused)]
 pub struct V1Client {
-    analyzer: Rc<dyn Analyzer>,
-    config: Rc<dyn Config>,
+    analyzer: Arc<dyn Analyzer>,
+    config: Arc<dyn Config>,
+    crypto_backend: &'static dyn CryptoBackend,
+    key_server_api: KeyServerApi,
+    server_capabilities: OnceLock<ServerCapabilities>,
 }
 
 impl V1Client {
     #[allow(unused)]
-    pub fn new(analyzer: Rc<dyn Analyzer>, config: Rc<dyn Config>) -> V1Client {
-        return V1Client { 
-            analyzer: analyzer,
-            config: config 
-        };
+    pub fn new(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>) -> V1Client {
+        V1Client::new_with_crypto_backend(analyzer, config, crypto::default_backend())
     }
 
-    fn send_lockdate_request(&self, lockdate: DateTime<FixedOffset>) -> Result<Value, String> {
-        /***********************************************************************
-         * Setup HTTP post input data
-         */
-        let mut input_string: String = String::from("{\"lock_date\":\"");
-        input_string.push_str(lockdate.format(self.get_datetime_format()).to_string().as_str());
-        input_string.push_str("\"}");
-        let mut input_str = input_string.as_bytes();
-
-        /***********************************************************************
-         * Setup HTTP post output data
-         */
-        let mut output_vector: Vec<u8> = Vec::with_capacity(512);
+    /// Create a client using a specific crypto backend instead of the default one.
+    pub(crate) fn new_with_crypto_backend(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>, crypto_backend: &'static dyn CryptoBackend) -> V1Client {
+        V1Client { 
+            analyzer,
+            key_server_api: KeyServerApi::new(Arc::clone(&config)),
+            config,
+            crypto_backend,
+            server_capabilities: OnceLock::new(),
+        }
+    }
 
-        /***********************************************************************
-         * Retrieve the URL
-         */
-        let api_url = self.get_config().get_api_url();
-        let api_url_keys = api_url.to_string() + "/keys";
+    /// Get the path of the cached public key of a lockdate. Returns `None` if the cache is disabled.
+    fn get_cache_path(&self, lockdate: DateTime<FixedOffset>) -> Option<PathBuf> {
+        let cache_dir = self.get_config().get_cache_dir()?;
 
-        /***********************************************************************
-         * Perform HTTP POST which will eventually fill output_vector
-         */
-        {
-            /*******************************************************************
-             * Basic setup of curl
-             */
-            let mut handle = Easy::new();
-            handle.url(&api_url_keys.as_str()).unwrap();
-            handle.post(true).unwrap_or_else(|error| {
-                panic!("Error: {:?}", error);
-            });
-            handle.post_field_size(input_str.len() as u64).unwrap_or_else(|error| {
-                panic!("Error: {:?}", error);
-            });
+        let file_name: String = self.crypto_backend
+            .sha256(&[self.get_config().get_api_url().as_bytes(),
+                      lockdate.timestamp().to_string().as_bytes()])
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
 
-            /*******************************************************************
-             * Set function to send data
-             */
-            let mut transfer = handle.transfer();
-            transfer.read_function(|buffer| {
-                Ok(input_str.read(buffer).unwrap_or(0))
-            }).unwrap_or_else(|error| {
-                panic!("Error: {:?}", error);
-            });
+        Some(cache_dir.join(file_name + ".pem"))
+    }
 
-            /*******************************************************************
-             * Set function to receive data
-             */
-            transfer.write_function(|buffer| {
-                output_vector.extend_from_slice(buffer);
+    /// Get the capabilities of the key server. They are discovered on first use and cached afterwards. As long as the
+    /// key server cannot be reached, the fallback capabilities are used without caching them.
+    pub(crate) fn get_server_capabilities(&self) -> ServerCapabilities {
+        if let Some(server_capabilities) = self.server_capabilities.get() {
+            return server_capabilities.clone();
+        }
 
-                Ok(buffer.len())
-            }).unwrap_or_else(|error| {
-                panic!("Error: {:?}", error);
-            });
+        match ServerCapabilities::discover(self.get_key_server_api()) {
+            Ok(server_capabilities) => self.server_capabilities
+                .get_or_init(|| server_capabilities)
+                .clone(),
+            Err(_) => ServerCapabilities::fallback(),
+        }
+    }
 
-            /*******************************************************************
-             * Perform POST
-             */
-            transfer.perform().unwrap_or_else(|error| {
-                panic!("Error {:?}", error);
-            });
+    /// Check a lockdate against the lockdate policy of the configuration and the limits of the key server. Returns the
+    /// lockdate rounded to the slot granularity of the key server.
+    pub(crate) fn apply_lockdate_policy(&self, lockdate: DateTime<FixedOffset>) -> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+        match self.get_server_capabilities().restrict_policy(self.get_config().get_lockdate_policy()) {
+            Some(lockdate_policy) => lockdate_policy
+                .apply(lockdate, self.get_config()
+                       .get_clock()
+                       .unwrap_or_else(clock::default_clock)
+                       .now()
+                       .fixed_offset()),
+            None => Ok(lockdate),
         }
+    }
 
+    pub(crate) fn get_public_key(&self, lockdate: DateTime<FixedOffset>) -> Result<Box<dyn PublicKey>, String> {
         /***********************************************************************
-         * Prase received JSON
+         * Use the cached public key if available
          */
-        let output_object: Value = serde_json::from_slice(&output_vector).unwrap_or_else(|error| {
-            panic!("Error {:?}", error);
-        });
-                
-        Ok(output_object)
-    }
+        let cache_path = self.get_cache_path(lockdate);
+        if let Some(cache_path) = cache_path.as_ref() {
+            if let Ok(public_key) = fs::read(cache_path)
+                .map_err(|error| error.to_string())
+                .and_then(|pem| self.crypto_backend.public_key_from_pem(&pem)) {
+                return Ok(public_key);
+            }
+        }
 
-    fn get_public_key(&self, lockdate: DateTime<FixedOffset>) -> Result<Rsa<Public>, String> {
         /***********************************************************************
-         * Extract public key attribute
+         * Request the public key
          */
-        let output_object: Value = self.send_lockdate_request(lockdate)
-                                       .unwrap_or_else(|error| {
-                                           panic!("Error {:?}", error);
-                                       });                                      
-        
+        self.get_server_capabilities().check_api_version()?;
+        let public_key_str: String = self.key_server_api
+            .get_public_key_pem(lockdate)
+            .map_err(|error| error.to_string())?;
+
         /***********************************************************************
-         * Error: request ended with an error
+         * Create public key object using the extracted public key
          */
-        if output_object["code"].is_number() {
-			return Err(String
-				::from(output_object["message"]
-					.as_str()
-					.unwrap_or_else(|| {
-						panic!("Error: unable to extract message from response")
-					})));
-		}
-    
+        let public_key: Box<dyn PublicKey> = self.crypto_backend
+            .public_key_from_pem(public_key_str.as_bytes())
+            .map_err(|error| format!("Error: unable to parse the public key of the key server: {}", error))?;
+
         /***********************************************************************
-         * Extract public key attribute
+         * Reject keys the key server does not advertise
          */
-        let public_key_str: String = 
-        	String::from(output_object["public_key"]
-					.as_str()
-					.unwrap_or_else(|| {
-						panic!("Error: unable to extract public key from response")
-					}))
-				.replace("'", "");
+        self.get_server_capabilities().check_key("RSA", public_key.size() * 8)?;
 
         /***********************************************************************
-         * Create public key object using the extracted public key
+         * Cache the public key. A failure only costs another request later on.
          */
-        let public_key: Rsa<Public> = 
-        	Rsa::public_key_from_pem(public_key_str
-				.as_bytes())
-				.unwrap_or_else(|error| {
-            panic!("Error {:?}", error);
-        });
+        if let Some(cache_path) = cache_path.as_ref() {
+            let _ = fs::create_dir_all(cache_path.parent().unwrap_or(cache_path))
+                .and_then(|_| fs::write(cache_path, public_key_str.as_bytes()));
+        }
 
         Ok(public_key)
     }
 
-    fn get_private_key(&self, lockdate: DateTime<FixedOffset>) -> Result<Rsa<Private>, String> {
-        /***********************************************************************
-         * Extract public key attribute
-         */
-        let output_object: Value = self.send_lockdate_request(lockdate)
-                                       .unwrap_or_else(|error| {
-                                           panic!("Error {:?}", error);
-                                       });
-                                       
-        /***********************************************************************
-         * Error: request ended with an error
-         */
-        if output_object["code"].is_string() {			
-			return Err(String
-				::from(output_object["message"]
-					.as_str()
-					.unwrap_or_else(|| {
-						panic!("Error: unable to extract message from response")
-					})));
-		}
-
+    /// Get the private key of a lockdate. If the key server has not released the key yet, the failure is of the kind `ClientDecryptErrorKind::NotYetReleased`.
+    pub(crate) fn get_private_key(&self, lockdate: DateTime<FixedOffset>) -> Result<Box<dyn PrivateKey>, ClientDecryptResultFailure> {
         /***********************************************************************
-         * Extract private key attribute
+         * Request the private key
          */
-		let private_key_string_result: &Value = 
-        	&output_object["private_key"];
-					
-		if !private_key_string_result.is_string() {
-			return Err(String
-				::from("Error: private key has not been yet released"));
-		}
-		
-        let private_key_string: String = 
-        	private_key_string_result
-        		.as_str()
-        		.unwrap()
-		        .replace("'", "");
+        self.get_server_capabilities()
+            .check_api_version()
+            .map_err(|error_message| ClientDecryptResultFailure {
+                error_message,
+                hint: String::from(""),
+                filename: String::from(""),
+                kind: ClientDecryptErrorKind::Generic,
+            })?;
+        let private_key_string: String = self.key_server_api
+            .get_private_key_pem(lockdate)
+            .map_err(|error| ClientDecryptResultFailure {
+                error_message: error.to_string(),
+                hint: String::from(""),
+                filename: String::from(""),
+                kind: ClientDecryptErrorKind::Generic,
+            })?
+            .ok_or_else(|| ClientDecryptResultFailure {
+                error_message: String::from("Error: private key has not been yet released"),
+                hint: String::from(""),
+                filename: String::from(""),
+                kind: ClientDecryptErrorKind::NotYetReleased,
+            })?;
 
         /***********************************************************************
          * Create private key object using the extracted private key
          */
-        let private_key: Rsa<Private> = 
-        	Rsa::private_key_from_pem(private_key_string
-        								.as_str()
-        								.as_bytes())
-        	.unwrap_or_else(|error| {
-            panic!("Error {:?}", error);
-        });
-
-        Ok(private_key)
+        self.crypto_backend
+            .private_key_from_pem(private_key_string.as_bytes())
+            .map_err(|error| ClientDecryptResultFailure {
+                error_message: format!("Error: unable to parse the private key of the key server: {}", error),
+                hint: String::from(""),
+                filename: String::from(""),
+                kind: ClientDecryptErrorKind::Generic,
+            })
     }
 
     fn to_snailcrypt_cipher(&self, ciphertext: &str, lockdate: &str) -> String {
@@ -255,7 +328,7 @@ impl V1Client {
         snailcrypt_cipher.push(':');
         snailcrypt_cipher.push_str(ciphertext);
 
-        return snailcrypt_cipher;
+        snailcrypt_cipher
     }
 
     
@@ -272,12 +345,30 @@ impl V1Client {
         Ok(cipher)
     }
     
-    pub fn get_analyzer(&self) -> &Rc<dyn Analyzer> {
-        return &self.analyzer;
+    pub fn get_analyzer(&self) -> &Arc<dyn Analyzer> {
+        &self.analyzer
     }
     
-    pub fn get_config(&self) -> &Rc<dyn Config> {
-		return &self.config;
+    pub fn get_config(&self) -> &Arc<dyn Config> {
+		&self.config
+	}
+
+    /// Get the typed access to the REST API of the key server.
+    pub(crate) fn get_key_server_api(&self) -> &KeyServerApi {
+		&self.key_server_api
+	}
+
+    pub(crate) fn get_crypto_backend(&self) -> &'static dyn CryptoBackend {
+		self.crypto_backend
+	}
+
+    /// Get the number of threads processing the chunks of large cipher texts.
+    fn get_thread_count(&self) -> usize {
+		self.get_config()
+			.get_thread_count()
+			.unwrap_or_else(|| thread::available_parallelism()
+							.map(|count| count.get())
+							.unwrap_or(1))
 	}
 }
 
@@ -286,58 +377,47 @@ impl Client for V1Client {
     	/***********************************************************************
          * Hinting is not supported
          */
-        if args.hint.len() > 0 {
+        if !args.hint.is_empty() {
         	return Err(String::from("Client version 1 does not support a plaintext hint."));
         }
+
+    	/***********************************************************************
+         * Passphrases are not supported
+         */
+        if !args.passphrase.is_empty() {
+        	return Err(String::from("Client version 1 does not support a passphrase."));
+        }
+
+    	/***********************************************************************
+         * Early-unlock recipients are not supported
+         */
+        if !args.escape_recipients.is_empty() {
+        	return Err(String::from("Client version 1 does not support early-unlock recipients."));
+        }
     
+        /***********************************************************************
+         * Check the lockdate against the policy
+         */
+        let lockdate: DateTime<FixedOffset> = self.apply_lockdate_policy(args.lockdate)
+            .map_err(|error| error.to_string())?;
+
         /***********************************************************************
          * Get the public key for the requested lockdate
          */
-		let public_key_result = self.get_public_key(args.lockdate);
+		let public_key_result = self.get_public_key(lockdate);
 		
-		if public_key_result.is_err() {
-			return Err(public_key_result.unwrap_err());
+		if let Err(error) = public_key_result {
+			return Err(error);
 		}
 		
-        let public_key: Rsa<Public> = public_key_result.unwrap_or_else(|error| {			
+        let public_key: Box<dyn PublicKey> = public_key_result.unwrap_or_else(|error| {			
             panic!("Error: {:?}", error);
         });
 
         /***********************************************************************
-         * Encrypt the plaintext
+         * Encrypt the plaintext. Large plain texts are encrypted in parallel.
          */
-        let ciphertext_chunk_size: i32 = public_key.size() as i32;
-
-        let mut cipher_vector: Vec<u8> = Vec::new();
-        let cipher_vector_len: usize = (((args.plaintext.len() as f64 / PLAINTEXT_CHUNK_SIZE as f64).ceil() as i32) * ciphertext_chunk_size) as usize;
-        cipher_vector.resize(cipher_vector_len, 0);
-
-        let mut i: i32 = 0;
-        while i * PLAINTEXT_CHUNK_SIZE < args.plaintext.len() as i32 {
-            let plaintext_cur_start: usize = (i * PLAINTEXT_CHUNK_SIZE) as usize;
-            let mut plaintext_cur_end: usize = plaintext_cur_start + PLAINTEXT_CHUNK_SIZE as usize;
-            if plaintext_cur_end > args.plaintext.len() {
-                plaintext_cur_end = args.plaintext.len();
-            }
-
-            let plaintext_slice = &
-                (args.plaintext.as_bytes())[plaintext_cur_start .. plaintext_cur_end];
-
-            let cipher_cur_start: usize = (i * ciphertext_chunk_size) as usize;
-            let cipher_cur_end: usize = cipher_cur_start + ciphertext_chunk_size as usize;
-            let cipher_vector_slice = &mut
-                (cipher_vector.as_mut_slice())[cipher_cur_start .. cipher_cur_end];
-
-            public_key
-                .public_encrypt(plaintext_slice,
-                                cipher_vector_slice,
-                                Padding::PKCS1_OAEP)
-                .unwrap_or_else(|error| {
-                    panic!("Error {:?}", error);
-                });
-
-            i += 1;
-        }
+        let cipher_vector: Vec<u8> = encrypt_chunks(public_key.as_ref(), args.plaintext.as_bytes(), self.get_thread_count())?;
 
         /***********************************************************************
          * Encode the cipher_vector to base64
@@ -347,7 +427,7 @@ impl Client for V1Client {
         /***********************************************************************
          * Encode the lockdate
          */
-        let lockdate_string: String = base64::encode(args.lockdate.format(self.get_datetime_format()).to_string());
+        let lockdate_string: String = base64::encode(lockdate.format(self.get_datetime_format()).to_string());
 
         Ok(self.to_snailcrypt_cipher(cipher_string.as_str(), lockdate_string.as_str()))
     }
@@ -358,24 +438,23 @@ impl Client for V1Client {
          * Extract the lockdate from the ciphertext
          */
         let lockdate: DateTime<FixedOffset> = self.lockdate_from_snailcrypt_cipher(ciphertext)
-            .unwrap_or_else(|error| {
-                panic!("Error {:?}", error);
-            });
+            .map_err(|error_message| ClientDecryptResultFailure {
+                error_message,
+                hint: String::from(""),
+                filename: String::from(""),
+                kind: ClientDecryptErrorKind::Generic,
+            })?;
 
         /***********************************************************************
          * Get the private key for the lockdate
          */
 		let private_key_result = self.get_private_key(lockdate);
 		
-		if private_key_result.is_err() {
-			return Err(ClientDecryptResultFailure { 
-				error_message: private_key_result.unwrap_err(), 
-				hint: String::from(""),
-				filename: String::from(""),
-			});
+		if let Err(error) = private_key_result {
+			return Err(error);
 		}		
 
-        let private_key: Rsa<Private> = private_key_result
+        let private_key: Box<dyn PrivateKey> = private_key_result
             .unwrap_or_else(|error| {
                 panic!("Error {:?}", error);
             });
@@ -388,46 +467,26 @@ impl Client for V1Client {
                                             .unwrap_or_else(|error| {
                                                 panic!("Error {:?}", error);
                                             }))
-                             .unwrap_or_else(|error| {
-                                 panic!("Error {:?}", error);
-                             });                             
-
-        let ciphertext_chunk_size: i32 = private_key.size() as i32;
-
-        let mut plaintext_vector: Vec<u8> = Vec::new();
-        let plaintext_vector_len: usize = ciphertext.len();
-        plaintext_vector.resize(plaintext_vector_len, 0);
-
-         let mut i: i32 = 0;
-         let mut plaintext_cur_actual_end: usize = 0;
-         while i * ciphertext_chunk_size < cipher_vector.len() as i32 {
-             let cipher_cur_start: usize = (i * ciphertext_chunk_size) as usize;
-             let cipher_cur_end: usize = cipher_cur_start + ciphertext_chunk_size as usize;
-             let cipher_vector_slice = &
-                 (cipher_vector.as_slice())[cipher_cur_start .. cipher_cur_end];
-                 
-             /*
-			  * A plaintext chunk may have a different size then a ciphertext chunk. Thererfore we
-			  * start at a different position but end at the same place as the ciphertext chunk.
-			  * The consequence of this is that the plaintext_vector may have a smaller length
-			  * at the end as the sum of the ciphertext chunks.
-			  */    
-             let plaintext_cur_start: usize = plaintext_cur_actual_end;
-             let plaintext_cur_end: usize = cipher_cur_end;
-             let plaintext_vector_slice = &mut
-             	(plaintext_vector.as_mut_slice())[plaintext_cur_start .. plaintext_cur_end];
-             	
-			 private_key
-	            .private_decrypt(cipher_vector_slice,
-	                             plaintext_vector_slice,
-	                             Padding::PKCS1_OAEP)
-	            .unwrap_or_else(|error| {
-	                panic!("Error {:?}", error);
-	            });             	
-
-             i += 1;
-             plaintext_cur_actual_end += PLAINTEXT_CHUNK_SIZE as usize;
-         }
+                             .map_err(|error| ClientDecryptResultFailure {
+                                 error_message: error.to_string(),
+                                 hint: String::from(""),
+                                 filename: String::from(""),
+                                 kind: ClientDecryptErrorKind::Generic,
+                             })?;
+
+        /*
+         * A plaintext chunk may have a different size then a ciphertext chunk. Thererfore every plaintext chunk
+         * starts at a multiple of the plaintext chunk size. The consequence of this is that the plaintext_vector may
+         * have a smaller length at the end as the sum of the ciphertext chunks. Large cipher texts are decrypted in
+         * parallel.
+         */
+        let mut plaintext_vector: Vec<u8> = decrypt_chunks(private_key.as_ref(), &cipher_vector, ciphertext.len(), self.get_thread_count())
+            .map_err(|error_message| ClientDecryptResultFailure {
+                error_message,
+                hint: String::from(""),
+                filename: String::from(""),
+                kind: ClientDecryptErrorKind::Generic,
+            })?;
 
         let mut end_pos: usize = plaintext_vector.len();
         for (pos, elem) in plaintext_vector.iter().enumerate() {
@@ -450,40 +509,41 @@ impl Client for V1Client {
 			filename: String::from(""),
 		})
     }
+
+    fn inspect(&self, ciphertext: &str) -> Result<ClientInspectResult, String> {
+    	format::inspect_v1(ciphertext)
+    }
     
     fn lockdate_from_snailcrypt_cipher(&self, ciphertext: &str) -> Result<DateTime<FixedOffset>, String> {
         let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
 
         if cipher_comp_vec.len() != 3 {
-            // Err("Cipher is invalid. It must consist of two components separted by a colon.")
-            panic!("Cipher is invalid. It must consist of 3 components separted by a colon.");
+            return Err(String::from("Cipher is invalid. It must consist of 3 components separted by a colon."));
         }
 
-        let lockdate: DateTime<FixedOffset> = 
-        	DateTime::parse_from_str(
-				String::from_utf8(
-					base64::decode(cipher_comp_vec[1])
-                 	.unwrap_or_else(|error| {
-                    	panic!("Error: {:?}", error);
-                    }))
-				.unwrap_or_else(|error| {
-   					panic!("Error: {:?}", error);
-				})
-				.as_str(),
-				self.get_datetime_format())
-			.unwrap_or_else(|error| {
-            	panic!("Error: {:?}", error);
-			});
-
-        Ok(lockdate)
+        let lockdate_string = format::decode_component(cipher_comp_vec[1])?;
+
+        DateTime::parse_from_str(lockdate_string.as_str(), self.get_datetime_format())
+            .map_err(|error| error.to_string())
+    }
+
+    fn check_lockdate(&self, lockdate: DateTime<FixedOffset>)
+    	-> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+    	self.apply_lockdate_policy(lockdate)
+    }
+
+    fn get_clock(&self) -> Arc<dyn Clock> {
+    	self.get_config()
+    		.get_clock()
+    		.unwrap_or_else(clock::default_clock)
     }
 
     fn get_datetime_format(&self) -> &str {
-        return "%Y-%m-%dT%H:%M:%S%z";
+        format::DATETIME_FORMAT
     }
     
     fn get_client_version(&self) -> ClientVersion {
-		return ClientVersion::V1;
+		ClientVersion::V1
 	}
 }
 
@@ -28,14 +28,20 @@ use crate::{
 		ClientVersion,
 		ClientEncryptArg,
 		ClientDecryptResultSuccess,
-		ClientDecryptResultFailure,		
+		ClientDecryptResultFailure,
+		ClientDecryptErrorKind,
+		ClientInspectResult,
 		V1Client,
 	},
+	clock::Clock,
 	config::Config,
+	crypto::CryptoBackend,
+	format,
+	lockdate::LockdatePolicyError,
 	util::Analyzer,
 };
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use chrono::{
     DateTime,
@@ -50,15 +56,37 @@ pub struct V2Client {
 /// This object implements the version 2 of snailcrypt strings. The version 2 allows encrypting an arbitary string until a specified date. It also allows the inclusion of a hint string which is not encrypted.
 impl V2Client {
     #[allow(unused)]
-    pub fn new(analyzer: Rc<dyn Analyzer>, config: Rc<dyn Config>) -> V2Client {
-        return V2Client { 
+    pub fn new(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>) -> V2Client {
+        V2Client { 
         	v1_client: V1Client::new(analyzer, config) 
-        };
+        }
+    }
+
+    /// Create a client using a specific crypto backend instead of the default one.
+    #[allow(unused)]
+    pub(crate) fn new_with_crypto_backend(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>, crypto_backend: &'static dyn CryptoBackend) -> V2Client {
+        V2Client { 
+        	v1_client: V1Client::new_with_crypto_backend(analyzer, config, crypto_backend) 
+        }
     }
 }
 
 impl Client for V2Client {
     fn encrypt(&self, args: &ClientEncryptArg) -> Result<String, String> {
+		/**********************************************************************
+		 * Passphrases are not supported
+		 */
+		if !args.passphrase.is_empty() {
+			return Err(String::from("Client version 2 does not support a passphrase."));
+		}
+
+		/**********************************************************************
+		 * Early-unlock recipients are not supported
+		 */
+		if !args.escape_recipients.is_empty() {
+			return Err(String::from("Client version 2 does not support early-unlock recipients."));
+		}
+
 		/**********************************************************************
 		 * Use the v1 client to encrypt the string.
 		 */
@@ -67,13 +95,15 @@ impl Client for V2Client {
 	    	lockdate: args.lockdate, 
 	    	hint: String::from(""),
         	filename: String::from(""),
+        	passphrase: String::from(""),
+        	escape_recipients: Vec::new(),
     	});
 
 		/**********************************************************************
 		 * Exit out on error
 		 */
-    	if encrypt_result.is_err() {
-    		return Err(encrypt_result.unwrap_err())
+    	if let Err(error) = encrypt_result {
+    		return Err(error)
     	}
     	
 		/**********************************************************************
@@ -100,9 +130,9 @@ impl Client for V2Client {
 		 */
     	let mut final_ciphertext: String = self.get_client_version()
     		.to_string();
-    	final_ciphertext.push_str(":");
+    	final_ciphertext.push(':');
     	final_ciphertext.push_str(corrected_ciphertext.as_str());
-    	final_ciphertext.push_str(":");
+    	final_ciphertext.push(':');
     	final_ciphertext.push_str(base64::encode(args.hint.as_str()).as_str());
     	
 		Ok(final_ciphertext)
@@ -120,6 +150,7 @@ impl Client for V2Client {
         		error_message: String::from("Cipher is invalid. It must consist of 4 components separted by a colon."),
         		hint: String::from(""),
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
         	});
         }
   		
@@ -127,11 +158,12 @@ impl Client for V2Client {
 		 * Try to decode the BASE64 string of the hint and exit out on error
 		 */
         let hint_base64_result = base64::decode(cipher_comp_vec[3]);
-        if hint_base64_result.is_err() {
+        if let Err(error) = hint_base64_result {
         	return Err(ClientDecryptResultFailure { 
-        		error_message: hint_base64_result.unwrap_err().to_string(), 
+        		error_message: error.to_string(), 
         		hint: String::from(""),
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
         	});
         }        
  		
@@ -140,11 +172,12 @@ impl Client for V2Client {
 		 * exit out on error        
 		 */
         let hint_result = String::from_utf8(hint_base64_result.unwrap());
-        if hint_result.is_err() {
+        if let Err(error) = hint_result {
         	return Err(ClientDecryptResultFailure { 
-        		error_message: hint_result.unwrap_err().to_string(), 
+        		error_message: error.to_string(), 
         		hint: String::from(""),
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
         	});
         }
         
@@ -157,11 +190,13 @@ impl Client for V2Client {
 		let corrected_ciphertext = cipher_comp_vec.join(":");
     
     	let decrypt_result = self.v1_client.decrypt(corrected_ciphertext.as_str());
-    	if decrypt_result.is_err() {
+    	if let Err(error) = decrypt_result {
+    		let decrypt_failure = error;
     		return Err(ClientDecryptResultFailure { 
-        		error_message: decrypt_result.unwrap_err().error_message.clone(), 
+        		error_message: decrypt_failure.error_message, 
         		hint,
         		filename: String::from(""),
+        		kind: decrypt_failure.kind,
         	});
     	}
     	    	
@@ -172,38 +207,37 @@ impl Client for V2Client {
 		})    	    	
     }
     
+    fn inspect(&self, ciphertext: &str) -> Result<ClientInspectResult, String> {
+    	format::inspect_v2(ciphertext)
+    }
+    
     fn lockdate_from_snailcrypt_cipher(&self, ciphertext: &str) -> Result<DateTime<FixedOffset>, String> {
         let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
 
         if cipher_comp_vec.len() != 4 {
-            // Err("Cipher is invalid. It must consist of two components separted by a colon.")
-            panic!("Cipher is invalid. It must consist of 4 components separted by a colon.");
+            return Err(String::from("Cipher is invalid. It must consist of 4 components separted by a colon."));
         }
 
-        let lockdate: DateTime<FixedOffset> = 
-        	DateTime::parse_from_str(
-				String::from_utf8(
-					base64::decode(cipher_comp_vec[1])
-                 	.unwrap_or_else(|error| {
-                    	panic!("Error: {:?}", error);
-                    }))
-				.unwrap_or_else(|error| {
-   					panic!("Error: {:?}", error);
-				})
-				.as_str(),
-				self.get_datetime_format())
-			.unwrap_or_else(|error| {
-            	panic!("Error: {:?}", error);
-			});
-
-        Ok(lockdate)
+        let lockdate_string = format::decode_component(cipher_comp_vec[1])?;
+
+        DateTime::parse_from_str(lockdate_string.as_str(), self.get_datetime_format())
+            .map_err(|error| error.to_string())
     }
         
+    fn check_lockdate(&self, lockdate: DateTime<FixedOffset>)
+    	-> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+    	self.v1_client.check_lockdate(lockdate)
+    }
+
+    fn get_clock(&self) -> Arc<dyn Clock> {
+    	self.v1_client.get_clock()
+    }
+
     fn get_datetime_format(&self) -> &str {
     	self.v1_client.get_datetime_format()
     }
     
 	fn get_client_version(&self) -> ClientVersion {
-		return ClientVersion::V2
+		ClientVersion::V2
 	}
 }
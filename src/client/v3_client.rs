@@ -28,14 +28,19 @@ use crate::{
 		ClientVersion,
 		ClientEncryptArg,
 		ClientDecryptResultSuccess,
-		ClientDecryptResultFailure,		
+		ClientDecryptResultFailure,
+		ClientDecryptErrorKind,
+		ClientInspectResult,
 		V2Client,
 	},
+	clock::Clock,
 	config::Config,
+	format,
+	lockdate::LockdatePolicyError,
 	util::Analyzer,
 };
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 use chrono::{
     DateTime,
@@ -50,15 +55,29 @@ pub struct V3Client {
 /// This object implements the version 3 of snailcrypt strings. The version 3 allows encrypting an arbitary file until a specified date.
 impl V3Client {
     #[allow(unused)]
-    pub fn new(analyzer: Rc<dyn Analyzer>, config: Rc<dyn Config>) -> V3Client {
-        return V3Client { 
+    pub fn new(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>) -> V3Client {
+        V3Client { 
         	v2_client: V2Client::new(analyzer, config) 
-        };
+        }
     }
 }
 
 impl Client for V3Client {
     fn encrypt(&self, args: &ClientEncryptArg) -> Result<String, String> {
+		/**********************************************************************
+		 * Passphrases are not supported
+		 */
+		if !args.passphrase.is_empty() {
+			return Err(String::from("Client version 3 does not support a passphrase."));
+		}
+
+		/**********************************************************************
+		 * Early-unlock recipients are not supported
+		 */
+		if !args.escape_recipients.is_empty() {
+			return Err(String::from("Client version 3 does not support early-unlock recipients."));
+		}
+
 		/**********************************************************************
 		 * Use the v2 client to encrypt the string.
 		 */
@@ -67,21 +86,24 @@ impl Client for V3Client {
 	    	lockdate: args.lockdate, 
 	    	hint: args.hint.clone(),
 	    	filename: String::from(""),
+	    	passphrase: String::from(""),
+	    	escape_recipients: Vec::new(),
     	});
     	
 		/**********************************************************************
 		 * Exit out on error
 		 */
-    	if encrypt_result.is_err() {
-    		return Err(encrypt_result.unwrap_err())
+    	if let Err(error) = encrypt_result {
+    		return Err(error)
     	}
     	
 		/**********************************************************************
-		 * Split the final cipher text from v2 to manipulate it later on
+		 * Split the final cipher text from v2 to manipulate it later on. An
+		 * empty hint is a trailing empty component, which must be kept.
 		 */
     	let encrypted = encrypt_result.unwrap();
     	let mut cipher_comp_vec: Vec<&str> = encrypted
-    		.split_terminator(':')
+    		.split(':')
     		.collect(); 
 		
 		/**********************************************************************
@@ -100,9 +122,9 @@ impl Client for V3Client {
 		 */
     	let mut final_ciphertext: String = self.get_client_version()
     		.to_string();
-    	final_ciphertext.push_str(":");
+    	final_ciphertext.push(':');
     	final_ciphertext.push_str(corrected_ciphertext.as_str());
-    	final_ciphertext.push_str(":");
+    	final_ciphertext.push(':');
     	final_ciphertext.push_str(base64::encode(args.filename.as_str()).as_str());
      	
 		Ok(final_ciphertext)
@@ -120,6 +142,7 @@ impl Client for V3Client {
         		error_message: String::from("Cipher is invalid. It must consist of 5 components separted by a colon."),
         		hint: String::from(""),
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
         	});
         }
 
@@ -127,11 +150,12 @@ impl Client for V3Client {
 		 * Try to decode the BASE64 string of the hint and exit out on error
 		 */
         let hint_base64_result = base64::decode(cipher_comp_vec[3]);
-        if hint_base64_result.is_err() {
+        if let Err(error) = hint_base64_result {
         	return Err(ClientDecryptResultFailure { 
-        		error_message: hint_base64_result.unwrap_err().to_string(), 
+        		error_message: error.to_string(), 
         		hint: String::from(""),
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
         	});
         }        
 
@@ -140,11 +164,12 @@ impl Client for V3Client {
 		 * exit out on error        
 		 */
         let hint_result = String::from_utf8(hint_base64_result.unwrap());
-        if hint_result.is_err() {
+        if let Err(error) = hint_result {
         	return Err(ClientDecryptResultFailure { 
-        		error_message: hint_result.unwrap_err().to_string(), 
+        		error_message: error.to_string(), 
         		hint: String::from(""),
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
         	});
         }
  		
@@ -158,11 +183,12 @@ impl Client for V3Client {
 		 * error
 		 */
         let filename_base64_result = base64::decode(cipher_comp_vec[4]);
-        if filename_base64_result.is_err() {
+        if let Err(error) = filename_base64_result {
         	return Err(ClientDecryptResultFailure { 
-        		error_message: filename_base64_result.unwrap_err().to_string(), 
+        		error_message: error.to_string(), 
         		hint,
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
         	});
         }        
 
@@ -171,11 +197,12 @@ impl Client for V3Client {
 		 * exit out on error        
 		 */
         let filename_result = String::from_utf8(filename_base64_result.unwrap());
-        if filename_result.is_err() {
+        if let Err(error) = filename_result {
         	return Err(ClientDecryptResultFailure { 
-        		error_message: filename_result.unwrap_err().to_string(), 
+        		error_message: error.to_string(), 
         		hint,
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
         	});
         }
 		
@@ -188,11 +215,13 @@ impl Client for V3Client {
 		let corrected_ciphertext = cipher_comp_vec.join(":");
     
     	let decrypt_result = self.v2_client.decrypt(corrected_ciphertext.as_str());
-    	if decrypt_result.is_err() {
+    	if let Err(error) = decrypt_result {
+    		let decrypt_failure = error;
     		return Err(ClientDecryptResultFailure { 
-        		error_message: decrypt_result.unwrap_err().error_message.clone(), 
+        		error_message: decrypt_failure.error_message, 
         		hint,
         		filename,
+        		kind: decrypt_failure.kind,
         	});
     	}
     	    	
@@ -203,38 +232,37 @@ impl Client for V3Client {
 		})    	    	
     }
     
+    fn inspect(&self, ciphertext: &str) -> Result<ClientInspectResult, String> {
+    	format::inspect_v3(ciphertext)
+    }
+    
     fn lockdate_from_snailcrypt_cipher(&self, ciphertext: &str) -> Result<DateTime<FixedOffset>, String> {
         let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
 
         if cipher_comp_vec.len() != 5 {
-            // Err("Cipher is invalid. It must consist of two components separted by a colon.")
-            panic!("Cipher is invalid. It must consist of 4 components separted by a colon.");
+            return Err(String::from("Cipher is invalid. It must consist of 5 components separted by a colon."));
         }
 
-        let lockdate: DateTime<FixedOffset> = 
-        	DateTime::parse_from_str(
-				String::from_utf8(
-					base64::decode(cipher_comp_vec[1])
-                 	.unwrap_or_else(|error| {
-                    	panic!("Error: {:?}", error);
-                    }))
-				.unwrap_or_else(|error| {
-   					panic!("Error: {:?}", error);
-				})
-				.as_str(),
-				self.get_datetime_format())
-			.unwrap_or_else(|error| {
-            	panic!("Error: {:?}", error);
-			});
-
-        Ok(lockdate)
+        let lockdate_string = format::decode_component(cipher_comp_vec[1])?;
+
+        DateTime::parse_from_str(lockdate_string.as_str(), self.get_datetime_format())
+            .map_err(|error| error.to_string())
     }
         
+    fn check_lockdate(&self, lockdate: DateTime<FixedOffset>)
+    	-> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+    	self.v2_client.check_lockdate(lockdate)
+    }
+
+    fn get_clock(&self) -> Arc<dyn Clock> {
+    	self.v2_client.get_clock()
+    }
+
     fn get_datetime_format(&self) -> &str {
     	self.v2_client.get_datetime_format()
     }
     
 	fn get_client_version(&self) -> ClientVersion {
-		return ClientVersion::V3
+		ClientVersion::V3
 	}
 }
@@ -0,0 +1,678 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+	api::{
+		MessageIdRequest,
+		MessageRequest,
+		MessageRevocationRequest,
+	},
+	client::{
+		Client,
+		ClientVersion,
+		ClientEncryptArg,
+		ClientDecryptResultSuccess,
+		ClientDecryptResultFailure,
+		ClientDecryptErrorKind,
+		ClientInspectResult,
+		ClientMessageStatus,
+		ClientMessageRegistration,
+		V1Client,
+	},
+	clock::Clock,
+	config::Config,
+	crypto::{
+		CryptoBackend,
+		PrivateKey,
+		PublicKey,
+		NONCE_SIZE,
+		TAG_SIZE,
+	},
+	format,
+	lockdate::LockdatePolicyError,
+	util::Analyzer,
+};
+
+use std::sync::Arc;
+
+use argon2::{
+    Algorithm,
+    Argon2,
+    Params,
+    Version,
+};
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+use serde_json::{
+    json,
+    Value,
+};
+
+const TIMELOCK_SECRET_SIZE: usize = 32;
+const MESSAGE_SECRET_SIZE: usize = 32;
+const CONTENT_KEY_CONTEXT: &str = "snailcrypt-v4";
+
+const PASSPHRASE_ALGORITHM: &str = "argon2id";
+const PASSPHRASE_SALT_SIZE: usize = 16;
+const PASSPHRASE_KEY_SIZE: usize = 32;
+const PASSPHRASE_MEMORY_COST: u32 = 19456;
+const PASSPHRASE_TIME_COST: u32 = 2;
+const PASSPHRASE_PARALLELISM: u32 = 1;
+/// Upper bound for the memory cost (in KiB) accepted from a cipher text. It prevents a crafted cipher text from exhausting the memory of the decrypting host.
+const PASSPHRASE_MAX_MEMORY_COST: u32 = 1048576;
+/// Upper bound for the number of passes accepted from a cipher text. It prevents a crafted cipher text from occupying the CPU of the decrypting host.
+const PASSPHRASE_MAX_TIME_COST: u32 = 16;
+/// Upper bound for the number of lanes accepted from a cipher text.
+const PASSPHRASE_MAX_PARALLELISM: u32 = 16;
+
+/// The Argon2id parameters of a passphrase. They are recorded in the cipher text to be able to derive the same key during the decryption.
+struct PassphraseParams {
+    memory_cost: u32,
+    time_cost: u32,
+    parallelism: u32,
+    salt: Vec<u8>,
+}
+
+/// Read a cost parameter of a passphrase from the header of a cipher text. Values above the maximum are rejected.
+fn header_cost(value: &Value, name: &str, max: u32) -> Result<u32, String> {
+    u32::try_from(value[name].as_u64().unwrap_or(0))
+        .ok()
+        .filter(|cost| *cost <= max)
+        .ok_or_else(|| format!("Cipher is invalid. The passphrase {} is too high.", name.replace('_', " ")))
+}
+
+impl PassphraseParams {
+    fn generate(crypto_backend: &dyn CryptoBackend) -> Result<PassphraseParams, String> {
+        let mut salt: Vec<u8> = vec![0; PASSPHRASE_SALT_SIZE];
+        crypto_backend.rand_bytes(&mut salt)?;
+
+        Ok(PassphraseParams {
+            memory_cost: PASSPHRASE_MEMORY_COST,
+            time_cost: PASSPHRASE_TIME_COST,
+            parallelism: PASSPHRASE_PARALLELISM,
+            salt,
+        })
+    }
+
+    fn from_header(value: &Value) -> Result<PassphraseParams, String> {
+        if value["algorithm"].as_str() != Some(PASSPHRASE_ALGORITHM) {
+            return Err(String::from("Cipher is invalid. The passphrase algorithm is not supported."));
+        }
+
+        let memory_cost = header_cost(value, "memory_cost", PASSPHRASE_MAX_MEMORY_COST)?;
+        let time_cost = header_cost(value, "time_cost", PASSPHRASE_MAX_TIME_COST)?;
+        let parallelism = header_cost(value, "parallelism", PASSPHRASE_MAX_PARALLELISM)?;
+
+        let salt = base64::decode(value["salt"].as_str().unwrap_or(""))
+            .map_err(|error| error.to_string())?;
+
+        Ok(PassphraseParams {
+            memory_cost,
+            time_cost,
+            parallelism,
+            salt,
+        })
+    }
+
+    fn to_header(&self) -> Value {
+        json!({
+            "algorithm": PASSPHRASE_ALGORITHM,
+            "memory_cost": self.memory_cost,
+            "time_cost": self.time_cost,
+            "parallelism": self.parallelism,
+            "salt": base64::encode(&self.salt),
+        })
+    }
+
+    fn derive_key(&self, passphrase: &str) -> Result<Vec<u8>, String> {
+        let params = Params::new(self.memory_cost,
+                                 self.time_cost,
+                                 self.parallelism,
+                                 Some(PASSPHRASE_KEY_SIZE))
+            .map_err(|error| error.to_string())?;
+
+        let mut passphrase_key: Vec<u8> = vec![0; PASSPHRASE_KEY_SIZE];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &self.salt, &mut passphrase_key)
+            .map_err(|error| error.to_string())?;
+
+        Ok(passphrase_key)
+    }
+}
+
+/// This object implements the version 4 of snailcrypt strings. The version 4 encrypts an arbitary string using AES-256-GCM. The content key is derived from a random secret, which is locked until the specified date using the key of the lockdate. Optionally the content key is also derived from a passphrase using Argon2id, so the decryption requires both the passphrase and the released key. A cipher text may also depend on the key of a revocable message held by the key server.
+#[allow(unused)]
+pub struct V4Client {
+	v1_client: V1Client,
+}
+
+impl V4Client {
+    #[allow(unused)]
+    pub fn new(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>) -> V4Client {
+        V4Client {
+        	v1_client: V1Client::new(analyzer, config)
+        }
+    }
+
+    /// Create a client using a specific crypto backend instead of the default one.
+    #[allow(unused)]
+    pub(crate) fn new_with_crypto_backend(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>, crypto_backend: &'static dyn CryptoBackend) -> V4Client {
+        V4Client {
+        	v1_client: V1Client::new_with_crypto_backend(analyzer, config, crypto_backend)
+        }
+    }
+
+    pub(crate) fn get_crypto_backend(&self) -> &'static dyn CryptoBackend {
+        self.v1_client.get_crypto_backend()
+    }
+
+    /// Register a revocable message on the key server. The returned registration is passed to `encrypt_revocable` and is needed to revoke the message later on. The lockdate of the registration follows the lockdate policy of the configuration.
+    pub fn register_message(&self, lockdate: DateTime<FixedOffset>) -> Result<ClientMessageRegistration, String> {
+        let lockdate = self.v1_client.apply_lockdate_policy(lockdate)
+            .map_err(|error| error.to_string())?;
+        let response = self.v1_client
+            .get_key_server_api()
+            .register_message(&MessageRequest::new(lockdate))
+            .map_err(|error| error.to_string())?;
+
+        Ok(ClientMessageRegistration {
+            message_id: response.message_id,
+            lockdate,
+            public_key: response.public_key,
+            revocation_token: response.revocation_token,
+        })
+    }
+
+    /// Revoke a message before its lockdate. The key server will never release the key of the message, so no cipher text of the message can be decrypted anymore.
+    pub fn revoke_message(&self, registration: &ClientMessageRegistration) -> Result<(), String> {
+        self.v1_client
+            .get_key_server_api()
+            .revoke_message(&MessageRevocationRequest {
+                message_id: registration.message_id.clone(),
+                revocation_token: registration.revocation_token.clone(),
+            })
+            .map_err(|error| error.to_string())
+    }
+
+    /// Get the status of a message from the key server.
+    pub fn get_message_status(&self, message_id: &str) -> Result<ClientMessageStatus, String> {
+        let response = self.v1_client
+            .get_key_server_api()
+            .get_message_status(&MessageIdRequest { message_id: String::from(message_id) })
+            .map_err(|error| error.to_string())?;
+
+        message_status_from_str(response.status.as_str())
+    }
+
+    /// Encrypt a plain text which additionally depends on the key of a registered message. Revoking the message before the lockdate makes the cipher text undecryptable.
+    pub fn encrypt_revocable(&self, args: &ClientEncryptArg, registration: &ClientMessageRegistration) -> Result<String, String> {
+        if self.v1_client.apply_lockdate_policy(args.lockdate).map_err(|error| error.to_string())? != registration.lockdate {
+            return Err(String::from("The lockdate does not match the lockdate of the message registration."));
+        }
+
+        if !args.escape_recipients.is_empty() {
+            return Err(String::from("Early-unlock recipients cannot be combined with a revocable message."));
+        }
+
+        self.encrypt_using(args, Some(registration), None)
+    }
+
+    /// Get the private key of a message. The failure is of the kind `ClientDecryptErrorKind::Revoked` if the message has been revoked.
+    fn get_message_private_key(&self, message_id: &str) -> Result<Box<dyn PrivateKey>, ClientDecryptResultFailure> {
+        let response = self.v1_client
+            .get_key_server_api()
+            .get_message_key(&MessageIdRequest { message_id: String::from(message_id) })
+            .map_err(|error| decrypt_failure(error.to_string(), "", "", ClientDecryptErrorKind::Generic))?;
+
+        let status = message_status_from_str(response.status.as_str())
+            .map_err(|error| decrypt_failure(error, "", "", ClientDecryptErrorKind::Generic))?;
+
+        match (status, response.private_key.as_deref()) {
+            (ClientMessageStatus::Revoked, _) =>
+                Err(decrypt_failure(String::from("The message has been revoked by its sender."),
+                                    "",
+                                    "",
+                                    ClientDecryptErrorKind::Revoked)),
+            (_, Some(private_key_string)) =>
+                self.get_crypto_backend().private_key_from_pem(private_key_string.as_bytes())
+                    .map_err(|error| decrypt_failure(error, "", "", ClientDecryptErrorKind::Generic)),
+            (_, None) =>
+                Err(decrypt_failure(String::from("Error: private key of the message has not been yet released"),
+                                    "",
+                                    "",
+                                    ClientDecryptErrorKind::NotYetReleased)),
+        }
+    }
+
+    fn derive_content_key(&self, timelock_secret: &[u8], passphrase_key: Option<&[u8]>, message_secret: Option<&[u8]>) -> [u8; 32] {
+        let mut parts: Vec<&[u8]> = vec![CONTENT_KEY_CONTEXT.as_bytes(), timelock_secret];
+        if let Some(passphrase_key) = passphrase_key {
+            parts.push(passphrase_key);
+        }
+        if let Some(message_secret) = message_secret {
+            parts.push(message_secret);
+        }
+
+        self.get_crypto_backend().sha256(&parts)
+    }
+
+    /// The additional authenticated data binds every unencrypted component to the encrypted payload. A binding ties the cipher text to its surrounding container, e.g. the section list of a version 6 cipher text.
+    fn to_additional_data(&self, lockdate: &str, hint: &str, filename: &str, header: &str, binding: Option<&str>) -> String {
+        let additional_data = format!("{}:{}:{}:{}:{}",
+                                      self.get_client_version(),
+                                      lockdate,
+                                      hint,
+                                      filename,
+                                      header);
+
+        match binding {
+            Some(binding) => format!("{}:{}", additional_data, binding),
+            None => additional_data,
+        }
+    }
+
+    /// Encrypt a plain text which is bound to a container. It can only be decrypted using `decrypt_bound` with the same binding.
+    pub(crate) fn encrypt_bound(&self, args: &ClientEncryptArg, binding: &str) -> Result<String, String> {
+        self.encrypt_using(args, None, Some(binding))
+    }
+
+    /// Decrypt a cipher text which has been encrypted using `encrypt_bound`.
+    pub(crate) fn decrypt_bound(&self, ciphertext: &str, passphrase: &str, escape_key_pem: Option<&str>, binding: &str)
+        -> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+        self.decrypt_using(ciphertext, passphrase, escape_key_pem, Some(binding))
+    }
+
+    /// Encrypt a plain text. The content key additionally depends on the key of a message if a registration is supplied.
+    fn encrypt_using(&self, args: &ClientEncryptArg, registration: Option<&ClientMessageRegistration>, binding: Option<&str>) -> Result<String, String> {
+		/**********************************************************************
+		 * Get the public key for the requested lockdate
+		 */
+		let lockdate = self.v1_client.apply_lockdate_policy(args.lockdate)
+			.map_err(|error| error.to_string())?;
+		let public_key = self.v1_client.get_public_key(lockdate)?;
+
+		/**********************************************************************
+		 * Lock a random secret using the public key of the lockdate
+		 */
+		let mut timelock_secret: Vec<u8> = vec![0; TIMELOCK_SECRET_SIZE];
+		self.get_crypto_backend().rand_bytes(&mut timelock_secret)?;
+
+		let locked_secret = public_key.encrypt(&timelock_secret)?;
+
+		/**********************************************************************
+		 * Derive the passphrase key if a passphrase is requested
+		 */
+		let mut header = json!({
+			"key": base64::encode(&locked_secret),
+		});
+
+		/**********************************************************************
+		 * Also lock the secret for every early-unlock recipient. They are
+		 * able to unlock the secret before the lockdate.
+		 */
+		if !args.escape_recipients.is_empty() {
+			let mut escape_headers: Vec<Value> = Vec::new();
+			for escape_recipient in args.escape_recipients.iter() {
+				let escape_key = public_key_from_pem(self.get_crypto_backend(), escape_recipient.as_str())?;
+
+				let escape_secret = escape_key.encrypt(&timelock_secret)?;
+
+				escape_headers.push(json!({
+					"fingerprint": public_key_fingerprint(self.get_crypto_backend(), &escape_key.to_der()?),
+					"key": base64::encode(&escape_secret),
+				}));
+			}
+			header["escape"] = Value::from(escape_headers);
+		}
+
+		let mut passphrase_key: Option<Vec<u8>> = None;
+		if !args.passphrase.is_empty() {
+			let passphrase_params = PassphraseParams::generate(self.get_crypto_backend())?;
+			passphrase_key = Some(passphrase_params.derive_key(args.passphrase.as_str())?);
+			header["passphrase"] = passphrase_params.to_header();
+		}
+
+		/**********************************************************************
+		 * Lock another random secret using the public key of the message.
+		 * The key server never releases its private key once the message
+		 * has been revoked.
+		 */
+		let mut message_secret: Option<Vec<u8>> = None;
+		if let Some(registration) = registration {
+			let message_key = public_key_from_pem(self.get_crypto_backend(), registration.public_key.as_str())?;
+
+			let mut unlocked_message_secret: Vec<u8> = vec![0; MESSAGE_SECRET_SIZE];
+			self.get_crypto_backend().rand_bytes(&mut unlocked_message_secret)?;
+
+			let locked_message_secret = message_key.encrypt(&unlocked_message_secret)?;
+
+			header["message"] = json!({
+				"id": registration.message_id,
+				"key": base64::encode(&locked_message_secret),
+			});
+			message_secret = Some(unlocked_message_secret);
+		}
+
+		let content_key = self.derive_content_key(&timelock_secret, passphrase_key.as_deref(), message_secret.as_deref());
+
+		/**********************************************************************
+		 * Encode all unencrypted components
+		 */
+		let nonce = generate_nonce(self.get_crypto_backend())?;
+		header["nonce"] = Value::from(base64::encode(&nonce));
+
+		let lockdate_string = base64::encode(lockdate.format(self.get_datetime_format()).to_string());
+		let hint_string = base64::encode(args.hint.as_str());
+		let filename_string = base64::encode(args.filename.as_str());
+		let header_string = base64::encode(header.to_string());
+
+		/**********************************************************************
+		 * Encrypt the plaintext using the content key
+		 */
+		let additional_data = self.to_additional_data(lockdate_string.as_str(),
+													  hint_string.as_str(),
+													  filename_string.as_str(),
+													  header_string.as_str(),
+													  binding);
+		let payload = seal_payload(self.get_crypto_backend(),
+								   &content_key,
+								   &nonce,
+								   additional_data.as_bytes(),
+								   args.plaintext.as_bytes())?;
+
+		/**********************************************************************
+		 * Build up the cipher text for v4
+		 */
+		Ok(format!("{}:{}:{}:{}:{}:{}",
+				   self.get_client_version(),
+				   lockdate_string,
+				   base64::encode(&payload),
+				   hint_string,
+				   filename_string,
+				   header_string))
+    }
+
+    /// Decrypt a cipher text. The secret is unlocked using the private key of the lockdate, or using the private key of an early-unlock recipient if one is supplied.
+    fn decrypt_using(&self, ciphertext: &str, passphrase: &str, escape_key_pem: Option<&str>, binding: Option<&str>)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+		/**********************************************************************
+		 * Extract the unencrypted components
+		 */
+		let inspect_result = self.inspect(ciphertext)
+			.map_err(|error| decrypt_failure(error, "", "", ClientDecryptErrorKind::Generic))?;
+		let hint = inspect_result.hint.as_str();
+		let filename = inspect_result.filename.as_str();
+
+		if inspect_result.passphrase_required && passphrase.is_empty() {
+			return Err(decrypt_failure(String::from("The cipher text is protected by a passphrase."),
+									   hint,
+									   filename,
+									   ClientDecryptErrorKind::PassphraseRequired));
+		}
+
+		let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
+		let header = format::decode_header(cipher_comp_vec[5])
+			.map_err(|error| decrypt_failure(error, hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		/**********************************************************************
+		 * Unlock the secret of a revocable message first, so a revoked
+		 * message is reported as such even before its lockdate
+		 */
+		let mut message_secret: Option<Vec<u8>> = None;
+		if header["message"].is_object() {
+			let message_private_key = self.get_message_private_key(header["message"]["id"].as_str().unwrap_or(""))
+				.map_err(|failure| decrypt_failure(failure.error_message, hint, filename, failure.kind))?;
+			let locked_message_secret = base64::decode(header["message"]["key"].as_str().unwrap_or(""))
+				.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+
+			let unlocked_message_secret = message_private_key
+				.decrypt(&locked_message_secret)
+				.map_err(|error| decrypt_failure(error, hint, filename, ClientDecryptErrorKind::Generic))?;
+			message_secret = Some(unlocked_message_secret);
+		}
+
+		/**********************************************************************
+		 * Unlock the secret using the private key of the lockdate or the
+		 * private key of an early-unlock recipient
+		 */
+		let (private_key, locked_secret_string) = match escape_key_pem {
+			None => {
+				let private_key = self.v1_client.get_private_key(inspect_result.lockdate)
+					.map_err(|failure| decrypt_failure(failure.error_message, hint, filename, failure.kind))?;
+
+				(private_key, header["key"].as_str().unwrap_or(""))
+			},
+			Some(escape_key_pem) => {
+				let private_key = self.get_crypto_backend().private_key_from_pem(escape_key_pem.as_bytes())
+					.map_err(|error| decrypt_failure(format!("Unable to parse the private key: {}", error),
+													 hint,
+													 filename,
+													 ClientDecryptErrorKind::Generic))?;
+				let fingerprint = private_key.public_key_to_der()
+					.map(|der| public_key_fingerprint(self.get_crypto_backend(), &der))
+					.map_err(|error| decrypt_failure(error, hint, filename, ClientDecryptErrorKind::Generic))?;
+
+				let escape_header = header["escape"]
+					.as_array()
+					.and_then(|escape_headers| escape_headers
+							  .iter()
+							  .find(|escape_header| escape_header["fingerprint"].as_str() == Some(fingerprint.as_str())))
+					.ok_or_else(|| decrypt_failure(String::from("The key is not an early-unlock recipient of the cipher text."),
+												   hint,
+												   filename,
+												   ClientDecryptErrorKind::Generic))?;
+
+				(private_key, escape_header["key"].as_str().unwrap_or(""))
+			},
+		};
+
+		let locked_secret = base64::decode(locked_secret_string)
+			.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		let timelock_secret = private_key
+			.decrypt(&locked_secret)
+			.map_err(|error| decrypt_failure(error, hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		/**********************************************************************
+		 * Derive the passphrase key. There is deliberately no verifier for
+		 * the passphrase within the cipher text, as it would allow guessing
+		 * the passphrase before the lockdate.
+		 */
+		let mut passphrase_key: Option<Vec<u8>> = None;
+		if inspect_result.passphrase_required {
+			let passphrase_params = PassphraseParams::from_header(&header["passphrase"])
+				.map_err(|error| decrypt_failure(error, hint, filename, ClientDecryptErrorKind::Generic))?;
+			passphrase_key = Some(passphrase_params.derive_key(passphrase)
+				.map_err(|error| decrypt_failure(error, hint, filename, ClientDecryptErrorKind::Generic))?);
+		}
+
+		let content_key = self.derive_content_key(&timelock_secret, passphrase_key.as_deref(), message_secret.as_deref());
+
+		/**********************************************************************
+		 * Decrypt the payload using the content key
+		 */
+		let nonce = base64::decode(header["nonce"].as_str().unwrap_or(""))
+			.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+		let payload = base64::decode(cipher_comp_vec[2])
+			.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		if nonce.len() != NONCE_SIZE || payload.len() < TAG_SIZE {
+			return Err(decrypt_failure(String::from("Cipher is invalid. The payload is malformed."),
+									   hint,
+									   filename,
+									   ClientDecryptErrorKind::Generic));
+		}
+
+		let additional_data = self.to_additional_data(cipher_comp_vec[1],
+													  cipher_comp_vec[3],
+													  cipher_comp_vec[4],
+													  cipher_comp_vec[5],
+													  binding);
+		let plaintext_bytes = open_payload(self.get_crypto_backend(),
+										   &content_key,
+										   &nonce,
+										   additional_data.as_bytes(),
+										   &payload)
+			.ok_or_else(|| {
+				if inspect_result.passphrase_required {
+					decrypt_failure(String::from("The passphrase is wrong."),
+									hint,
+									filename,
+									ClientDecryptErrorKind::WrongPassphrase)
+				} else {
+					decrypt_failure(String::from("Cipher is invalid. The payload failed the integrity check."),
+									hint,
+									filename,
+									ClientDecryptErrorKind::Generic)
+				}
+			})?;
+
+		let plaintext = String::from_utf8(plaintext_bytes)
+			.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		Ok(ClientDecryptResultSuccess {
+			plaintext,
+			hint: String::from(hint),
+			filename: String::from(filename),
+		})
+    }
+}
+
+/// Build a failure of the decryption.
+pub(crate) fn decrypt_failure(error_message: String, hint: &str, filename: &str, kind: ClientDecryptErrorKind)
+    -> ClientDecryptResultFailure {
+    ClientDecryptResultFailure {
+        error_message,
+        hint: String::from(hint),
+        filename: String::from(filename),
+        kind,
+    }
+}
+
+/// Parse the status of a message as reported by the key server.
+fn message_status_from_str(status: &str) -> Result<ClientMessageStatus, String> {
+    match status {
+        "pending" => Ok(ClientMessageStatus::Pending),
+        "released" => Ok(ClientMessageStatus::Released),
+        "revoked" => Ok(ClientMessageStatus::Revoked),
+        _ => Err(format!("Error: unknown message status: {}", status)),
+    }
+}
+
+/// Get the fingerprint of a public key. It is the hex encoded SHA-256 digest of the DER encoded public key.
+fn public_key_fingerprint(crypto_backend: &dyn CryptoBackend, der: &[u8]) -> String {
+    crypto_backend
+        .sha256(&[der])
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+/// Parse a PEM encoded RSA public key. Both the SubjectPublicKeyInfo and the PKCS#1 format are accepted.
+fn public_key_from_pem(crypto_backend: &dyn CryptoBackend, pem: &str) -> Result<Box<dyn PublicKey>, String> {
+    crypto_backend
+        .public_key_from_pem(pem.as_bytes())
+        .map_err(|error| format!("Unable to parse the public key: {}", error))
+}
+
+/// Generate a random nonce for `seal_payload`.
+pub(crate) fn generate_nonce(crypto_backend: &dyn CryptoBackend) -> Result<Vec<u8>, String> {
+    let mut nonce: Vec<u8> = vec![0; NONCE_SIZE];
+    crypto_backend.rand_bytes(&mut nonce)?;
+
+    Ok(nonce)
+}
+
+/// Encrypt a payload using AES-256-GCM. Returns the encrypted payload followed by its tag.
+pub(crate) fn seal_payload(crypto_backend: &dyn CryptoBackend, content_key: &[u8], nonce: &[u8], additional_data: &[u8], plaintext: &[u8])
+    -> Result<Vec<u8>, String> {
+    crypto_backend.encrypt_aes_256_gcm(content_key, nonce, additional_data, plaintext)
+}
+
+/// Decrypt a payload produced by `seal_payload`. Returns `None` if the payload is malformed or fails the integrity check.
+pub(crate) fn open_payload(crypto_backend: &dyn CryptoBackend, content_key: &[u8], nonce: &[u8], additional_data: &[u8], payload: &[u8])
+    -> Option<Vec<u8>> {
+    if nonce.len() != NONCE_SIZE || payload.len() < TAG_SIZE {
+        return None;
+    }
+
+    crypto_backend.decrypt_aes_256_gcm(content_key, nonce, additional_data, payload)
+}
+
+impl Client for V4Client {
+    fn encrypt(&self, args: &ClientEncryptArg) -> Result<String, String> {
+    	self.encrypt_using(args, None, None)
+    }
+
+    fn decrypt(&self, ciphertext: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+    	self.decrypt_with_passphrase(ciphertext, "")
+    }
+
+    fn decrypt_with_passphrase(&self, ciphertext: &str, passphrase: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+    	self.decrypt_using(ciphertext, passphrase, None, None)
+    }
+
+    fn decrypt_with_escape_key(&self, ciphertext: &str, private_key_pem: &str, passphrase: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+    	self.decrypt_using(ciphertext, passphrase, Some(private_key_pem), None)
+    }
+
+    fn inspect(&self, ciphertext: &str) -> Result<ClientInspectResult, String> {
+    	format::inspect_v4(ciphertext)
+    }
+
+    fn lockdate_from_snailcrypt_cipher(&self, ciphertext: &str) -> Result<DateTime<FixedOffset>, String> {
+        let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
+
+        if cipher_comp_vec.len() != 6 {
+            return Err(String::from("Cipher is invalid. It must consist of 6 components separted by a colon."));
+        }
+
+        let lockdate_string = format::decode_component(cipher_comp_vec[1])?;
+
+        DateTime::parse_from_str(lockdate_string.as_str(), self.get_datetime_format())
+            .map_err(|error| error.to_string())
+    }
+
+    fn check_lockdate(&self, lockdate: DateTime<FixedOffset>)
+    	-> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+    	self.v1_client.check_lockdate(lockdate)
+    }
+
+    fn get_clock(&self) -> Arc<dyn Clock> {
+    	self.v1_client.get_clock()
+    }
+
+    fn get_datetime_format(&self) -> &str {
+    	self.v1_client.get_datetime_format()
+    }
+
+	fn get_client_version(&self) -> ClientVersion {
+		ClientVersion::V4
+	}
+}
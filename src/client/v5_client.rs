@@ -0,0 +1,458 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+	client::{
+		Client,
+		ClientVersion,
+		ClientEncryptArg,
+		ClientDecryptResultSuccess,
+		ClientDecryptResultFailure,
+		ClientDecryptErrorKind,
+		ClientInspectResult,
+		V1Client,
+		v4_client::{
+			decrypt_failure,
+			generate_nonce,
+			open_payload,
+			seal_payload,
+		},
+	},
+	config::{
+		Config,
+		OverrideConfig,
+	},
+	crypto::{
+		self,
+		shamir,
+		CryptoBackend,
+	},
+	format,
+	clock::{
+		self,
+		Clock,
+	},
+	lockdate::LockdatePolicyError,
+	util::Analyzer,
+};
+
+use std::sync::Arc;
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+use serde_json::{
+    json,
+    Value,
+};
+
+const CONTENT_KEY_SIZE: usize = 32;
+
+/// Input parameter structure for a single share of a threshold time lock.
+pub struct V5EncryptShareArg {
+	/// The configuration of the key server locking the share.
+	pub config: Arc<dyn Config>,
+	pub lockdate: DateTime<FixedOffset>,
+}
+
+/// Input parameter structure to encrypt something using a threshold time lock.
+pub struct V5EncryptArg {
+	pub plaintext: String,
+	pub hint: String,
+	pub filename: String,
+	/// The number of released shares needed to decrypt the cipher text.
+	pub threshold: u8,
+	pub shares: Vec<V5EncryptShareArg>,
+}
+
+/// This object implements the version 5 of snailcrypt strings. The version 5 encrypts an arbitary string using a threshold time lock. The content key is split into n shares using Shamir's secret sharing and every share is locked on its own key server until its own lockdate. Any k released shares recover the plain text, so no single key server is able to release the plain text early or to lose it.
+///
+/// The lockdate of a version 5 cipher text is the earliest date at which enough shares are released.
+#[allow(unused)]
+pub struct V5Client {
+	analyzer: Arc<dyn Analyzer>,
+	configs: Vec<Arc<dyn Config>>,
+	threshold: u8,
+	crypto_backend: &'static dyn CryptoBackend,
+}
+
+impl V5Client {
+    /// Create a client locking one share on every configured key server. `encrypt` requires `threshold` of them to release their key.
+    #[allow(unused)]
+    pub fn new(analyzer: Arc<dyn Analyzer>, configs: Vec<Arc<dyn Config>>, threshold: u8) -> V5Client {
+        V5Client {
+        	analyzer,
+        	configs,
+        	threshold,
+        	crypto_backend: crypto::default_backend(),
+        }
+    }
+
+    /// Encrypt a plain text using an individual key server and lockdate per share.
+    pub fn encrypt_threshold(&self, args: &V5EncryptArg) -> Result<String, String> {
+		/**********************************************************************
+		 * Verify the share layout
+		 */
+		if args.shares.is_empty() || args.shares.len() > u8::MAX as usize {
+			return Err(format!("A threshold time lock needs between 1 and {} shares.", u8::MAX));
+		}
+
+		if args.threshold == 0 || args.threshold as usize > args.shares.len() {
+			return Err(format!("The threshold must be between 1 and the number of shares ({}).", args.shares.len()));
+		}
+
+		/**********************************************************************
+		 * Split a random content key into the shares
+		 */
+		let mut content_key: Vec<u8> = vec![0; CONTENT_KEY_SIZE];
+		self.crypto_backend.rand_bytes(&mut content_key)?;
+
+		let shares: Vec<Vec<u8>> = shamir::split(self.crypto_backend,
+												 &content_key,
+												 args.threshold,
+												 args.shares.len() as u8)?;
+
+		/**********************************************************************
+		 * Lock every share using the public key of its key server and lockdate
+		 */
+		let mut share_headers: Vec<Value> = Vec::new();
+		let mut lockdates: Vec<DateTime<FixedOffset>> = Vec::new();
+		for (share_arg, share) in args.shares.iter().zip(shares.iter()) {
+			let v1_client = V1Client::new_with_crypto_backend(Arc::clone(&self.analyzer), Arc::clone(&share_arg.config), self.crypto_backend);
+			let share_lockdate = v1_client.apply_lockdate_policy(share_arg.lockdate)
+				.map_err(|error| error.to_string())?;
+			let public_key = v1_client.get_public_key(share_lockdate)?;
+
+			let locked_share = public_key.encrypt(share)?;
+
+			share_headers.push(json!({
+				"api_url": share_arg.config.get_api_url(),
+				"lockdate": share_lockdate.format(self.get_datetime_format()).to_string(),
+				"key": base64::encode(&locked_share),
+			}));
+			lockdates.push(share_lockdate);
+		}
+
+		/**********************************************************************
+		 * The cipher text can be decrypted once the share with the k-th
+		 * earliest lockdate is released
+		 */
+		lockdates.sort();
+		let lockdate = lockdates[args.threshold as usize - 1];
+
+		/**********************************************************************
+		 * Encode all unencrypted components. The nonce is part of the header.
+		 */
+		let nonce = generate_nonce(self.crypto_backend)?;
+		let header = json!({
+			"threshold": args.threshold,
+			"shares": share_headers,
+			"nonce": base64::encode(&nonce),
+		});
+
+		let lockdate_string = base64::encode(lockdate.format(self.get_datetime_format()).to_string());
+		let hint_string = base64::encode(args.hint.as_str());
+		let filename_string = base64::encode(args.filename.as_str());
+		let header_string = base64::encode(header.to_string());
+
+		/**********************************************************************
+		 * Encrypt the plaintext using the content key
+		 */
+		let additional_data = self.to_additional_data(lockdate_string.as_str(),
+													  hint_string.as_str(),
+													  filename_string.as_str(),
+													  header_string.as_str());
+		let payload = seal_payload(self.crypto_backend,
+								   &content_key,
+								   &nonce,
+								   additional_data.as_bytes(),
+								   args.plaintext.as_bytes())?;
+
+		/**********************************************************************
+		 * Build up the cipher text for v5
+		 */
+		Ok(format!("{}:{}:{}:{}:{}:{}",
+				   self.get_client_version(),
+				   lockdate_string,
+				   base64::encode(&payload),
+				   hint_string,
+				   filename_string,
+				   header_string))
+    }
+
+    /// Get the configuration of a key server named by a cipher text. Key servers which are not configured are only
+    /// contacted if the first configuration allows them, using its settings. Anything else is rejected, as the cipher
+    /// text is not authenticated yet.
+    fn get_config_for_api_url(&self, api_url: &str) -> Result<Arc<dyn Config>, String> {
+        for config in self.configs.iter() {
+            if config.get_api_url() == api_url {
+                return Ok(Arc::clone(config));
+            }
+        }
+
+        let base = self.configs
+            .first()
+            .filter(|base| base.get_allowed_api_urls().iter().any(|allowed_api_url| allowed_api_url == api_url))
+            .filter(|_| api_url.starts_with("https://") || api_url.starts_with("http://"))
+            .ok_or_else(|| String::from("The key server is neither configured nor allowed."))?;
+
+        Ok(Arc::new(OverrideConfig::new_with_api_url(Arc::clone(base), api_url)))
+    }
+
+    /// The additional authenticated data binds every unencrypted component to the encrypted payload.
+    fn to_additional_data(&self, lockdate: &str, hint: &str, filename: &str, header: &str) -> String {
+        format!("{}:{}:{}:{}:{}",
+                       self.get_client_version(),
+                       lockdate,
+                       hint,
+                       filename,
+                       header)
+    }
+}
+
+impl Client for V5Client {
+    fn encrypt(&self, args: &ClientEncryptArg) -> Result<String, String> {
+		/**********************************************************************
+		 * Passphrases are not supported
+		 */
+		if !args.passphrase.is_empty() {
+			return Err(String::from("Client version 5 does not support a passphrase."));
+		}
+
+		/**********************************************************************
+		 * Early-unlock recipients are not supported
+		 */
+		if !args.escape_recipients.is_empty() {
+			return Err(String::from("Client version 5 does not support early-unlock recipients."));
+		}
+
+		/**********************************************************************
+		 * Lock one share on every configured key server
+		 */
+		self.encrypt_threshold(&V5EncryptArg {
+			plaintext: args.plaintext.clone(),
+			hint: args.hint.clone(),
+			filename: args.filename.clone(),
+			threshold: self.threshold,
+			shares: self.configs
+				.iter()
+				.map(|config| V5EncryptShareArg {
+					config: Arc::clone(config),
+					lockdate: args.lockdate,
+				})
+				.collect(),
+		})
+    }
+
+    fn decrypt(&self, ciphertext: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+		/**********************************************************************
+		 * Extract the unencrypted components
+		 */
+		let inspect_result = self.inspect(ciphertext)
+			.map_err(|error| decrypt_failure(error, "", "", ClientDecryptErrorKind::Generic))?;
+		let hint = inspect_result.hint.as_str();
+		let filename = inspect_result.filename.as_str();
+
+		let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
+		let header = format::decode_header(cipher_comp_vec[5])
+			.map_err(|error| decrypt_failure(error, hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		/**********************************************************************
+		 * Unlock the shares in the order of their lockdates until enough
+		 * shares are available. Unreachable key servers are skipped.
+		 */
+		let mut share_order: Vec<usize> = (0..inspect_result.shares.len()).collect();
+		share_order.sort_by_key(|index| inspect_result.shares[*index].lockdate);
+
+		let mut shares: Vec<Vec<u8>> = Vec::new();
+		let mut errors: Vec<String> = Vec::new();
+		let mut only_not_yet_released: bool = true;
+		for index in share_order {
+			if shares.len() >= inspect_result.threshold {
+				break;
+			}
+
+			let share_info = &inspect_result.shares[index];
+			let share_config = match self.get_config_for_api_url(share_info.api_url.as_str()) {
+				Ok(share_config) => share_config,
+				Err(error) => {
+					only_not_yet_released = false;
+					errors.push(format!("{}: {}", share_info.api_url, error));
+					continue;
+				}
+			};
+			let v1_client = V1Client::new_with_crypto_backend(Arc::clone(&self.analyzer),
+															  share_config,
+															  self.crypto_backend);
+
+			let private_key = match v1_client.get_private_key(share_info.lockdate) {
+				Ok(private_key) => private_key,
+				Err(failure) => {
+					only_not_yet_released &= failure.kind == ClientDecryptErrorKind::NotYetReleased;
+					errors.push(format!("{}: {}", share_info.api_url, failure.error_message));
+					continue;
+				}
+			};
+
+			let locked_share = base64::decode(header["shares"][index]["key"].as_str().unwrap_or(""))
+				.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+
+			let share_bytes = match private_key.decrypt(&locked_share) {
+				Ok(share_bytes) => share_bytes,
+				Err(error) => {
+					only_not_yet_released = false;
+					errors.push(format!("{}: {}", share_info.api_url, error));
+					continue;
+				}
+			};
+
+			match shamir::check_share(&share_bytes) {
+				Ok(()) => shares.push(share_bytes),
+				Err(error) => {
+					only_not_yet_released = false;
+					errors.push(format!("{}: {}", share_info.api_url, error));
+				}
+			}
+		}
+
+		if shares.len() < inspect_result.threshold {
+			return Err(decrypt_failure(format!("Only {} of {} required shares have been released. {}",
+											   shares.len(),
+											   inspect_result.threshold,
+											   errors.join(" ")),
+									   hint,
+									   filename,
+									   if only_not_yet_released {
+										   ClientDecryptErrorKind::NotYetReleased
+									   } else {
+										   ClientDecryptErrorKind::Generic
+									   }));
+		}
+
+		/**********************************************************************
+		 * Recover the content key and decrypt the payload
+		 */
+		let content_key = shamir::recover(inspect_result.threshold, &shares)
+			.map_err(|error| decrypt_failure(error, hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		let nonce = base64::decode(header["nonce"].as_str().unwrap_or(""))
+			.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+		let payload = base64::decode(cipher_comp_vec[2])
+			.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		let additional_data = self.to_additional_data(cipher_comp_vec[1],
+													  cipher_comp_vec[3],
+													  cipher_comp_vec[4],
+													  cipher_comp_vec[5]);
+		let plaintext_bytes = open_payload(self.crypto_backend,
+										   &content_key,
+										   &nonce,
+										   additional_data.as_bytes(),
+										   &payload)
+			.ok_or_else(|| decrypt_failure(String::from("Cipher is invalid. The payload failed the integrity check."),
+										   hint,
+										   filename,
+										   ClientDecryptErrorKind::Generic))?;
+
+		let plaintext = String::from_utf8(plaintext_bytes)
+			.map_err(|error| decrypt_failure(error.to_string(), hint, filename, ClientDecryptErrorKind::Generic))?;
+
+		Ok(ClientDecryptResultSuccess {
+			plaintext,
+			hint: String::from(hint),
+			filename: String::from(filename),
+		})
+    }
+
+    fn decrypt_with_passphrase(&self, ciphertext: &str, _passphrase: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+		/**********************************************************************
+		 * Version 5 does not support passphrases
+		 */
+    	self.decrypt(ciphertext)
+    }
+
+    fn decrypt_with_escape_key(&self, _ciphertext: &str, _private_key_pem: &str, _passphrase: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+    	Err(ClientDecryptResultFailure {
+    		error_message: String::from("Client version 5 does not support early-unlock recipients."),
+    		hint: String::from(""),
+    		filename: String::from(""),
+    		kind: ClientDecryptErrorKind::Generic,
+    	})
+    }
+
+    fn inspect(&self, ciphertext: &str) -> Result<ClientInspectResult, String> {
+    	format::inspect_v5(ciphertext)
+    }
+
+    fn lockdate_from_snailcrypt_cipher(&self, ciphertext: &str) -> Result<DateTime<FixedOffset>, String> {
+        let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
+
+        if cipher_comp_vec.len() != 6 {
+            return Err(String::from("Cipher is invalid. It must consist of 6 components separted by a colon."));
+        }
+
+        let lockdate_string = format::decode_component(cipher_comp_vec[1])?;
+
+        DateTime::parse_from_str(lockdate_string.as_str(), self.get_datetime_format())
+            .map_err(|error| error.to_string())
+    }
+
+    fn check_lockdate(&self, lockdate: DateTime<FixedOffset>)
+    	-> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+		/**********************************************************************
+		 * Every key server applies its own policy. The cipher text opens
+		 * once the share with the k-th earliest lockdate is released.
+		 */
+		let mut lockdates: Vec<DateTime<FixedOffset>> = Vec::new();
+		for config in self.configs.iter() {
+			let v1_client = V1Client::new_with_crypto_backend(Arc::clone(&self.analyzer), Arc::clone(config), self.crypto_backend);
+			lockdates.push(v1_client.apply_lockdate_policy(lockdate)?);
+		}
+
+		lockdates.sort();
+		Ok(lockdates
+			.get((self.threshold as usize).saturating_sub(1))
+			.copied()
+			.unwrap_or(lockdate))
+    }
+
+    fn get_clock(&self) -> Arc<dyn Clock> {
+		self.configs
+			.first()
+			.and_then(|config| config.get_clock())
+			.unwrap_or_else(clock::default_clock)
+    }
+
+    fn get_datetime_format(&self) -> &str {
+        format::DATETIME_FORMAT
+    }
+
+	fn get_client_version(&self) -> ClientVersion {
+		ClientVersion::V5
+	}
+}
@@ -0,0 +1,285 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+	client::{
+		Client,
+		ClientVersion,
+		ClientEncryptArg,
+		ClientDecryptResultSuccess,
+		ClientDecryptResultFailure,
+		ClientDecryptErrorKind,
+		ClientInspectResult,
+		V4Client,
+	},
+	clock::Clock,
+	config::Config,
+	format,
+	lockdate::LockdatePolicyError,
+	util::Analyzer,
+};
+
+use std::sync::Arc;
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+use serde_json::Value;
+
+/// Domain separation of the digest of the section list.
+const SECTION_LIST_DOMAIN: &[u8] = b"snailcrypt-v6-sections";
+
+/// Result of a single section after decrypting a cipher text with several sections.
+#[derive(Debug)]
+pub enum V6SectionResult {
+	/// The key of the section has been released and the section has been decrypted.
+	Released(ClientDecryptResultSuccess),
+	/// The key of the section has not been released yet. The lockdate of the inspection result is the unlock time of the section.
+	Locked(ClientInspectResult),
+	/// The section could not be decrypted for any other reason.
+	Failed(ClientDecryptResultFailure),
+}
+
+/// This object implements the version 6 of snailcrypt strings. The version 6 is a container of several sections, each with its own lockdate, hint and filename. This allows staged reveals, where the sections of a single cipher text open one after another. Every section is a version 4 cipher text.
+///
+/// The lockdate of a version 6 cipher text is the earliest lockdate of its sections. Every section is bound to the section list and the lockdate of the cipher text, so sections can neither be reordered, dropped nor moved to another cipher text.
+#[allow(unused)]
+pub struct V6Client {
+	v4_client: V4Client,
+}
+
+impl V6Client {
+    #[allow(unused)]
+    pub fn new(analyzer: Arc<dyn Analyzer>, config: Arc<dyn Config>) -> V6Client {
+        V6Client {
+        	v4_client: V4Client::new(analyzer, config)
+        }
+    }
+
+    /// Encrypt several sections into a single cipher text.
+    pub fn encrypt_sections(&self, sections: &[ClientEncryptArg]) -> Result<String, String> {
+		if sections.is_empty() {
+			return Err(String::from("At least one section is needed."));
+		}
+
+		/**********************************************************************
+		 * Apply the lockdate policy up front, as the section list has to be
+		 * known before any section is encrypted
+		 */
+		let mut rounded_sections: Vec<ClientEncryptArg> = Vec::new();
+		for section in sections {
+			let lockdate = self.v4_client.check_lockdate(section.lockdate)
+				.map_err(|error| error.to_string())?;
+			rounded_sections.push(ClientEncryptArg {
+				lockdate,
+				..section.clone()
+			});
+		}
+
+		let lockdate = rounded_sections
+			.iter()
+			.map(|section| section.lockdate)
+			.min()
+			.unwrap();
+		let lockdate_string = base64::encode(lockdate.format(self.get_datetime_format()).to_string());
+
+		let layouts: Vec<String> = rounded_sections
+			.iter()
+			.map(|section| section_layout(base64::encode(section.lockdate.format(self.get_datetime_format()).to_string()).as_str(),
+										  base64::encode(section.hint.as_str()).as_str(),
+										  base64::encode(section.filename.as_str()).as_str()))
+			.collect();
+		let bindings = self.section_bindings(lockdate_string.as_str(), &layouts);
+
+		/**********************************************************************
+		 * Encrypt every section on its own, bound to the section list
+		 */
+		let mut section_ciphers: Vec<String> = Vec::new();
+		for ((section, layout), binding) in rounded_sections.iter().zip(layouts.iter()).zip(bindings.iter()) {
+			let section_cipher = self.v4_client.encrypt_bound(section, binding.as_str())?;
+			if section_layout_of(section_cipher.as_str())? != *layout {
+				return Err(String::from("The lockdate of a section changed while encrypting it."));
+			}
+			section_ciphers.push(section_cipher);
+		}
+
+		/**********************************************************************
+		 * Build up the cipher text for v6
+		 */
+		Ok(format!("{}:{}:{}",
+				   self.get_client_version(),
+				   lockdate_string,
+				   base64::encode(Value::from(section_ciphers).to_string())))
+    }
+
+    /// Compute the binding of every section. It consists of the position of the section and a digest of the lockdate of the cipher text and the unencrypted components of all sections.
+    fn section_bindings(&self, lockdate: &str, layouts: &[String]) -> Vec<String> {
+		let section_list = format!("{}:{}:{}", lockdate, layouts.len(), layouts.join(";"));
+		let digest = base64::encode(self.v4_client
+			.get_crypto_backend()
+			.sha256(&[SECTION_LIST_DOMAIN, section_list.as_bytes()]));
+
+		(0..layouts.len())
+			.map(|index| format!("{}:{}", index, digest))
+			.collect()
+    }
+
+    /// Extract the sections of a cipher text together with the binding they have been encrypted with.
+    fn bound_sections(&self, ciphertext: &str) -> Result<Vec<(String, String)>, String> {
+		let section_ciphers = format::section_ciphers(ciphertext)?;
+		let lockdate = ciphertext.split_terminator(':').nth(1).unwrap_or("");
+
+		let mut layouts: Vec<String> = Vec::new();
+		for section_cipher in section_ciphers.iter() {
+			layouts.push(section_layout_of(section_cipher.as_str())?);
+		}
+		let bindings = self.section_bindings(lockdate, &layouts);
+
+		Ok(section_ciphers.into_iter().zip(bindings).collect())
+    }
+
+    /// Extract the single section of a cipher text which is decrypted as a whole.
+    fn single_section(&self, ciphertext: &str) -> Result<(String, String), ClientDecryptResultFailure> {
+		let mut sections = self.bound_sections(ciphertext)
+			.map_err(|error_message| ClientDecryptResultFailure {
+				error_message,
+				hint: String::from(""),
+				filename: String::from(""),
+				kind: ClientDecryptErrorKind::Generic,
+			})?;
+
+		/**********************************************************************
+		 * A single plain text can only be returned for a single section
+		 */
+		if sections.len() != 1 {
+			return Err(ClientDecryptResultFailure {
+				error_message: format!("The cipher text consists of {} sections. Use V6Client::decrypt_sections to decrypt them.", sections.len()),
+				hint: String::from(""),
+				filename: String::from(""),
+				kind: ClientDecryptErrorKind::Generic,
+			});
+		}
+
+		Ok(sections.remove(0))
+    }
+
+    /// Decrypt every section whose key has been released. Sections which are still locked are reported together with their lockdate.
+    pub fn decrypt_sections(&self, ciphertext: &str) -> Result<Vec<V6SectionResult>, String> {
+    	self.decrypt_sections_with_passphrase(ciphertext, "")
+    }
+
+    /// Decrypt every section whose key has been released using a passphrase for protected sections.
+    pub fn decrypt_sections_with_passphrase(&self, ciphertext: &str, passphrase: &str) -> Result<Vec<V6SectionResult>, String> {
+		let mut results: Vec<V6SectionResult> = Vec::new();
+
+		for (section_cipher, binding) in self.bound_sections(ciphertext)? {
+			match self.v4_client.decrypt_bound(section_cipher.as_str(), passphrase, None, binding.as_str()) {
+				Ok(success) => results.push(V6SectionResult::Released(success)),
+				Err(failure) if failure.kind == ClientDecryptErrorKind::NotYetReleased =>
+					results.push(V6SectionResult::Locked(self.v4_client.inspect(section_cipher.as_str())?)),
+				Err(failure) => results.push(V6SectionResult::Failed(failure)),
+			}
+		}
+
+		Ok(results)
+    }
+}
+
+/// The unencrypted components of a section which are covered by the binding of every section.
+fn section_layout(lockdate: &str, hint: &str, filename: &str) -> String {
+	format!("{}:{}:{}", lockdate, hint, filename)
+}
+
+fn section_layout_of(section_cipher: &str) -> Result<String, String> {
+	let cipher_comp_vec: Vec<&str> = section_cipher.split_terminator(':').collect();
+
+	if cipher_comp_vec.len() != 6 {
+		return Err(String::from("Cipher is invalid. A section must consist of 6 components separted by a colon."));
+	}
+
+	Ok(section_layout(cipher_comp_vec[1], cipher_comp_vec[3], cipher_comp_vec[4]))
+}
+
+impl Client for V6Client {
+    fn encrypt(&self, args: &ClientEncryptArg) -> Result<String, String> {
+    	self.encrypt_sections(std::slice::from_ref(args))
+    }
+
+    fn decrypt(&self, ciphertext: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+    	self.decrypt_with_passphrase(ciphertext, "")
+    }
+
+    fn decrypt_with_passphrase(&self, ciphertext: &str, passphrase: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+		let (section_cipher, binding) = self.single_section(ciphertext)?;
+
+		self.v4_client.decrypt_bound(section_cipher.as_str(), passphrase, None, binding.as_str())
+    }
+
+    fn decrypt_with_escape_key(&self, ciphertext: &str, private_key_pem: &str, passphrase: &str)
+    	-> Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+		let (section_cipher, binding) = self.single_section(ciphertext)?;
+
+		self.v4_client.decrypt_bound(section_cipher.as_str(), passphrase, Some(private_key_pem), binding.as_str())
+    }
+
+    fn inspect(&self, ciphertext: &str) -> Result<ClientInspectResult, String> {
+    	format::inspect_v6(ciphertext)
+    }
+
+    fn lockdate_from_snailcrypt_cipher(&self, ciphertext: &str) -> Result<DateTime<FixedOffset>, String> {
+        let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
+
+        if cipher_comp_vec.len() != 3 {
+            return Err(String::from("Cipher is invalid. It must consist of 3 components separted by a colon."));
+        }
+
+        let lockdate_bytes = base64::decode(cipher_comp_vec[1])
+            .map_err(|error| error.to_string())?;
+        let lockdate_string = String::from_utf8(lockdate_bytes)
+            .map_err(|error| error.to_string())?;
+
+        DateTime::parse_from_str(lockdate_string.as_str(), self.get_datetime_format())
+            .map_err(|error| error.to_string())
+    }
+
+    fn check_lockdate(&self, lockdate: DateTime<FixedOffset>)
+    	-> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+    	self.v4_client.check_lockdate(lockdate)
+    }
+
+    fn get_clock(&self) -> Arc<dyn Clock> {
+    	self.v4_client.get_clock()
+    }
+
+    fn get_datetime_format(&self) -> &str {
+    	self.v4_client.get_datetime_format()
+    }
+
+	fn get_client_version(&self) -> ClientVersion {
+		ClientVersion::V6
+	}
+}
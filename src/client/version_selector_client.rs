@@ -29,14 +29,18 @@ pub use crate::{
         ClientEncryptArg,
         ClientDecryptResultSuccess,
         ClientDecryptResultFailure,
-        V1Client,
-        V2Client,
-        V3Client,
+        ClientDecryptErrorKind,
+        ClientInspectResult,
     },
     util::Analyzer,
 };
 
-use std::rc::Rc;
+use crate::{
+    clock::Clock,
+    lockdate::LockdatePolicyError,
+};
+
+use std::sync::Arc;
 
 use chrono::{
     DateTime,
@@ -45,10 +49,13 @@ use chrono::{
 
 #[allow(unused)]
 pub struct VersionSelectorClient {
-    analyzer: Rc<dyn Analyzer>,
-    v1_client: Rc<dyn Client>,
-    v2_client: Rc<dyn Client>,
-    v3_client: Rc<dyn Client>,
+    analyzer: Arc<dyn Analyzer>,
+    v1_client: Arc<dyn Client>,
+    v2_client: Arc<dyn Client>,
+    v3_client: Arc<dyn Client>,
+    v4_client: Arc<dyn Client>,
+    v5_client: Arc<dyn Client>,
+    v6_client: Arc<dyn Client>,
 }
 
 /// This object implements an automatic switch between the available versions of the clients. Depending on the input parameters for the encryption and decryption it will automatically choose the applicable client version and uses its implementation for the requested operation.
@@ -56,17 +63,35 @@ pub struct VersionSelectorClient {
 /// NOT IMPLEMENTED YET
 impl VersionSelectorClient {
     #[allow(unused)]
-    pub fn new(analyzer: Rc<dyn Analyzer>, v1_client: Rc<dyn Client>, v2_client: Rc<dyn Client>, v3_client: Rc<dyn Client>) -> VersionSelectorClient {
-        return VersionSelectorClient {
+    pub fn new(analyzer: Arc<dyn Analyzer>, v1_client: Arc<dyn Client>, v2_client: Arc<dyn Client>, v3_client: Arc<dyn Client>, v4_client: Arc<dyn Client>, v5_client: Arc<dyn Client>, v6_client: Arc<dyn Client>) -> VersionSelectorClient {
+        VersionSelectorClient {
             analyzer,
             v1_client,
             v2_client,
             v3_client,
-        };
+            v4_client,
+            v5_client,
+            v6_client,
+        }
     }
     
-    pub fn get_analyzer(&self) -> &Rc<dyn Analyzer> {
-        return &self.analyzer;
+    pub fn get_analyzer(&self) -> &Arc<dyn Analyzer> {
+        &self.analyzer
+    }
+
+    /// Get the client which is able to handle the version of a cipher text.
+    fn get_client_for_cipher(&self, ciphertext: &str) -> Result<&Arc<dyn Client>, String> {
+        let version = self.get_analyzer()
+            .get_version(ciphertext)?;
+
+        match version {
+            ClientVersion::V1 => Ok(&self.v1_client),
+            ClientVersion::V2 => Ok(&self.v2_client),
+            ClientVersion::V3 => Ok(&self.v3_client),
+            ClientVersion::V4 => Ok(&self.v4_client),
+            ClientVersion::V5 => Ok(&self.v5_client),
+            ClientVersion::V6 => Ok(&self.v6_client),
+        }
     }
 }
 
@@ -74,9 +99,11 @@ impl Client for VersionSelectorClient {
     fn encrypt(&self, args: &ClientEncryptArg)
         ->
         Result<String, String> {
-        if args.filename.len() > 0 {
+        if !args.passphrase.is_empty() || !args.escape_recipients.is_empty() {
+            self.v4_client.encrypt(args)
+        } else if !args.filename.is_empty() {
             self.v3_client.encrypt(args)
-        } else if args.hint.len() > 0 {
+        } else if !args.hint.is_empty() {
             self.v2_client.encrypt(args)
         } else {
             self.v1_client.encrypt(args)
@@ -92,22 +119,73 @@ impl Client for VersionSelectorClient {
         let result = self.get_analyzer()
             .get_version(ciphertext);
         
-        if result.is_err() {
+        if let Err(error) = result {
             return Err(ClientDecryptResultFailure {
-                error_message: result.unwrap_err(),
+                error_message: error,
                 hint: String::from(""),
         		filename: String::from(""),
+        		kind: ClientDecryptErrorKind::Generic,
             });
         }
         
         let version = result.unwrap();
         
         match version {
-            ClientVersion::V1 => return self.v1_client.decrypt(ciphertext),
-            ClientVersion::V2 => return self.v2_client.decrypt(ciphertext),
-            ClientVersion::V3 => return self.v3_client.decrypt(ciphertext),
+            ClientVersion::V1 => self.v1_client.decrypt(ciphertext),
+            ClientVersion::V2 => self.v2_client.decrypt(ciphertext),
+            ClientVersion::V3 => self.v3_client.decrypt(ciphertext),
+            ClientVersion::V4 => self.v4_client.decrypt(ciphertext),
+            ClientVersion::V5 => self.v5_client.decrypt(ciphertext),
+            ClientVersion::V6 => self.v6_client.decrypt(ciphertext),
+        }
+    }
+
+    fn decrypt_with_passphrase(
+        &self,
+        ciphertext: &str,
+        passphrase: &str
+        )
+        ->
+        Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+        match self.get_client_for_cipher(ciphertext) {
+            Ok(client) => client.decrypt_with_passphrase(ciphertext, passphrase),
+            Err(error_message) => Err(ClientDecryptResultFailure {
+                error_message,
+                hint: String::from(""),
+                filename: String::from(""),
+                kind: ClientDecryptErrorKind::Generic,
+            }),
+        }
+    }
+
+    fn decrypt_with_escape_key(
+        &self,
+        ciphertext: &str,
+        private_key_pem: &str,
+        passphrase: &str
+        )
+        ->
+        Result<ClientDecryptResultSuccess, ClientDecryptResultFailure> {
+        match self.get_client_for_cipher(ciphertext) {
+            Ok(client) => client.decrypt_with_escape_key(ciphertext, private_key_pem, passphrase),
+            Err(error_message) => Err(ClientDecryptResultFailure {
+                error_message,
+                hint: String::from(""),
+                filename: String::from(""),
+                kind: ClientDecryptErrorKind::Generic,
+            }),
         }
     }
+
+    fn inspect(
+        &self,
+        ciphertext: &str
+        )
+        ->
+        Result<ClientInspectResult, String> {
+        self.get_client_for_cipher(ciphertext)?
+            .inspect(ciphertext)
+    }
     
     fn lockdate_from_snailcrypt_cipher(
         &self,
@@ -115,14 +193,24 @@ impl Client for VersionSelectorClient {
         )
         ->
         Result<DateTime<FixedOffset>, String> {
-        Ok(self.v1_client.lockdate_from_snailcrypt_cipher(ciphertext).unwrap())
+        self.get_client_for_cipher(ciphertext)?
+            .lockdate_from_snailcrypt_cipher(ciphertext)
+    }
+
+    fn check_lockdate(&self, lockdate: DateTime<FixedOffset>)
+        -> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+        self.v1_client.check_lockdate(lockdate)
+    }
+
+    fn get_clock(&self) -> Arc<dyn Clock> {
+        self.v1_client.get_clock()
     }
 
     fn get_datetime_format(&self) -> &str {
-        return self.v1_client.get_datetime_format();
+        self.v1_client.get_datetime_format()
     }
     
     fn get_client_version(&self) -> ClientVersion {
-        return self.v1_client.get_client_version();
+        self.v1_client.get_client_version()
     }
 }
@@ -0,0 +1,54 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use std::{
+    thread,
+    time,
+};
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+/// The longest time to sleep before asking the clock again while waiting.
+const MAX_SLEEP: time::Duration = time::Duration::from_secs(1);
+
+/// This trait supplies the current point in time. Everything depending on "now", e.g. lockdate policies and unlock
+/// checks, asks a clock, so tests and simulations are able to control the time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Block the calling thread until the clock has reached a point in time. The clock is asked again at least every
+    /// second, so corrections of the clock are taken into account.
+    fn sleep_until(&self, instant: DateTime<Utc>) {
+        while let Ok(remaining) = (instant - self.now()).to_std() {
+            if remaining.is_zero() {
+                return;
+            }
+
+            thread::sleep(remaining.min(MAX_SLEEP));
+        }
+    }
+}
@@ -0,0 +1,88 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    api::KeyServerApi,
+    clock::TimeSource,
+    config::Config,
+};
+
+use std::{
+    sync::Arc,
+    time,
+};
+
+use chrono::{
+    DateTime,
+    Duration,
+    Utc,
+};
+
+/// The longest time a request for the `Date` header may take by default. A `TrustedClock` asks while reading the time,
+/// so the request must not block as long as an encryption may.
+pub const DEFAULT_PROBE_TIMEOUT: time::Duration = time::Duration::from_secs(5);
+
+/// A time source using the `Date` header of the key server of a configuration.
+pub struct KeyServerTimeSource {
+    key_server_api: KeyServerApi,
+    probe_timeout: time::Duration,
+}
+
+impl KeyServerTimeSource {
+    pub fn new(config: Arc<dyn Config>) -> KeyServerTimeSource {
+        KeyServerTimeSource {
+            key_server_api: KeyServerApi::new(config),
+            probe_timeout: DEFAULT_PROBE_TIMEOUT,
+        }
+    }
+
+    /// Set the longest time a request may take. The timeouts of the configuration are used if they are shorter. The
+    /// default is `DEFAULT_PROBE_TIMEOUT`.
+    pub fn set_probe_timeout(&mut self, probe_timeout: time::Duration) {
+        self.probe_timeout = probe_timeout;
+    }
+
+    pub fn get_probe_timeout(&self) -> time::Duration {
+        self.probe_timeout
+    }
+
+    /// Bound a timeout of the configuration, which is unlimited if not set, by the probe timeout.
+    fn bound_timeout(&self, timeout: Option<time::Duration>) -> Option<time::Duration> {
+        Some(timeout.map_or(self.probe_timeout, |timeout| timeout.min(self.probe_timeout)))
+    }
+}
+
+impl TimeSource for KeyServerTimeSource {
+    /// The `Date` header only has a resolution of one second, so half a second is added to hit the middle of that
+    /// second on average.
+    fn fetch_time(&self) -> Result<DateTime<Utc>, String> {
+        let config = self.key_server_api.get_config();
+        let date = self.key_server_api
+            .get_server_date(self.bound_timeout(config.get_connect_timeout()),
+                             self.bound_timeout(config.get_timeout()))
+            .map_err(|error| error.to_string())?;
+
+        Ok(date + Duration::milliseconds(500))
+    }
+}
@@ -0,0 +1,88 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::clock::Clock;
+
+use std::sync::Mutex;
+
+use chrono::{
+    DateTime,
+    Duration,
+    Utc,
+};
+
+/// A clock which only moves when told to. Share it using an `Arc` between the code under test and the test itself.
+///
+/// # Examples
+///
+/// ```
+/// use snailcrypt::clock::{
+///     Clock,
+///     ManualClock,
+/// };
+///
+/// use chrono::{
+///     DateTime,
+///     Duration,
+/// };
+///
+/// let clock = ManualClock::new(DateTime::parse_from_rfc3339("2030-01-01T12:00:00Z").unwrap().to_utc());
+/// clock.advance(Duration::hours(1));
+/// assert_eq!("2030-01-01T13:00:00+00:00", clock.now().to_rfc3339());
+/// ```
+#[derive(Debug)]
+pub struct ManualClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> ManualClock {
+        ManualClock {
+            now: Mutex::new(now),
+        }
+    }
+
+    /// Jump to a point in time, which may also be in the past.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+    }
+
+    /// Move the clock forward.
+    pub fn advance(&self, duration: Duration) {
+        let mut now = self.now.lock().unwrap();
+        *now += duration;
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        return *self.now.lock().unwrap();
+    }
+
+    /// Sleeping does not wait but moves the clock forward to the point in time.
+    fn sleep_until(&self, instant: DateTime<Utc>) {
+        let mut now = self.now.lock().unwrap();
+        *now = instant.max(*now);
+    }
+}
@@ -0,0 +1,50 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+#[allow(clippy::module_inception)]
+mod clock;
+#[cfg(feature = "client")]
+mod key_server_time_source;
+mod manual_clock;
+mod system_clock;
+mod time_source;
+mod trusted_clock;
+
+pub use clock::Clock;
+#[cfg(feature = "client")]
+pub use key_server_time_source::{
+    KeyServerTimeSource,
+    DEFAULT_PROBE_TIMEOUT,
+};
+pub use manual_clock::ManualClock;
+pub use system_clock::SystemClock;
+pub use time_source::TimeSource;
+pub use trusted_clock::TrustedClock;
+
+use std::sync::Arc;
+
+/// The clock used if the configuration does not supply one.
+pub fn default_clock() -> Arc<dyn Clock> {
+    Arc::new(SystemClock::new())
+}
@@ -0,0 +1,164 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::clock::{
+    Clock,
+    TimeSource,
+};
+
+use std::sync::{
+    Arc,
+    Mutex,
+};
+
+use chrono::{
+    DateTime,
+    Duration,
+    Utc,
+};
+
+/// The state of the last synchronization.
+#[derive(Clone, Copy, Default)]
+struct TrustedClockState {
+    /// The difference between the trusted time and the local clock. `None` until a synchronization has succeeded.
+    skew: Option<Duration>,
+    /// The local time of the last attempt to synchronize.
+    last_attempt: Option<DateTime<Utc>>,
+}
+
+/// A clock deriving the current time from a trusted time source instead of the local clock, which is often wrong on
+/// field devices. The skew of the local clock is estimated by asking the source and assuming that its answer belongs
+/// to the middle of the request. It is estimated again once it is older than the maximum age.
+///
+/// As a `Clock` it falls back to the local clock if the source has never been reached. Use `now_or_local` to find out
+/// whether it did or `trusted_now` to get an error instead. Reading the time as a `Clock` asks the source once the
+/// skew is due, so the source should bound its requests, as `KeyServerTimeSource` does.
+pub struct TrustedClock {
+    source: Arc<dyn TimeSource>,
+    local_clock: Arc<dyn Clock>,
+    max_age: Duration,
+    state: Mutex<TrustedClockState>,
+}
+
+impl TrustedClock {
+    /// Create a trusted clock correcting the local clock using a time source. The skew is estimated on first use.
+    pub fn new(source: Arc<dyn TimeSource>, local_clock: Arc<dyn Clock>) -> TrustedClock {
+        TrustedClock {
+            source,
+            local_clock,
+            max_age: Duration::hours(1),
+            state: Mutex::new(TrustedClockState::default()),
+        }
+    }
+
+    /// Set the time after which the skew is estimated again. The default is one hour.
+    pub fn set_max_age(&mut self, max_age: Duration) {
+        self.max_age = max_age;
+    }
+
+    pub fn get_max_age(&self) -> Duration {
+        self.max_age
+    }
+
+    /// Ask the time source and estimate the skew of the local clock again. Returns the new skew, which is positive if
+    /// the local clock is behind.
+    pub fn synchronize(&self) -> Result<Duration, String> {
+        let request_start = self.local_clock.now();
+        let result = self.source.fetch_time();
+        let request_end = self.local_clock.now();
+
+        let mut state = self.state.lock().unwrap();
+        state.last_attempt = Some(request_end);
+
+        let skew = result? - (request_start + (request_end - request_start) / 2);
+        state.skew = Some(skew);
+
+        Ok(skew)
+    }
+
+    /// Get the estimated skew of the local clock. `None` if the time source has never been reached.
+    pub fn get_skew(&self) -> Option<Duration> {
+        return self.state.lock().unwrap().skew;
+    }
+
+    /// Get the current point in time according to the time source. The skew is estimated first if it is unknown or
+    /// older than the maximum age.
+    pub fn trusted_now(&self) -> Result<DateTime<Utc>, String> {
+        let skew = match self.get_fresh_skew() {
+            Some(skew) => skew,
+            None => self.synchronize()?,
+        };
+
+        Ok(self.local_clock.now() + skew)
+    }
+
+    /// Get the current point in time like `now`, i.e. without an error if the time source cannot be reached. Returns
+    /// `Err` with the time of the local clock if the time source has never been reached, so the caller can tell that
+    /// the time is not trusted.
+    pub fn now_or_local(&self) -> Result<DateTime<Utc>, DateTime<Utc>> {
+        if self.is_synchronization_due() {
+            let _ = self.synchronize();
+        }
+
+        match self.get_skew() {
+            Some(skew) => Ok(self.local_clock.now() + skew),
+            None => Err(self.local_clock.now()),
+        }
+    }
+
+    /// Get the skew if it is known and not older than the maximum age.
+    fn get_fresh_skew(&self) -> Option<Duration> {
+        let state = *self.state.lock().unwrap();
+
+        match state.last_attempt {
+            Some(last_attempt) if self.local_clock.now() - last_attempt <= self.max_age => state.skew,
+            _ => None,
+        }
+    }
+
+    /// Check whether another attempt to synchronize is due. Failed attempts are repeated after the maximum age as
+    /// well, so an unreachable source is not asked every time.
+    fn is_synchronization_due(&self) -> bool {
+        let state = *self.state.lock().unwrap();
+
+        match state.last_attempt {
+            Some(last_attempt) => self.local_clock.now() - last_attempt > self.max_age,
+            None => true,
+        }
+    }
+}
+
+impl Clock for TrustedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.now_or_local()
+            .unwrap_or_else(|local_now| local_now)
+    }
+
+    /// Sleep using the local clock until it has reached the point in time corrected by the skew.
+    fn sleep_until(&self, instant: DateTime<Utc>) {
+        let skew = self.now() - self.local_clock.now();
+
+        self.local_clock.sleep_until(instant - skew);
+    }
+}
@@ -22,7 +22,72 @@
   SOFTWARE.
 *******************************************************************************/
 
-/// This trait supplies the methods for a configuration consumed by client implementations.
-pub trait Config {
+#[cfg(feature = "async")]
+use crate::transport::AsyncTransport;
+use crate::transport::Transport;
+use crate::lockdate::LockdatePolicy;
+use crate::clock::Clock;
+
+use std::{
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+/// This trait supplies the methods for a configuration consumed by client implementations. Configurations are shared
+/// between threads together with their clients.
+pub trait Config: Send + Sync {
     fn get_api_url(&self) -> &str;
+
+    /// The maximum time to establish a connection to the key server. `None` uses the default of the HTTP library.
+    fn get_connect_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// The maximum time of a whole request to the key server. `None` means no limit.
+    fn get_timeout(&self) -> Option<Duration> {
+        None
+    }
+
+    /// A directory to cache the public keys of lockdates in. `None` disables the cache.
+    fn get_cache_dir(&self) -> Option<&Path> {
+        None
+    }
+
+    /// The maximum number of threads encrypting and decrypting the chunks of large version 1 to 3 cipher texts. `None`
+    /// uses the available parallelism.
+    fn get_thread_count(&self) -> Option<usize> {
+        None
+    }
+
+    /// The policy every lockdate is checked against before encrypting. `None` accepts every lockdate, even one in the
+    /// past. The granularity and the horizon discovered from the key server apply in any case.
+    fn get_lockdate_policy(&self) -> Option<&LockdatePolicy> {
+        None
+    }
+
+    /// The clock telling the current point in time, e.g. for the lockdate policy. `None` uses the clock of the
+    /// operating system.
+    fn get_clock(&self) -> Option<Arc<dyn Clock>> {
+        None
+    }
+
+    /// Further key servers a threshold cipher text may name besides the configured ones. Cipher texts are not
+    /// authenticated before decrypting, so any other key server named by a cipher text is never contacted. Only http
+    /// and https URLs are accepted.
+    fn get_allowed_api_urls(&self) -> &[String] {
+        &[]
+    }
+
+    /// The transport used to access the key server. `None` uses the default transport of the library.
+    fn get_transport(&self) -> Option<Arc<dyn Transport>> {
+        None
+    }
+
+    /// The transport used by asynchronous clients to access the key server. `None` uses the default asynchronous
+    /// transport of the library.
+    #[cfg(feature = "async")]
+    fn get_async_transport(&self) -> Option<Arc<dyn AsyncTransport>> {
+        None
+    }
 }
@@ -22,22 +22,124 @@
   SOFTWARE.
 *******************************************************************************/
 
-use crate::config::Config;
+use crate::{
+    clock::Clock,
+    config::Config,
+    lockdate::LockdatePolicy,
+};
+
+use std::{
+    path::{
+        Path,
+        PathBuf,
+    },
+    sync::Arc,
+    time::Duration,
+};
 
 /// The default implementation for a configuration object.
+#[derive(Clone)]
 pub struct DefaultConfig {
     api_url: String,	
+    connect_timeout: Option<Duration>,
+    timeout: Option<Duration>,
+    cache_dir: Option<PathBuf>,
+    thread_count: Option<usize>,
+    lockdate_policy: Option<LockdatePolicy>,
+    clock: Option<Arc<dyn Clock>>,
+    allowed_api_urls: Vec<String>,
 }
 
 impl DefaultConfig {
     #[allow(unused)]
     pub fn new() -> DefaultConfig {
-		return DefaultConfig { api_url: String::from("https://api.snailcrypt.com") };
+		DefaultConfig::new_with_api_url("https://api.snailcrypt.com")
+    }
+
+    /// Create a configuration using a different key server, e.g. a self-hosted one.
+    #[allow(unused)]
+    pub fn new_with_api_url(api_url: &str) -> DefaultConfig {
+		DefaultConfig {
+			api_url: String::from(api_url),
+			connect_timeout: None,
+			timeout: None,
+			cache_dir: None,
+			thread_count: None,
+			lockdate_policy: None,
+			clock: None,
+			allowed_api_urls: Vec::new(),
+		}
+    }
+
+    pub fn set_api_url(&mut self, api_url: &str) {
+		self.api_url = String::from(api_url);
+    }
+
+    pub fn set_connect_timeout(&mut self, connect_timeout: Option<Duration>) {
+		self.connect_timeout = connect_timeout;
+    }
+
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+		self.timeout = timeout;
+    }
+
+    pub fn set_cache_dir(&mut self, cache_dir: Option<PathBuf>) {
+		self.cache_dir = cache_dir;
+    }
+
+    pub fn set_thread_count(&mut self, thread_count: Option<usize>) {
+		self.thread_count = thread_count;
+    }
+
+    pub fn set_lockdate_policy(&mut self, lockdate_policy: Option<LockdatePolicy>) {
+		self.lockdate_policy = lockdate_policy;
+    }
+
+    pub fn set_clock(&mut self, clock: Option<Arc<dyn Clock>>) {
+		self.clock = clock;
+    }
+
+    pub fn set_allowed_api_urls(&mut self, allowed_api_urls: Vec<String>) {
+		self.allowed_api_urls = allowed_api_urls;
+    }
+}
+
+impl Default for DefaultConfig {
+    fn default() -> DefaultConfig {
+        DefaultConfig::new()
     }
 }
 
 impl Config for DefaultConfig {
 	fn get_api_url(&self) -> &str {
-        return self.api_url.as_str();
+        self.api_url.as_str()
+    }
+
+	fn get_connect_timeout(&self) -> Option<Duration> {
+        self.connect_timeout
+    }
+
+	fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+	fn get_cache_dir(&self) -> Option<&Path> {
+        self.cache_dir.as_deref()
+    }
+
+	fn get_thread_count(&self) -> Option<usize> {
+        self.thread_count
+    }
+
+	fn get_lockdate_policy(&self) -> Option<&LockdatePolicy> {
+        self.lockdate_policy.as_ref()
+    }
+
+	fn get_clock(&self) -> Option<Arc<dyn Clock>> {
+        self.clock.clone()
+    }
+
+	fn get_allowed_api_urls(&self) -> &[String] {
+        &self.allowed_api_urls
     }
 }
@@ -22,8 +22,11 @@
   SOFTWARE.
 *******************************************************************************/
 
+#[allow(clippy::module_inception)]
 mod config;
 mod default_config;
+mod override_config;
 
 pub use config::Config;
 pub use default_config::DefaultConfig;
+pub(crate) use override_config::OverrideConfig;
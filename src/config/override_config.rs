@@ -0,0 +1,112 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::config::Config;
+use crate::lockdate::LockdatePolicy;
+use crate::clock::Clock;
+#[cfg(feature = "async")]
+use crate::transport::AsyncTransport;
+use crate::transport::Transport;
+
+use std::{
+    path::Path,
+    sync::Arc,
+    time::Duration,
+};
+
+/// A configuration replacing the API URL or the transport of another configuration. Everything else is taken from
+/// the other configuration, e.g. to reach a key server which is not configured using the settings of a configured one.
+pub(crate) struct OverrideConfig {
+    base: Arc<dyn Config>,
+    api_url: Option<String>,
+    transport: Option<Arc<dyn Transport>>,
+}
+
+impl OverrideConfig {
+    /// Create a configuration using another transport.
+    pub(crate) fn new_with_transport(base: Arc<dyn Config>, transport: Arc<dyn Transport>) -> OverrideConfig {
+        OverrideConfig {
+            base,
+            api_url: None,
+            transport: Some(transport),
+        }
+    }
+
+    /// Create a configuration using another key server.
+    pub(crate) fn new_with_api_url(base: Arc<dyn Config>, api_url: &str) -> OverrideConfig {
+        OverrideConfig {
+            base,
+            api_url: Some(String::from(api_url)),
+            transport: None,
+        }
+    }
+}
+
+impl Config for OverrideConfig {
+    fn get_api_url(&self) -> &str {
+        match &self.api_url {
+            Some(api_url) => api_url.as_str(),
+            None => self.base.get_api_url(),
+        }
+    }
+
+    fn get_connect_timeout(&self) -> Option<Duration> {
+        self.base.get_connect_timeout()
+    }
+
+    fn get_timeout(&self) -> Option<Duration> {
+        self.base.get_timeout()
+    }
+
+    fn get_cache_dir(&self) -> Option<&Path> {
+        self.base.get_cache_dir()
+    }
+
+    fn get_thread_count(&self) -> Option<usize> {
+        self.base.get_thread_count()
+    }
+
+    fn get_lockdate_policy(&self) -> Option<&LockdatePolicy> {
+        self.base.get_lockdate_policy()
+    }
+
+    fn get_clock(&self) -> Option<Arc<dyn Clock>> {
+        self.base.get_clock()
+    }
+
+    fn get_allowed_api_urls(&self) -> &[String] {
+        self.base.get_allowed_api_urls()
+    }
+
+    fn get_transport(&self) -> Option<Arc<dyn Transport>> {
+        self.transport
+            .clone()
+            .or_else(|| self.base.get_transport())
+    }
+
+    #[cfg(feature = "async")]
+    fn get_async_transport(&self) -> Option<Arc<dyn AsyncTransport>> {
+        self.base.get_async_transport()
+    }
+}
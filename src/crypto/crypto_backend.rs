@@ -0,0 +1,73 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use std::fmt;
+
+/// An RSA public key of a crypto backend. Encryption uses OAEP with SHA-1, the padding of all cipher text versions.
+pub(crate) trait PublicKey: fmt::Debug + Send + Sync {
+    /// The size of the modulus in bytes, which is also the size of every encrypted block.
+    fn size(&self) -> usize;
+
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, String>;
+
+    /// The DER encoded SubjectPublicKeyInfo of the key.
+    fn to_der(&self) -> Result<Vec<u8>, String>;
+}
+
+/// An RSA private key of a crypto backend.
+pub(crate) trait PrivateKey: fmt::Debug + Send + Sync {
+    fn size(&self) -> usize;
+
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, String>;
+
+    /// The DER encoded SubjectPublicKeyInfo of the public part of the key.
+    fn public_key_to_der(&self) -> Result<Vec<u8>, String>;
+}
+
+/// This trait supplies the cryptographic primitives used by the clients. Every backend produces byte-compatible
+/// cipher texts.
+pub(crate) trait CryptoBackend: Send + Sync {
+    #[allow(unused)]
+    fn get_name(&self) -> &'static str;
+
+    /// Parse a PEM encoded public key. Both the SubjectPublicKeyInfo and the PKCS#1 format are accepted.
+    fn public_key_from_pem(&self, pem: &[u8]) -> Result<Box<dyn PublicKey>, String>;
+
+    /// Parse a PEM encoded private key. Both the PKCS#8 and the PKCS#1 format are accepted.
+    fn private_key_from_pem(&self, pem: &[u8]) -> Result<Box<dyn PrivateKey>, String>;
+
+    /// The SHA-256 digest of the concatenation of all parts.
+    fn sha256(&self, parts: &[&[u8]]) -> [u8; 32];
+
+    /// Fill a buffer using a cryptographically secure random number generator.
+    fn rand_bytes(&self, buffer: &mut [u8]) -> Result<(), String>;
+
+    /// Encrypt using AES-256-GCM. Returns the encrypted data followed by its tag.
+    fn encrypt_aes_256_gcm(&self, key: &[u8], nonce: &[u8], additional_data: &[u8], plaintext: &[u8])
+        -> Result<Vec<u8>, String>;
+
+    /// Decrypt the output of `encrypt_aes_256_gcm`. Returns `None` if it fails the integrity check.
+    fn decrypt_aes_256_gcm(&self, key: &[u8], nonce: &[u8], additional_data: &[u8], encrypted: &[u8])
+        -> Option<Vec<u8>>;
+}
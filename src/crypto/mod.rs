@@ -0,0 +1,71 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+//! The cryptographic primitives used by the clients. They are supplied by a crypto backend, which is OpenSSL using the
+//! feature "openssl" or pure Rust crates using the feature "rustcrypto". OpenSSL is preferred if both are enabled.
+
+#[cfg(not(any(feature = "openssl", feature = "rustcrypto")))]
+compile_error!("The features \"client\" and \"native\" need either the feature \"openssl\" or the feature \"rustcrypto\", e.g. \"--features native,openssl\".");
+
+mod crypto_backend;
+#[cfg(feature = "openssl")]
+mod openssl_backend;
+#[cfg(feature = "rustcrypto")]
+mod rust_crypto_backend;
+pub(crate) mod shamir;
+
+pub(crate) use crypto_backend::{
+    CryptoBackend,
+    PrivateKey,
+    PublicKey,
+};
+#[cfg(feature = "openssl")]
+pub(crate) use openssl_backend::OpensslBackend;
+#[cfg(feature = "rustcrypto")]
+pub(crate) use rust_crypto_backend::RustCryptoBackend;
+
+/// The size of the authentication tag appended by `CryptoBackend::encrypt_aes_256_gcm`.
+pub(crate) const TAG_SIZE: usize = 16;
+/// The size of the nonce of AES-256-GCM.
+pub(crate) const NONCE_SIZE: usize = 12;
+
+/// The crypto backend used by the clients.
+pub(crate) fn default_backend() -> &'static dyn CryptoBackend {
+    #[cfg(feature = "openssl")]
+    return &OpensslBackend;
+
+    #[cfg(not(feature = "openssl"))]
+    return &RustCryptoBackend;
+}
+
+/// All crypto backends built into the library.
+#[allow(unused)]
+pub(crate) fn all_backends() -> Vec<&'static dyn CryptoBackend> {
+    vec![
+        #[cfg(feature = "openssl")]
+        &OpensslBackend,
+        #[cfg(feature = "rustcrypto")]
+        &RustCryptoBackend,
+    ]
+}
@@ -0,0 +1,185 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::crypto::{
+    CryptoBackend,
+    PrivateKey,
+    PublicKey,
+    NONCE_SIZE,
+    TAG_SIZE,
+};
+
+use std::fmt;
+
+use openssl::{
+    pkey::{
+        Private,
+        Public,
+    },
+    rsa::{
+        Padding,
+        Rsa,
+    },
+    sha::Sha256,
+    symm::{
+        Cipher,
+        decrypt_aead,
+        encrypt_aead,
+    },
+};
+
+/// The crypto backend using OpenSSL.
+pub(crate) struct OpensslBackend;
+
+#[derive(Debug)]
+struct OpensslPublicKey {
+    key: Rsa<Public>,
+}
+
+impl PublicKey for OpensslPublicKey {
+    fn size(&self) -> usize {
+        self.key.size() as usize
+    }
+
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut encrypted: Vec<u8> = vec![0; self.size()];
+        self.key
+            .public_encrypt(data, &mut encrypted, Padding::PKCS1_OAEP)
+            .map_err(|error| error.to_string())?;
+
+        Ok(encrypted)
+    }
+
+    fn to_der(&self) -> Result<Vec<u8>, String> {
+        self.key
+            .public_key_to_der()
+            .map_err(|error| error.to_string())
+    }
+}
+
+struct OpensslPrivateKey {
+    key: Rsa<Private>,
+}
+
+impl fmt::Debug for OpensslPrivateKey {
+    /// Only print the size, as the key must not end up in a log.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("OpensslPrivateKey")
+            .field("size", &self.size())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PrivateKey for OpensslPrivateKey {
+    fn size(&self) -> usize {
+        self.key.size() as usize
+    }
+
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        let mut decrypted: Vec<u8> = vec![0; self.size()];
+        let decrypted_len = self.key
+            .private_decrypt(data, &mut decrypted, Padding::PKCS1_OAEP)
+            .map_err(|error| error.to_string())?;
+        decrypted.truncate(decrypted_len);
+
+        Ok(decrypted)
+    }
+
+    fn public_key_to_der(&self) -> Result<Vec<u8>, String> {
+        self.key
+            .public_key_to_der()
+            .map_err(|error| error.to_string())
+    }
+}
+
+impl CryptoBackend for OpensslBackend {
+    fn get_name(&self) -> &'static str {
+        "openssl"
+    }
+
+    fn public_key_from_pem(&self, pem: &[u8]) -> Result<Box<dyn PublicKey>, String> {
+        let key = Rsa::public_key_from_pem(pem)
+            .or_else(|_| Rsa::public_key_from_pem_pkcs1(pem))
+            .map_err(|error| error.to_string())?;
+
+        Ok(Box::new(OpensslPublicKey { key }))
+    }
+
+    fn private_key_from_pem(&self, pem: &[u8]) -> Result<Box<dyn PrivateKey>, String> {
+        let key = Rsa::private_key_from_pem(pem)
+            .map_err(|error| error.to_string())?;
+
+        Ok(Box::new(OpensslPrivateKey { key }))
+    }
+
+    fn sha256(&self, parts: &[&[u8]]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part);
+        }
+
+        hasher.finish()
+    }
+
+    fn rand_bytes(&self, buffer: &mut [u8]) -> Result<(), String> {
+        openssl::rand::rand_bytes(buffer)
+            .map_err(|error| error.to_string())
+    }
+
+    fn encrypt_aes_256_gcm(&self, key: &[u8], nonce: &[u8], additional_data: &[u8], plaintext: &[u8])
+        -> Result<Vec<u8>, String> {
+        if nonce.len() != NONCE_SIZE {
+            return Err(String::from("The nonce must be 12 bytes long."));
+        }
+
+        let mut tag: Vec<u8> = vec![0; TAG_SIZE];
+        let mut encrypted = encrypt_aead(Cipher::aes_256_gcm(),
+                                         key,
+                                         Some(nonce),
+                                         additional_data,
+                                         plaintext,
+                                         &mut tag)
+            .map_err(|error| error.to_string())?;
+        encrypted.extend_from_slice(&tag);
+
+        Ok(encrypted)
+    }
+
+    fn decrypt_aes_256_gcm(&self, key: &[u8], nonce: &[u8], additional_data: &[u8], encrypted: &[u8])
+        -> Option<Vec<u8>> {
+        if nonce.len() != NONCE_SIZE || encrypted.len() < TAG_SIZE {
+            return None;
+        }
+
+        let (encrypted_data, tag) = encrypted.split_at(encrypted.len() - TAG_SIZE);
+        decrypt_aead(Cipher::aes_256_gcm(),
+                     key,
+                     Some(nonce),
+                     additional_data,
+                     encrypted_data,
+                     tag)
+            .ok()
+    }
+}
@@ -0,0 +1,202 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::crypto::{
+    CryptoBackend,
+    PrivateKey,
+    PublicKey,
+    NONCE_SIZE,
+    TAG_SIZE,
+};
+
+use std::fmt;
+
+use aes_gcm::{
+    aead::{
+        Aead,
+        KeyInit,
+        Payload,
+    },
+    Aes256Gcm,
+    Nonce,
+};
+use rsa::{
+    pkcs1::{
+        DecodeRsaPrivateKey,
+        DecodeRsaPublicKey,
+    },
+    pkcs8::{
+        DecodePrivateKey,
+        DecodePublicKey,
+        EncodePublicKey,
+    },
+    rand_core::OsRng,
+    traits::PublicKeyParts,
+    Oaep,
+    RsaPrivateKey,
+    RsaPublicKey,
+};
+use sha2::{
+    Digest,
+    Sha256,
+};
+
+/// The crypto backend using pure Rust crates. It needs neither OpenSSL nor any other C library.
+pub(crate) struct RustCryptoBackend;
+
+#[derive(Debug)]
+struct RustCryptoPublicKey {
+    key: RsaPublicKey,
+}
+
+impl PublicKey for RustCryptoPublicKey {
+    fn size(&self) -> usize {
+        self.key.size()
+    }
+
+    fn encrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        self.key
+            .encrypt(&mut OsRng, Oaep::new::<sha1::Sha1>(), data)
+            .map_err(|error| error.to_string())
+    }
+
+    fn to_der(&self) -> Result<Vec<u8>, String> {
+        self.key
+            .to_public_key_der()
+            .map(|der| der.into_vec())
+            .map_err(|error| error.to_string())
+    }
+}
+
+struct RustCryptoPrivateKey {
+    key: RsaPrivateKey,
+}
+
+impl fmt::Debug for RustCryptoPrivateKey {
+    /// Only print the size, as the key must not end up in a log.
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("RustCryptoPrivateKey")
+            .field("size", &self.size())
+            .finish_non_exhaustive()
+    }
+}
+
+impl PrivateKey for RustCryptoPrivateKey {
+    fn size(&self) -> usize {
+        self.key.size()
+    }
+
+    fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, String> {
+        self.key
+            .decrypt(Oaep::new::<sha1::Sha1>(), data)
+            .map_err(|error| error.to_string())
+    }
+
+    fn public_key_to_der(&self) -> Result<Vec<u8>, String> {
+        self.key
+            .to_public_key()
+            .to_public_key_der()
+            .map(|der| der.into_vec())
+            .map_err(|error| error.to_string())
+    }
+}
+
+/// Convert a PEM to a string without surrounding whitespace, which the PEM parsers reject.
+fn pem_to_str(pem: &[u8]) -> Result<&str, String> {
+    std::str::from_utf8(pem)
+        .map(|pem| pem.trim())
+        .map_err(|error| error.to_string())
+}
+
+impl CryptoBackend for RustCryptoBackend {
+    fn get_name(&self) -> &'static str {
+        "rustcrypto"
+    }
+
+    fn public_key_from_pem(&self, pem: &[u8]) -> Result<Box<dyn PublicKey>, String> {
+        let pem = pem_to_str(pem)?;
+        let key = RsaPublicKey::from_public_key_pem(pem)
+            .or_else(|_| RsaPublicKey::from_pkcs1_pem(pem))
+            .map_err(|error| error.to_string())?;
+
+        Ok(Box::new(RustCryptoPublicKey { key }))
+    }
+
+    fn private_key_from_pem(&self, pem: &[u8]) -> Result<Box<dyn PrivateKey>, String> {
+        let pem = pem_to_str(pem)?;
+        let key = RsaPrivateKey::from_pkcs1_pem(pem)
+            .or_else(|_| RsaPrivateKey::from_pkcs8_pem(pem))
+            .map_err(|error| error.to_string())?;
+
+        Ok(Box::new(RustCryptoPrivateKey { key }))
+    }
+
+    fn sha256(&self, parts: &[&[u8]]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        for part in parts {
+            hasher.update(part);
+        }
+
+        hasher.finalize().into()
+    }
+
+    fn rand_bytes(&self, buffer: &mut [u8]) -> Result<(), String> {
+        getrandom::getrandom(buffer)
+            .map_err(|error| error.to_string())
+    }
+
+    fn encrypt_aes_256_gcm(&self, key: &[u8], nonce: &[u8], additional_data: &[u8], plaintext: &[u8])
+        -> Result<Vec<u8>, String> {
+        if nonce.len() != NONCE_SIZE {
+            return Err(String::from("The nonce must be 12 bytes long."));
+        }
+
+        let cipher = Aes256Gcm::new_from_slice(key)
+            .map_err(|error| error.to_string())?;
+
+        cipher
+            .encrypt(Nonce::from_slice(nonce), Payload {
+                msg: plaintext,
+                aad: additional_data,
+            })
+            .map_err(|error| error.to_string())
+    }
+
+    fn decrypt_aes_256_gcm(&self, key: &[u8], nonce: &[u8], additional_data: &[u8], encrypted: &[u8])
+        -> Option<Vec<u8>> {
+        if nonce.len() != NONCE_SIZE || encrypted.len() < TAG_SIZE {
+            return None;
+        }
+
+        let cipher = Aes256Gcm::new_from_slice(key).ok()?;
+
+        cipher
+            .decrypt(Nonce::from_slice(nonce), Payload {
+                msg: encrypted,
+                aad: additional_data,
+            })
+            .ok()
+    }
+}
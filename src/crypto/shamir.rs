@@ -0,0 +1,140 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+//! Shamir's secret sharing over GF(256) with the primitive polynomial x^8 + x^4 + x^3 + x^2 + 1. A share consists of
+//! its x coordinate followed by one y coordinate for every byte of the secret, which is the layout of the shares of
+//! version 5 cipher texts.
+
+use super::CryptoBackend;
+
+/// The reduction of the primitive polynomial 0x11d.
+const REDUCTION: u8 = 0x1d;
+
+fn multiply(mut a: u8, mut b: u8) -> u8 {
+    let mut product: u8 = 0;
+
+    for _ in 0..8 {
+        product ^= a & (b & 1).wrapping_neg();
+        a = (a << 1) ^ (REDUCTION & (a >> 7).wrapping_neg());
+        b >>= 1;
+    }
+
+    product
+}
+
+/// The multiplicative inverse, computed as a^254.
+fn inverse(a: u8) -> u8 {
+    let mut result: u8 = 1;
+    let mut power: u8 = a;
+
+    for bit in 0..8 {
+        if (254 >> bit) & 1 == 1 {
+            result = multiply(result, power);
+        }
+        power = multiply(power, power);
+    }
+
+    result
+}
+
+/// Split a secret into `count` shares of which `threshold` are needed to recover it. Every coefficient of the
+/// polynomials is drawn uniformly from GF(256) using the random number generator of the crypto backend.
+pub(crate) fn split(crypto_backend: &dyn CryptoBackend, secret: &[u8], threshold: u8, count: u8)
+    -> Result<Vec<Vec<u8>>, String> {
+    if threshold == 0 || threshold > count {
+        return Err(format!("The threshold must be between 1 and the number of shares ({}).", count));
+    }
+
+    let degree = threshold as usize - 1;
+    let mut coefficients: Vec<u8> = vec![0; secret.len() * degree];
+    crypto_backend.rand_bytes(&mut coefficients)?;
+
+    Ok((1..=count)
+        .map(|x| {
+            let mut share: Vec<u8> = Vec::with_capacity(secret.len() + 1);
+            share.push(x);
+
+            for (index, secret_byte) in secret.iter().enumerate() {
+                /* Horner's method, from the highest coefficient down to the secret */
+                let y = coefficients[index * degree..(index + 1) * degree]
+                    .iter()
+                    .fold(0, |acc, coefficient| multiply(acc, x) ^ coefficient);
+                share.push(multiply(y, x) ^ secret_byte);
+            }
+
+            share
+        })
+        .collect())
+}
+
+/// Recover a secret from at least `threshold` shares with distinct x coordinates.
+pub(crate) fn recover(threshold: usize, shares: &[Vec<u8>]) -> Result<Vec<u8>, String> {
+    let mut points: Vec<&[u8]> = Vec::new();
+    for share in shares {
+        check_share(share)?;
+
+        if share.len() != shares[0].len() {
+            return Err(String::from("All shares must have the same length."));
+        }
+
+        if !points.iter().any(|point| point[0] == share[0]) {
+            points.push(share.as_slice());
+        }
+    }
+
+    if points.is_empty() || points.len() < threshold {
+        return Err(String::from("Not enough shares to recover the secret."));
+    }
+
+    /**************************************************************************
+     * Lagrange interpolation at x = 0
+     */
+    let weights: Vec<u8> = points
+        .iter()
+        .map(|point_i| points
+             .iter()
+             .filter(|point_j| point_j[0] != point_i[0])
+             .fold(1, |acc, point_j| multiply(acc, multiply(point_j[0], inverse(point_j[0] ^ point_i[0])))))
+        .collect();
+
+    Ok((1..shares[0].len())
+       .map(|index| points
+            .iter()
+            .zip(weights.iter())
+            .fold(0, |acc, (point, weight)| acc ^ multiply(*weight, point[index])))
+       .collect())
+}
+
+/// Check the layout of a single share.
+pub(crate) fn check_share(share: &[u8]) -> Result<(), String> {
+    if share.len() < 2 {
+        return Err(String::from("A share must be at least 2 bytes long."));
+    }
+
+    if share[0] == 0 {
+        return Err(String::from("A share must not be located at x = 0."));
+    }
+
+    Ok(())
+}
@@ -27,7 +27,7 @@ pub use crate::util::{
 	DefaultAnalyzer,
 };
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 /// This factory produces analyzers.
 #[allow(unused)]
@@ -37,11 +37,17 @@ pub struct AnalyzerFactory {
 impl AnalyzerFactory {
     #[allow(unused)]
     pub fn new() -> AnalyzerFactory {
-        return AnalyzerFactory { };
+        AnalyzerFactory { }
     }
 
     /// Create a new analyzer object
-    pub fn create(&self) -> Rc<dyn Analyzer> {
-        return Rc::new(DefaultAnalyzer::new());
+    pub fn create(&self) -> Arc<dyn Analyzer> {
+        Arc::new(DefaultAnalyzer::new())
+    }
+}
+
+impl Default for AnalyzerFactory {
+    fn default() -> AnalyzerFactory {
+        AnalyzerFactory::new()
     }
 }
@@ -24,18 +24,33 @@
 
 pub use crate::{
 	client::{ 
-		ClientVersion,
+		BatchClient,
 		Client,
         V1Client,
         V2Client,
         V3Client,
+        V4Client,
+        V5Client,
+        V6Client,
 		VersionSelectorClient,
 	},
     config::Config,
     util::Analyzer,
 };
+use crate::api::KeyServerApi;
+use crate::clock::{
+    self,
+    KeyServerTimeSource,
+    TrustedClock,
+};
+use crate::transport::TransportStats;
+#[cfg(feature = "async")]
+use crate::client::{
+    AsyncClient,
+    DefaultAsyncClient,
+};
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 /// This factory produces clients.
 ///
@@ -49,60 +64,110 @@ use std::rc::Rc;
 ///     util,
 /// };
 ///
-/// use std::rc::Rc;
+/// use std::sync::Arc;
 ///
 /// let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-/// let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+/// let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
 ///
 /// let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-/// let config: Rc<dyn config::Config> = config_factory.create();
+/// let config: Arc<dyn config::Config> = config_factory.create();
 ///
-/// let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-///                             Rc::clone(&config));
-/// let client: Rc<dyn client::Client> = client_factory.create();
+/// let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+///                             Arc::clone(&config));
+/// let client: Arc<dyn client::Client> = client_factory.create();
 /// ```
 #[allow(unused)]
 pub struct ClientFactory {
-    analyzer: Rc<dyn Analyzer>,
-    config: Rc<dyn Config>
+    analyzer: Arc<dyn Analyzer>,
+    config: Arc<dyn Config>
 }
 
 impl ClientFactory {
     #[allow(unused)]
-    pub fn new(analyzer: Rc<dyn Analyzer>,
-            config: Rc<dyn Config>) -> ClientFactory {
-        return ClientFactory { 
-			analyzer: analyzer,
-            config: config,
-		};
+    pub fn new(analyzer: Arc<dyn Analyzer>,
+            config: Arc<dyn Config>) -> ClientFactory {
+        ClientFactory { 
+			analyzer,
+            config,
+		}
     }
 
     /// Create a new client object using a specific version.
-    pub fn create(&self) -> Rc<dyn Client> {
-        return Rc::new(
+    pub fn create(&self) -> Arc<dyn Client> {
+        Arc::new(
             VersionSelectorClient::new(
-                Rc::clone(self.get_analyzer()),
-                Rc::new(
+                Arc::clone(self.get_analyzer()),
+                Arc::new(
                     V1Client::new(
-                        Rc::clone(self.get_analyzer()),
-                        Rc::clone(self.get_config()))),
-                Rc::new(
+                        Arc::clone(self.get_analyzer()),
+                        Arc::clone(self.get_config()))),
+                Arc::new(
                     V2Client::new(
-                        Rc::clone(self.get_analyzer()),
-                        Rc::clone(self.get_config()))),
-                Rc::new(
+                        Arc::clone(self.get_analyzer()),
+                        Arc::clone(self.get_config()))),
+                Arc::new(
                     V3Client::new(
-                        Rc::clone(self.get_analyzer()),
-                        Rc::clone(self.get_config())))));
+                        Arc::clone(self.get_analyzer()),
+                        Arc::clone(self.get_config()))),
+                Arc::new(
+                    V4Client::new(
+                        Arc::clone(self.get_analyzer()),
+                        Arc::clone(self.get_config()))),
+                Arc::new(
+                    V5Client::new(
+                        Arc::clone(self.get_analyzer()),
+                        vec![Arc::clone(self.get_config())],
+                        1)),
+                Arc::new(
+                    V6Client::new(
+                        Arc::clone(self.get_analyzer()),
+                        Arc::clone(self.get_config())))))
+    }
+
+    /// Create a new client encrypting and decrypting many items at once.
+    pub fn create_batch(&self) -> BatchClient {
+        BatchClient::new(
+            Arc::clone(self.get_analyzer()),
+            Arc::clone(self.get_config()))
+    }
+
+    /// Create a new asynchronous client supporting every version.
+    #[cfg(feature = "async")]
+    pub fn create_async(&self) -> Arc<dyn AsyncClient> {
+        Arc::new(
+            DefaultAsyncClient::new(
+                Arc::clone(self.get_analyzer()),
+                Arc::clone(self.get_config())))
     }
     
+    /// Get the counters about the reuse of connections of the transport used by the clients of this factory. Unless
+    /// the configuration supplies a transport, all clients of the process share the connections of the default
+    /// transport.
+    pub fn get_transport_stats(&self) -> Option<TransportStats> {
+        KeyServerApi::new(Arc::clone(self.get_config()))
+            .get_transport()
+            .ok()
+            .and_then(|transport| transport.get_stats())
+    }
+
+    /// Create a clock deriving the time from the `Date` header of the key server. It corrects the clock of the
+    /// configuration, e.g. for countdowns on devices with a wrong clock.
+    pub fn create_trusted_clock(&self) -> Arc<TrustedClock> {
+        Arc::new(
+            TrustedClock::new(
+                Arc::new(KeyServerTimeSource::new(Arc::clone(self.get_config()))),
+                self.get_config()
+                    .get_clock()
+                    .unwrap_or_else(clock::default_clock)))
+    }
+
     /// Get the analyzer.
-    pub fn get_analyzer(&self) -> &Rc<dyn Analyzer> {
-		return &self.analyzer;
+    pub fn get_analyzer(&self) -> &Arc<dyn Analyzer> {
+		&self.analyzer
 	}
     
     /// Get the configuraton.
-    pub fn get_config(&self) -> &Rc<dyn Config> {
-		return &self.config
+    pub fn get_config(&self) -> &Arc<dyn Config> {
+		&self.config
 	}
 }
@@ -27,7 +27,7 @@ pub use crate::config::{
 	DefaultConfig,
 };
 
-use std::rc::Rc;
+use std::sync::Arc;
 
 #[allow(unused)]
 pub struct ConfigFactory {
@@ -37,11 +37,17 @@ pub struct ConfigFactory {
 impl ConfigFactory {
     #[allow(unused)]
     pub fn new() -> ConfigFactory {
-        return ConfigFactory { };
+        ConfigFactory { }
     }
 
     /// Create a new configuration object
-    pub fn create(&self) -> Rc<dyn Config> {
-        return Rc::new(DefaultConfig::new());
+    pub fn create(&self) -> Arc<dyn Config> {
+        Arc::new(DefaultConfig::new())
+    }
+}
+
+impl Default for ConfigFactory {
+    fn default() -> ConfigFactory {
+        ConfigFactory::new()
     }
 }
@@ -23,10 +23,14 @@
 *******************************************************************************/
 
 mod analyzer_factory;
+#[cfg(feature = "client")]
 mod config_factory;
+#[cfg(feature = "client")]
 mod client_factory;
 
 pub use analyzer_factory::AnalyzerFactory;
+#[cfg(feature = "client")]
 pub use config_factory::ConfigFactory;
+#[cfg(feature = "client")]
 pub use client_factory::ClientFactory;
 
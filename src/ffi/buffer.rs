@@ -0,0 +1,228 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    client::{
+        Client,
+        ClientEncryptArg,
+    },
+    ffi::{
+        client::{
+            SnailcryptClient,
+            check_lockdate,
+            client_from_ptr,
+        },
+        error::{
+            FfiError,
+            guard,
+            optional_str_from_ptr,
+            str_from_ptr,
+            SNAILCRYPT_ERROR_DECRYPTION,
+            SNAILCRYPT_ERROR_ENCRYPTION,
+            SNAILCRYPT_ERROR_INVALID_LOCKDATE,
+            SNAILCRYPT_ERROR_INVALID_UTF8,
+            SNAILCRYPT_ERROR_NULL_POINTER,
+            SNAILCRYPT_OK,
+        },
+    },
+    lockdate::Lockdate,
+};
+
+use std::{
+    ffi::c_char,
+    ptr,
+    sync::Arc,
+    slice,
+    str,
+};
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+
+/// A buffer allocated by the library. The data is followed by a NUL byte which is not part of the length, so a buffer holding text can be used as a C string. It must be released using `snailcrypt_buffer_free`.
+#[repr(C)]
+pub struct SnailcryptBuffer {
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl SnailcryptBuffer {
+    fn empty() -> SnailcryptBuffer {
+        SnailcryptBuffer {
+            data: ptr::null_mut(),
+            len: 0,
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> SnailcryptBuffer {
+        let mut data: Vec<u8> = Vec::with_capacity(bytes.len() + 1);
+        data.extend_from_slice(bytes);
+        data.push(0);
+
+        SnailcryptBuffer {
+            data: Box::into_raw(data.into_boxed_slice()) as *mut u8,
+            len: bytes.len(),
+        }
+    }
+}
+
+/// Convert a buffer argument into a byte slice.
+///
+/// # Safety
+///
+/// The pointer must be NULL or point to at least `len` readable bytes which outlive the returned slice.
+pub(crate) unsafe fn bytes_from_ptr<'a>(data: *const u8, len: usize, name: &str) -> Result<&'a [u8], FfiError> {
+    if data.is_null() {
+        if len == 0 {
+            return Ok(&[]);
+        }
+
+        return Err(FfiError::new(SNAILCRYPT_ERROR_NULL_POINTER,
+                                 format!("The argument \"{}\" must not be NULL.", name)));
+    }
+
+    Ok(slice::from_raw_parts(data, len))
+}
+
+/// Store bytes in an output buffer argument.
+///
+/// # Safety
+///
+/// The pointer must be NULL or point to a writable `SnailcryptBuffer`.
+pub(crate) unsafe fn write_buffer(out_buffer: *mut SnailcryptBuffer, bytes: &[u8], name: &str) -> Result<(), FfiError> {
+    let out_buffer = out_buffer.as_mut()
+        .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_NULL_POINTER,
+                                     format!("The argument \"{}\" must not be NULL.", name)))?;
+    *out_buffer = SnailcryptBuffer::from_bytes(bytes);
+
+    Ok(())
+}
+
+/// Encrypt binary content. Content with a filename is base64 encoded within the cipher text, as files are locked using the version 3. Content without a filename must be valid UTF-8.
+pub(crate) fn encrypt_bytes(client: &Arc<dyn Client>, data: &[u8], lockdate: &str, hint: &str, filename: &str) -> Result<String, FfiError> {
+    let plaintext = if filename.is_empty() {
+        String::from(str::from_utf8(data)
+                     .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_INVALID_UTF8,
+                                                    format!("Binary content needs a filename: {}", error)))?)
+    } else {
+        base64::encode(data)
+    };
+
+    let lockdate: DateTime<FixedOffset> = Lockdate::parse_with_clock(lockdate, client.get_clock().as_ref())
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_INVALID_LOCKDATE, error))?
+        .get_datetime();
+    let lockdate = check_lockdate(client, lockdate)?;
+
+    client.encrypt(&ClientEncryptArg {
+        plaintext,
+        lockdate,
+        hint: String::from(hint),
+        filename: String::from(filename),
+        passphrase: String::from(""),
+        escape_recipients: Vec::new(),
+    }).map_err(|error| FfiError::new(SNAILCRYPT_ERROR_ENCRYPTION, error))
+}
+
+/// Decrypt binary content. Returns the content and the filename. The content is base64 decoded if the cipher text contains a filename.
+pub(crate) fn decrypt_bytes(client: &Arc<dyn Client>, cipher: &str) -> Result<(Vec<u8>, String), FfiError> {
+    let result_success = client
+        .decrypt(cipher)
+        .map_err(|error| FfiError::from_decrypt_kind(error.kind, error.error_message))?;
+
+    if result_success.filename.is_empty() {
+        return Ok((result_success.plaintext.into_bytes(), result_success.filename));
+    }
+
+    let data = base64::decode(result_success.plaintext.as_str())
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_DECRYPTION,
+                                       format!("The content of the file is invalid: {}", error)))?;
+
+    Ok((data, result_success.filename))
+}
+
+/// Encrypt binary content until a lockdate using a client handle. The cipher text is stored in `out_cipher`.
+///
+/// The hint may be NULL. The filename may be NULL or empty for text content. Binary content needs a filename.
+///
+/// Returns `SNAILCRYPT_OK` on success or one of the negative `SNAILCRYPT_ERROR_*` codes.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. `data` must point to `len` readable bytes. All strings must be NULL or valid NUL-terminated strings. `out_cipher` must point to a writable `SnailcryptBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_encrypt_buffer(client: *const SnailcryptClient, data: *const u8, len: usize, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, out_cipher: *mut SnailcryptBuffer) -> i32 {
+    guard(|| {
+        let client = client_from_ptr(client)?;
+        let data = bytes_from_ptr(data, len, "data")?;
+        let lockdate = str_from_ptr(lockdate, "lockdate")?;
+        let hint = optional_str_from_ptr(hint, "hint")?;
+        let filename = optional_str_from_ptr(filename, "filename")?;
+
+        let cipher = encrypt_bytes(client, data, lockdate, hint, filename)?;
+        write_buffer(out_cipher, cipher.as_bytes(), "out_cipher")?;
+
+        Ok(SNAILCRYPT_OK)
+    })
+}
+
+/// Decrypt a cipher text into binary content using a client handle. The content is stored in `out_plaintext` and the embedded filename, which may be empty, in `out_filename`.
+///
+/// Returns `SNAILCRYPT_OK` on success or one of the negative `SNAILCRYPT_ERROR_*` codes.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. `cipher` must point to `cipher_len` readable bytes. `out_plaintext` must point to a writable `SnailcryptBuffer`. `out_filename` must be NULL or point to a writable `SnailcryptBuffer`.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_decrypt_buffer(client: *const SnailcryptClient, cipher: *const u8, cipher_len: usize, out_plaintext: *mut SnailcryptBuffer, out_filename: *mut SnailcryptBuffer) -> i32 {
+    guard(|| {
+        let client = client_from_ptr(client)?;
+        let cipher = str::from_utf8(bytes_from_ptr(cipher, cipher_len, "cipher")?)
+            .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_INVALID_UTF8,
+                                           format!("The argument \"cipher\" is not valid UTF-8: {}", error)))?;
+
+        let (data, filename) = decrypt_bytes(client, cipher)?;
+        write_buffer(out_plaintext, &data, "out_plaintext")?;
+        if !out_filename.is_null() {
+            write_buffer(out_filename, filename.as_bytes(), "out_filename")?;
+        }
+
+        Ok(SNAILCRYPT_OK)
+    })
+}
+
+/// Release a buffer allocated by the library. The buffer is reset to be empty afterwards. Passing NULL or an empty buffer does nothing.
+///
+/// # Safety
+///
+/// The buffer must be NULL or filled by the library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_buffer_free(buffer: *mut SnailcryptBuffer) {
+    if let Some(buffer) = buffer.as_mut() {
+        if !buffer.data.is_null() {
+            drop(Box::from_raw(ptr::slice_from_raw_parts_mut(buffer.data, buffer.len + 1)));
+        }
+        *buffer = SnailcryptBuffer::empty();
+    }
+}
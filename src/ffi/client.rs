@@ -0,0 +1,528 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    client::{
+        Client,
+        ClientEncryptArg,
+    },
+    clock::TrustedClock,
+    config::DefaultConfig,
+    factory,
+    format,
+    lockdate::{
+        Lockdate,
+        LockdatePolicy,
+    },
+    ffi::{
+        error::{
+            FfiError,
+            cstring_from_str,
+            guard,
+            guard_result,
+            str_from_ptr,
+            SNAILCRYPT_ERROR_DECRYPTION,
+            SNAILCRYPT_ERROR_ENCRYPTION,
+            SNAILCRYPT_ERROR_INVALID_LOCKDATE,
+            SNAILCRYPT_ERROR_LOCKDATE_POLICY,
+            SNAILCRYPT_ERROR_NULL_POINTER,
+            SNAILCRYPT_OK,
+            SNAILCRYPT_WARNING_LOCAL_CLOCK,
+        },
+        ez::{
+            SnailcryptEzDecryptionCallback,
+            SnailcryptEzEncryptionCallback,
+        },
+        worker::Worker,
+    },
+};
+
+use std::{
+    cell::{
+        Ref,
+        RefCell,
+    },
+    ffi::{
+        c_char,
+        c_void,
+        CString,
+    },
+    path::PathBuf,
+    ptr,
+    sync::Arc,
+    time::Duration,
+};
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+
+/// The configuration of a client handle. A zero-initialized structure uses the default for every field.
+#[repr(C)]
+pub struct SnailcryptConfig {
+    /// The URL of the key server. NULL uses https://api.snailcrypt.com.
+    pub api_url: *const c_char,
+    /// The maximum time in milliseconds to establish a connection to the key server. 0 uses the default of the HTTP library.
+    pub connect_timeout_ms: u64,
+    /// The maximum time in milliseconds of a whole request to the key server. 0 means no limit.
+    pub timeout_ms: u64,
+    /// A directory to cache the public keys of lockdates in. NULL disables the cache.
+    pub cache_dir: *const c_char,
+    /// Reject lockdates which have already passed. Implied by a minimum lead time.
+    pub reject_past_lockdates: bool,
+    /// The minimum time in seconds between now and the lockdate. 0 means no minimum.
+    pub min_lead_time_s: u64,
+    /// The slot granularity in seconds lockdates are rounded up to. 0 keeps lockdates as they are.
+    pub lockdate_granularity_s: u64,
+    /// The maximum time in seconds between now and the lockdate. 0 means no limit.
+    pub max_horizon_s: u64,
+}
+
+/// An opaque client handle. It is created using `snailcrypt_client_new` and released using `snailcrypt_client_free`. A handle must only be used by one thread at a time.
+pub struct SnailcryptClient {
+    client: Arc<dyn Client>,
+    /// The time of the key server. The skew of the local clock is estimated on first use.
+    trusted_clock: Arc<TrustedClock>,
+    /// The worker thread running the asynchronous requests. It is started on the first asynchronous request.
+    worker: RefCell<Option<Worker>>,
+}
+
+impl SnailcryptClient {
+    /// Get the worker thread of the handle and start it if needed.
+    pub(crate) fn get_worker(&self) -> Result<Ref<'_, Worker>, FfiError> {
+        if self.worker.borrow().is_none() {
+            *self.worker.borrow_mut() = Some(Worker::start(Arc::clone(&self.client))?);
+        }
+
+        Ok(Ref::map(self.worker.borrow(), |worker| worker.as_ref().unwrap()))
+    }
+}
+
+/// A callback receiving user data and the cipher text. It must not be NULL.
+pub type SnailcryptEncryptionCallback = Option<unsafe extern "C" fn(user_data: *mut c_void, cipher: *const c_char) -> i32>;
+/// A callback receiving user data, the plain text, the hint and the filename. It must not be NULL.
+pub type SnailcryptDecryptionCallback = Option<unsafe extern "C" fn(user_data: *mut c_void, plaintext: *const c_char, hint: *const c_char, filename: *const c_char) -> i32>;
+
+/// A callback receiving the version, the lockdate, the hint and the filename of an inspected cipher text. It must not be NULL.
+pub type SnailcryptInspectCallback = Option<unsafe extern "C" fn(version: u32, lockdate: *const c_char, hint: *const c_char, filename: *const c_char) -> i32>;
+/// A callback receiving user data and the version, the lockdate, the hint and the filename of an inspected cipher text. It must not be NULL.
+pub type SnailcryptInspectionCallback = Option<unsafe extern "C" fn(user_data: *mut c_void, version: u32, lockdate: *const c_char, hint: *const c_char, filename: *const c_char) -> i32>;
+
+/// Convert a C configuration into a configuration object.
+///
+/// # Safety
+///
+/// The configuration must be NULL or point to a valid `SnailcryptConfig`.
+unsafe fn config_from_ptr(config: *const SnailcryptConfig) -> Result<DefaultConfig, FfiError> {
+    let mut default_config = DefaultConfig::new();
+
+    if let Some(config) = config.as_ref() {
+        if !config.api_url.is_null() {
+            default_config.set_api_url(str_from_ptr(config.api_url, "api_url")?);
+        }
+
+        if config.connect_timeout_ms > 0 {
+            default_config.set_connect_timeout(Some(Duration::from_millis(config.connect_timeout_ms)));
+        }
+
+        if config.timeout_ms > 0 {
+            default_config.set_timeout(Some(Duration::from_millis(config.timeout_ms)));
+        }
+
+        if !config.cache_dir.is_null() {
+            default_config.set_cache_dir(Some(PathBuf::from(str_from_ptr(config.cache_dir, "cache_dir")?)));
+        }
+
+        default_config.set_lockdate_policy(lockdate_policy_from_config(config)?);
+    }
+
+    Ok(default_config)
+}
+
+/// Build the lockdate policy of a C configuration. A configuration without any lockdate rule has no policy.
+fn lockdate_policy_from_config(config: &SnailcryptConfig) -> Result<Option<LockdatePolicy>, FfiError> {
+    if !config.reject_past_lockdates
+        && config.min_lead_time_s == 0
+        && config.lockdate_granularity_s == 0
+        && config.max_horizon_s == 0 {
+        return Ok(None);
+    }
+
+    let mut lockdate_policy = LockdatePolicy::new();
+    if config.reject_past_lockdates || config.min_lead_time_s > 0 {
+        lockdate_policy.set_min_lead_time(Some(duration_from_secs(config.min_lead_time_s, "min_lead_time_s")?));
+    }
+
+    if config.lockdate_granularity_s > 0 {
+        lockdate_policy.set_granularity(Some(duration_from_secs(config.lockdate_granularity_s, "lockdate_granularity_s")?));
+    }
+
+    if config.max_horizon_s > 0 {
+        lockdate_policy.set_max_horizon(Some(duration_from_secs(config.max_horizon_s, "max_horizon_s")?));
+    }
+
+    Ok(Some(lockdate_policy))
+}
+
+/// Convert a number of seconds of a C configuration into a duration.
+fn duration_from_secs(seconds: u64, name: &str) -> Result<chrono::Duration, FfiError> {
+    i64::try_from(seconds)
+        .ok()
+        .and_then(chrono::Duration::try_seconds)
+        .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_INVALID_LOCKDATE,
+                                     format!("The field \"{}\" is too large.", name)))
+}
+
+/// Check a lockdate against the lockdate policy of a client. Returns the lockdate the cipher text will be locked until.
+pub(crate) fn check_lockdate(client: &Arc<dyn Client>, lockdate: DateTime<FixedOffset>) -> Result<DateTime<FixedOffset>, FfiError> {
+    client.check_lockdate(lockdate)
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_LOCKDATE_POLICY, error.to_string()))
+}
+
+/// Get the handle behind a pointer.
+///
+/// # Safety
+///
+/// The handle must be NULL or created by `snailcrypt_client_new` and not yet freed.
+pub(crate) unsafe fn handle_from_ptr<'a>(client: *const SnailcryptClient) -> Result<&'a SnailcryptClient, FfiError> {
+    client.as_ref()
+        .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_NULL_POINTER,
+                                     String::from("The argument \"client\" must not be NULL.")))
+}
+
+/// Get the client of a handle.
+///
+/// # Safety
+///
+/// The handle must be NULL or created by `snailcrypt_client_new` and not yet freed.
+pub(crate) unsafe fn client_from_ptr<'a>(client: *const SnailcryptClient) -> Result<&'a Arc<dyn Client>, FfiError> {
+    handle_from_ptr(client)
+        .map(|client| &client.client)
+}
+
+/// The arguments of an encryption. They are copied from the C strings, so they can be passed to a worker thread.
+pub(crate) struct EncryptRequest {
+    plaintext: String,
+    lockdate: String,
+    hint: String,
+    filename: String,
+}
+
+impl EncryptRequest {
+    /// Copy the C string arguments of an encryption.
+    ///
+    /// # Safety
+    ///
+    /// All strings must be NULL or valid NUL-terminated strings.
+    pub(crate) unsafe fn from_ptrs(plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char) -> Result<EncryptRequest, FfiError> {
+        Ok(EncryptRequest {
+            plaintext: String::from(str_from_ptr(plaintext, "plaintext")?),
+            lockdate: String::from(str_from_ptr(lockdate, "lockdate")?),
+            hint: String::from(str_from_ptr(hint, "hint")?),
+            filename: String::from(str_from_ptr(filename, "filename")?),
+        })
+    }
+
+    /// Perform the encryption and return the cipher text.
+    pub(crate) fn run(&self, client: &Arc<dyn Client>) -> Result<CString, FfiError> {
+        /**********************************************************************
+         * Interpret the string in "lockdate" to retrieve an actual
+         * structured object "lockdate"
+         */
+        let lockdate: DateTime<FixedOffset> = Lockdate::parse_with_clock(self.lockdate.as_str(), client.get_clock().as_ref())
+            .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_INVALID_LOCKDATE, error))?
+            .get_datetime();
+        let lockdate = check_lockdate(client, lockdate)?;
+
+        /**********************************************************************
+         * Perform the encryption
+         */
+        let cipher: String = client.encrypt(&ClientEncryptArg {
+            plaintext: self.plaintext.clone(),
+            lockdate,
+            hint: self.hint.clone(),
+            filename: self.filename.clone(),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
+        }).map_err(|error| FfiError::new(SNAILCRYPT_ERROR_ENCRYPTION, error))?;
+
+        cstring_from_str(cipher.as_str(), "cipher text")
+    }
+}
+
+/// The C strings of a successful decryption.
+pub(crate) struct DecryptResult {
+    pub plaintext: CString,
+    pub hint: CString,
+    pub filename: CString,
+}
+
+/// Decrypt a cipher text and convert the result into C strings.
+pub(crate) fn decrypt_cipher(client: &Arc<dyn Client>, cipher: &str) -> Result<DecryptResult, FfiError> {
+    let result_success = client
+        .decrypt(cipher)
+        .map_err(|error| FfiError::from_decrypt_kind(error.kind, error.error_message))?;
+
+    Ok(DecryptResult {
+        plaintext: cstring_from_str(result_success.plaintext.as_str(), "plain text")?,
+        hint: cstring_from_str(result_success.hint.as_str(), "hint")?,
+        filename: cstring_from_str(result_success.filename.as_str(), "filename")?,
+    })
+}
+
+/// The version and the C strings of an inspection.
+pub(crate) struct InspectResult {
+    pub version: u32,
+    pub lockdate: CString,
+    pub hint: CString,
+    pub filename: CString,
+}
+
+/// Inspect a cipher text without accessing the network and convert the result into C strings.
+pub(crate) fn inspect_cipher(client: &Arc<dyn Client>, cipher: &str) -> Result<InspectResult, FfiError> {
+    let inspect_result = client
+        .inspect(cipher)
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_DECRYPTION, error))?;
+
+    Ok(InspectResult {
+        version: u32::from(&inspect_result.version),
+        lockdate: cstring_from_str(inspect_result.lockdate.format(client.get_datetime_format()).to_string().as_str(), "lockdate")?,
+        hint: cstring_from_str(inspect_result.hint.as_str(), "hint")?,
+        filename: cstring_from_str(inspect_result.filename.as_str(), "filename")?,
+    })
+}
+
+/// Fail if a callback argument is NULL.
+pub(crate) fn require_callback<T>(callback: Option<T>) -> Result<T, FfiError> {
+    callback
+        .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_NULL_POINTER,
+                                     String::from("The argument \"callback\" must not be NULL.")))
+}
+
+/// Encrypt the C string arguments using a client and pass the cipher text to a callback.
+///
+/// # Safety
+///
+/// All strings must be NULL or valid NUL-terminated strings.
+pub(crate) unsafe fn encrypt_using(client: &Arc<dyn Client>, plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, callback: SnailcryptEzEncryptionCallback) -> Result<i32, FfiError> {
+    let callback = require_callback(callback)?;
+    let cipher = EncryptRequest::from_ptrs(plaintext, lockdate, hint, filename)?.run(client)?;
+
+    Ok(callback(cipher.as_ptr()))
+}
+
+/// Decrypt a C string cipher text using a client and pass the plain text, the hint and the filename to a callback.
+///
+/// # Safety
+///
+/// The cipher text must be NULL or a valid NUL-terminated string.
+pub(crate) unsafe fn decrypt_using(client: &Arc<dyn Client>, cipher: *const c_char, callback: SnailcryptEzDecryptionCallback) -> Result<i32, FfiError> {
+    let callback = require_callback(callback)?;
+    let result = decrypt_cipher(client, str_from_ptr(cipher, "cipher")?)?;
+
+    Ok(callback(result.plaintext.as_ptr(), result.hint.as_ptr(), result.filename.as_ptr()))
+}
+
+/// Encrypt the C string arguments using a client and pass the cipher text to a callback carrying user data.
+///
+/// # Safety
+///
+/// All strings must be NULL or valid NUL-terminated strings.
+pub(crate) unsafe fn encrypt_with_data_using(client: &Arc<dyn Client>, plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, callback: SnailcryptEncryptionCallback, user_data: *mut c_void) -> Result<i32, FfiError> {
+    let callback = require_callback(callback)?;
+    let cipher = EncryptRequest::from_ptrs(plaintext, lockdate, hint, filename)?.run(client)?;
+
+    Ok(callback(user_data, cipher.as_ptr()))
+}
+
+/// Decrypt a C string cipher text using a client and pass the result to a callback carrying user data.
+///
+/// # Safety
+///
+/// The cipher text must be NULL or a valid NUL-terminated string.
+pub(crate) unsafe fn decrypt_with_data_using(client: &Arc<dyn Client>, cipher: *const c_char, callback: SnailcryptDecryptionCallback, user_data: *mut c_void) -> Result<i32, FfiError> {
+    let callback = require_callback(callback)?;
+    let result = decrypt_cipher(client, str_from_ptr(cipher, "cipher")?)?;
+
+    Ok(callback(user_data, result.plaintext.as_ptr(), result.hint.as_ptr(), result.filename.as_ptr()))
+}
+
+/// Create a client handle. The configuration may be NULL to use the defaults.
+///
+/// Returns NULL on failure. The reason is available using `snailcrypt_last_error_message`.
+///
+/// # Safety
+///
+/// The configuration must be NULL or point to a valid `SnailcryptConfig` whose strings are NULL or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_new(config: *const SnailcryptConfig) -> *mut SnailcryptClient {
+    guard_result(|| {
+        let config = config_from_ptr(config)?;
+
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(factory::AnalyzerFactory::new().create(),
+                                                                                 Arc::new(config));
+
+        Ok(Box::into_raw(Box::new(SnailcryptClient {
+            client: client_factory.create(),
+            trusted_clock: client_factory.create_trusted_clock(),
+            worker: RefCell::new(None),
+        })))
+    }).unwrap_or(ptr::null_mut())
+}
+
+/// Release a client handle. Pending asynchronous requests are completed before the handle is released, so this must not be called from within the callback of an asynchronous request. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// The handle must be NULL or created by `snailcrypt_client_new` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_free(client: *mut SnailcryptClient) {
+    if !client.is_null() {
+        drop(Box::from_raw(client));
+    }
+}
+
+/// Get the number of seconds until the lockdate of a cipher text according to the time of the key server of a client handle. Unlike `snailcrypt_seconds_until_unlock` this does not trust the local clock. Its skew is estimated using the `Date` header of the key server on first use and once an hour afterwards. The request takes at most the timeouts of the configuration and never longer than 5 seconds. The local clock is used as long as the key server has never been reached. The result is 0 if the lockdate has passed.
+///
+/// Returns `SNAILCRYPT_OK` on success, `SNAILCRYPT_WARNING_LOCAL_CLOCK` if the result was counted using the local clock or one of the negative `SNAILCRYPT_ERROR_*` codes.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. The cipher text must be NULL or a valid NUL-terminated string. `out_seconds` must point to a writable `int64_t`.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_seconds_until_unlock(client: *const SnailcryptClient, cipher: *const c_char, out_seconds: *mut i64) -> i32 {
+    guard(|| {
+        let handle = handle_from_ptr(client)?;
+        let cipher = str_from_ptr(cipher, "cipher")?;
+        let out_seconds = out_seconds.as_mut()
+            .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_NULL_POINTER,
+                                         String::from("The argument \"out_seconds\" must not be NULL.")))?;
+
+        let inspect_result = format::inspect(cipher)
+            .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_DECRYPTION, error))?;
+
+        /**********************************************************************
+         * Tell the caller if the key server could not be reached
+         */
+        let (now, code) = match handle.trusted_clock.now_or_local() {
+            Ok(trusted_now) => (trusted_now, SNAILCRYPT_OK),
+            Err(local_now) => (local_now, SNAILCRYPT_WARNING_LOCAL_CLOCK),
+        };
+        *out_seconds = (inspect_result.lockdate.to_utc() - now).max(chrono::Duration::zero()).num_seconds();
+
+        Ok(code)
+    })
+}
+
+/// Encrypt a plain text until a lockdate using a client handle and pass the cipher text to a callback. The lockdate is either RFC 3339, a Unix timestamp prefixed by "@", a duration from now like "+3d12h" or a day with an optional time and time zone like "tomorrow 09:00 Europe/Vienna".
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. All strings must be NULL or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_encrypt(client: *const SnailcryptClient, plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, callback: SnailcryptEzEncryptionCallback) -> i32 {
+    guard(|| {
+        encrypt_using(client_from_ptr(client)?, plaintext, lockdate, hint, filename, callback)
+    })
+}
+
+/// Decrypt a cipher text using a client handle and pass the plain text, the hint and the filename to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. The cipher text must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_decrypt(client: *const SnailcryptClient, cipher: *const c_char, callback: SnailcryptEzDecryptionCallback) -> i32 {
+    guard(|| {
+        decrypt_using(client_from_ptr(client)?, cipher, callback)
+    })
+}
+
+/// Inspect a cipher text without accessing the network and pass its version, lockdate, hint and filename to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. The cipher text must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_inspect(client: *const SnailcryptClient, cipher: *const c_char, callback: SnailcryptInspectCallback) -> i32 {
+    guard(|| {
+        let client = client_from_ptr(client)?;
+        let callback = require_callback(callback)?;
+        let result = inspect_cipher(client, str_from_ptr(cipher, "cipher")?)?;
+
+        Ok(callback(result.version, result.lockdate.as_ptr(), result.hint.as_ptr(), result.filename.as_ptr()))
+    })
+}
+
+/// Inspect a cipher text without accessing the network and pass its version, lockdate, hint and filename together with `user_data` to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. The cipher text must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_inspect_with_data(client: *const SnailcryptClient, cipher: *const c_char, callback: SnailcryptInspectionCallback, user_data: *mut c_void) -> i32 {
+    guard(|| {
+        let client = client_from_ptr(client)?;
+        let callback = require_callback(callback)?;
+        let result = inspect_cipher(client, str_from_ptr(cipher, "cipher")?)?;
+
+        Ok(callback(user_data, result.version, result.lockdate.as_ptr(), result.hint.as_ptr(), result.filename.as_ptr()))
+    })
+}
+
+/// Encrypt a plain text until a lockdate using a client handle and pass the cipher text together with `user_data` to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. All strings must be NULL or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_encrypt_with_data(client: *const SnailcryptClient, plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, callback: SnailcryptEncryptionCallback, user_data: *mut c_void) -> i32 {
+    guard(|| {
+        encrypt_with_data_using(client_from_ptr(client)?, plaintext, lockdate, hint, filename, callback, user_data)
+    })
+}
+
+/// Decrypt a cipher text using a client handle and pass the plain text, the hint and the filename together with `user_data` to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. The cipher text must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_decrypt_with_data(client: *const SnailcryptClient, cipher: *const c_char, callback: SnailcryptDecryptionCallback, user_data: *mut c_void) -> i32 {
+    guard(|| {
+        decrypt_with_data_using(client_from_ptr(client)?, cipher, callback, user_data)
+    })
+}
@@ -0,0 +1,197 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::client::ClientDecryptErrorKind;
+
+use std::{
+    cell::RefCell,
+    ffi::{
+        c_char,
+        CStr,
+        CString,
+    },
+    panic,
+    ptr,
+};
+
+/// The call succeeded.
+pub const SNAILCRYPT_OK: i32 = 0;
+/// The call succeeded, but the time of the key server could not be determined, so the local clock was used instead.
+pub const SNAILCRYPT_WARNING_LOCAL_CLOCK: i32 = 1;
+/// A required pointer argument was NULL.
+pub const SNAILCRYPT_ERROR_NULL_POINTER: i32 = -1;
+/// A string argument is not valid UTF-8.
+pub const SNAILCRYPT_ERROR_INVALID_UTF8: i32 = -2;
+/// The lockdate could not be parsed.
+pub const SNAILCRYPT_ERROR_INVALID_LOCKDATE: i32 = -3;
+/// The encryption failed, e.g. because the key server is not reachable.
+pub const SNAILCRYPT_ERROR_ENCRYPTION: i32 = -4;
+/// The decryption failed, e.g. because the cipher text is invalid.
+pub const SNAILCRYPT_ERROR_DECRYPTION: i32 = -5;
+/// The key server has not released the key of the lockdate yet.
+pub const SNAILCRYPT_ERROR_NOT_YET_RELEASED: i32 = -6;
+/// The cipher text is protected by a passphrase.
+pub const SNAILCRYPT_ERROR_PASSPHRASE_REQUIRED: i32 = -7;
+/// The passphrase is wrong.
+pub const SNAILCRYPT_ERROR_WRONG_PASSPHRASE: i32 = -8;
+/// The message has been revoked by its sender.
+pub const SNAILCRYPT_ERROR_REVOKED: i32 = -9;
+/// A result contains a NUL character and cannot be passed as a C string.
+pub const SNAILCRYPT_ERROR_INTERIOR_NUL: i32 = -10;
+/// An unexpected internal error occurred.
+pub const SNAILCRYPT_ERROR_INTERNAL: i32 = -11;
+/// Reading or writing a file failed.
+pub const SNAILCRYPT_ERROR_IO: i32 = -12;
+/// The filename embedded in a cipher text is missing or cannot be restored safely.
+pub const SNAILCRYPT_ERROR_INVALID_FILENAME: i32 = -13;
+/// The lockdate violates the lockdate policy of the configuration or the limits of the key server.
+pub const SNAILCRYPT_ERROR_LOCKDATE_POLICY: i32 = -14;
+
+thread_local! {
+    static LAST_ERROR_MESSAGE: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// A failure of a C interface function together with its code.
+pub(crate) struct FfiError {
+    pub code: i32,
+    pub message: String,
+}
+
+impl FfiError {
+    pub(crate) fn new(code: i32, message: String) -> FfiError {
+        FfiError {
+            code,
+            message,
+        }
+    }
+
+    /// Build a failure from the kind of a failed decryption.
+    pub(crate) fn from_decrypt_kind(kind: ClientDecryptErrorKind, message: String) -> FfiError {
+        let code = match kind {
+            ClientDecryptErrorKind::Generic => SNAILCRYPT_ERROR_DECRYPTION,
+            ClientDecryptErrorKind::PassphraseRequired => SNAILCRYPT_ERROR_PASSPHRASE_REQUIRED,
+            ClientDecryptErrorKind::WrongPassphrase => SNAILCRYPT_ERROR_WRONG_PASSPHRASE,
+            ClientDecryptErrorKind::NotYetReleased => SNAILCRYPT_ERROR_NOT_YET_RELEASED,
+            ClientDecryptErrorKind::Revoked => SNAILCRYPT_ERROR_REVOKED,
+        };
+
+        FfiError::new(code, message)
+    }
+}
+
+fn set_last_error_message(message: &str) {
+    /***************************************************************************
+     * Drop NUL characters, as they cannot be part of a C string
+     */
+    let message = CString::new(message.replace('\0', "")).ok();
+
+    LAST_ERROR_MESSAGE.with(|last_error_message| {
+        *last_error_message.borrow_mut() = message;
+    });
+}
+
+/// Get a copy of the last error message of the calling thread.
+pub(crate) fn last_error_message() -> CString {
+    LAST_ERROR_MESSAGE.with(|last_error_message| {
+        last_error_message
+            .borrow()
+            .clone()
+            .unwrap_or_default()
+    })
+}
+
+/// Run the body of a C interface function. Failures are stored as the last error message of the thread and a panic is turned into `SNAILCRYPT_ERROR_INTERNAL`, so no panic unwinds into the C host.
+pub(crate) fn guard<F: FnOnce() -> Result<i32, FfiError>>(body: F) -> i32 {
+    match guard_result(body) {
+        Ok(code) => code,
+        Err(code) => code,
+    }
+}
+
+/// Run the body of a C interface function like `guard`, but return the code of a failure as an error.
+pub(crate) fn guard_result<T, F: FnOnce() -> Result<T, FfiError>>(body: F) -> Result<T, i32> {
+    let result = panic::catch_unwind(panic::AssertUnwindSafe(body))
+        .unwrap_or_else(|payload| {
+            let message = payload
+                .downcast_ref::<String>()
+                .cloned()
+                .or_else(|| payload.downcast_ref::<&str>().map(|message| String::from(*message)))
+                .unwrap_or_else(|| String::from("Unknown internal error"));
+
+            Err(FfiError::new(SNAILCRYPT_ERROR_INTERNAL, message))
+        });
+
+    result.map_err(|error| {
+        set_last_error_message(error.message.as_str());
+        error.code
+    })
+}
+
+/// Convert a C string argument into a string slice.
+///
+/// # Safety
+///
+/// The pointer must be NULL or point to a valid NUL-terminated string which outlives the returned slice.
+pub(crate) unsafe fn str_from_ptr<'a>(value: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if value.is_null() {
+        return Err(FfiError::new(SNAILCRYPT_ERROR_NULL_POINTER,
+                                 format!("The argument \"{}\" must not be NULL.", name)));
+    }
+
+    CStr::from_ptr(value)
+        .to_str()
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_INVALID_UTF8,
+                                       format!("The argument \"{}\" is not valid UTF-8: {}", name, error)))
+}
+
+/// Convert an optional C string argument. NULL is treated as an empty string.
+///
+/// # Safety
+///
+/// The pointer must be NULL or point to a valid NUL-terminated string.
+pub(crate) unsafe fn optional_str_from_ptr<'a>(value: *const c_char, name: &str) -> Result<&'a str, FfiError> {
+    if value.is_null() {
+        return Ok("");
+    }
+
+    str_from_ptr(value, name)
+}
+
+/// Convert a result into a C string.
+pub(crate) fn cstring_from_str(value: &str, name: &str) -> Result<CString, FfiError> {
+    CString::new(value)
+        .map_err(|_| FfiError::new(SNAILCRYPT_ERROR_INTERIOR_NUL,
+                                   format!("The {} contains a NUL character.", name)))
+}
+
+/// Get the message of the last failed call on the calling thread. Returns NULL if no call has failed yet. The message is owned by the library and stays valid until the next failing call on the same thread.
+#[no_mangle]
+pub extern "C" fn snailcrypt_last_error_message() -> *const c_char {
+    LAST_ERROR_MESSAGE.with(|last_error_message| {
+        match last_error_message.borrow().as_ref() {
+            Some(message) => message.as_ptr(),
+            None => ptr::null(),
+        }
+    })
+}
@@ -0,0 +1,133 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    client,
+    config,
+    factory,
+    ffi::{
+        client::{
+            SnailcryptDecryptionCallback,
+            SnailcryptEncryptionCallback,
+            decrypt_using,
+            decrypt_with_data_using,
+            encrypt_using,
+            encrypt_with_data_using,
+        },
+        error::guard,
+    },
+    util,
+};
+
+use std::{
+    ffi::{
+        c_char,
+        c_void,
+    },
+    sync::Arc,
+};
+
+/// A callback receiving the cipher text. It must not be NULL.
+pub type SnailcryptEzEncryptionCallback = Option<unsafe extern "C" fn(cipher: *const c_char) -> i32>;
+/// A callback receiving the plain text, the hint and the filename. It must not be NULL.
+pub type SnailcryptEzDecryptionCallback = Option<unsafe extern "C" fn(plaintext: *const c_char, hint: *const c_char, filename: *const c_char) -> i32>;
+
+pub(crate) fn create_client() -> Arc<dyn client::Client> {
+    /**************************************************************************
+     * Get an "anylzer" from an "analyzer_factory"
+     */
+    let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+    let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+    /**************************************************************************
+     * Get a "config" from a "config_factory"
+     */
+    let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
+    let config: Arc<dyn config::Config> = config_factory.create();
+
+    /**************************************************************************
+     * Get a "client" from a "client_factory" using:
+     * 1. An "analyzer"
+     * 2. A "config"
+     */
+    let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                    Arc::clone(&config));
+    client_factory.create()
+}
+
+/// Encrypt a plain text until a lockdate and pass the cipher text to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned. The reason is available using `snailcrypt_last_error_message`.
+///
+/// # Safety
+///
+/// All strings must be NULL or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_ez_encrypt(plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, callback: SnailcryptEzEncryptionCallback) -> i32 {
+    guard(|| {
+        encrypt_using(&create_client(), plaintext, lockdate, hint, filename, callback)
+    })
+}
+
+/// Decrypt a cipher text and pass the plain text, the hint and the filename to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned. The reason is available using `snailcrypt_last_error_message`.
+///
+/// # Safety
+///
+/// The cipher text must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_ez_decrypt(cipher: *const c_char, callback: SnailcryptEzDecryptionCallback) -> i32 {
+    guard(|| {
+        decrypt_using(&create_client(), cipher, callback)
+    })
+}
+
+/// Encrypt a plain text until a lockdate and pass the cipher text together with `user_data` to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// All strings must be NULL or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_ez_encrypt_with_data(plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, callback: SnailcryptEncryptionCallback, user_data: *mut c_void) -> i32 {
+    guard(|| {
+        encrypt_with_data_using(&create_client(), plaintext, lockdate, hint, filename, callback, user_data)
+    })
+}
+
+/// Decrypt a cipher text and pass the plain text, the hint and the filename together with `user_data` to a callback.
+///
+/// Returns the return value of the callback on success. On failure the callback is not called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The cipher text must be NULL or a valid NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_ez_decrypt_with_data(cipher: *const c_char, callback: SnailcryptDecryptionCallback, user_data: *mut c_void) -> i32 {
+    guard(|| {
+        decrypt_with_data_using(&create_client(), cipher, callback, user_data)
+    })
+}
@@ -0,0 +1,187 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    client::Client,
+    ffi::{
+        buffer::{
+            decrypt_bytes,
+            encrypt_bytes,
+        },
+        client::{
+            SnailcryptClient,
+            client_from_ptr,
+        },
+        ez::create_client,
+        error::{
+            FfiError,
+            guard,
+            optional_str_from_ptr,
+            str_from_ptr,
+            SNAILCRYPT_ERROR_INVALID_FILENAME,
+            SNAILCRYPT_ERROR_IO,
+            SNAILCRYPT_OK,
+        },
+    },
+};
+
+use std::{
+    ffi::c_char,
+    fs,
+    io::Write,
+    path::{
+        Component,
+        Path,
+    },
+    sync::Arc,
+};
+
+/// Check that an embedded filename is a single plain path component, so restoring it cannot escape the output directory.
+fn safe_filename(filename: &str) -> Result<&str, FfiError> {
+    let mut components = Path::new(filename).components();
+
+    match (components.next(), components.next()) {
+        (Some(Component::Normal(component)), None)
+            if component == filename && !filename.contains(['/', '\\', '\0']) => Ok(filename),
+        _ => Err(FfiError::new(SNAILCRYPT_ERROR_INVALID_FILENAME,
+                               format!("The filename \"{}\" cannot be restored safely.", filename.escape_default()))),
+    }
+}
+
+fn encrypt_file_using(client: &Arc<dyn Client>, in_path: &str, out_path: &str, lockdate: &str, hint: &str) -> Result<i32, FfiError> {
+    /**************************************************************************
+     * Read the file and record its name without the directories
+     */
+    let data = fs::read(in_path)
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_IO,
+                                       format!("Unable to read \"{}\": {}", in_path, error)))?;
+    let filename = Path::new(in_path)
+        .file_name()
+        .and_then(|filename| filename.to_str())
+        .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_INVALID_FILENAME,
+                                     format!("The path \"{}\" has no valid filename.", in_path)))?;
+
+    let cipher = encrypt_bytes(client, &data, lockdate, hint, filename)?;
+
+    fs::write(out_path, cipher.as_bytes())
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_IO,
+                                       format!("Unable to write \"{}\": {}", out_path, error)))?;
+
+    Ok(SNAILCRYPT_OK)
+}
+
+fn decrypt_file_using(client: &Arc<dyn Client>, in_path: &str, out_dir: &str) -> Result<i32, FfiError> {
+    let cipher = fs::read_to_string(in_path)
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_IO,
+                                       format!("Unable to read \"{}\": {}", in_path, error)))?;
+
+    let (data, filename) = decrypt_bytes(client, cipher.trim())?;
+    if filename.is_empty() {
+        return Err(FfiError::new(SNAILCRYPT_ERROR_INVALID_FILENAME,
+                                 String::from("The cipher text does not contain a file.")));
+    }
+
+    /**************************************************************************
+     * Never overwrite an existing file, which also refuses to follow a
+     * symbolic link placed at the restored path
+     */
+    let out_path = Path::new(out_dir).join(safe_filename(filename.as_str())?);
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&out_path)
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_IO,
+                                       format!("Unable to create \"{}\": {}", out_path.display(), error)))?;
+    file.write_all(&data)
+        .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_IO,
+                                       format!("Unable to write \"{}\": {}", out_path.display(), error)))?;
+
+    Ok(SNAILCRYPT_OK)
+}
+
+/// Encrypt a file until a lockdate and write the cipher text to `out_path`. The name of the file is embedded in the cipher text.
+///
+/// Returns `SNAILCRYPT_OK` on success or one of the negative `SNAILCRYPT_ERROR_*` codes.
+///
+/// # Safety
+///
+/// All strings must be NULL or valid NUL-terminated strings. The hint may be NULL.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_encrypt_file(in_path: *const c_char, out_path: *const c_char, lockdate: *const c_char, hint: *const c_char) -> i32 {
+    guard(|| {
+        encrypt_file_using(&create_client(),
+                           str_from_ptr(in_path, "in_path")?,
+                           str_from_ptr(out_path, "out_path")?,
+                           str_from_ptr(lockdate, "lockdate")?,
+                           optional_str_from_ptr(hint, "hint")?)
+    })
+}
+
+/// Decrypt a file written by `snailcrypt_encrypt_file` and restore the embedded file within `out_dir`.
+///
+/// The embedded filename must be a plain filename without any directories. An existing file is never overwritten.
+///
+/// Returns `SNAILCRYPT_OK` on success or one of the negative `SNAILCRYPT_ERROR_*` codes.
+///
+/// # Safety
+///
+/// All strings must be NULL or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_decrypt_file(in_path: *const c_char, out_dir: *const c_char) -> i32 {
+    guard(|| {
+        decrypt_file_using(&create_client(),
+                           str_from_ptr(in_path, "in_path")?,
+                           str_from_ptr(out_dir, "out_dir")?)
+    })
+}
+
+/// Encrypt a file like `snailcrypt_encrypt_file` using a client handle.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. All strings must be NULL or valid NUL-terminated strings. The hint may be NULL.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_encrypt_file(client: *const SnailcryptClient, in_path: *const c_char, out_path: *const c_char, lockdate: *const c_char, hint: *const c_char) -> i32 {
+    guard(|| {
+        encrypt_file_using(client_from_ptr(client)?,
+                           str_from_ptr(in_path, "in_path")?,
+                           str_from_ptr(out_path, "out_path")?,
+                           str_from_ptr(lockdate, "lockdate")?,
+                           optional_str_from_ptr(hint, "hint")?)
+    })
+}
+
+/// Decrypt a file like `snailcrypt_decrypt_file` using a client handle.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. All strings must be NULL or valid NUL-terminated strings.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_decrypt_file(client: *const SnailcryptClient, in_path: *const c_char, out_dir: *const c_char) -> i32 {
+    guard(|| {
+        decrypt_file_using(client_from_ptr(client)?,
+                           str_from_ptr(in_path, "in_path")?,
+                           str_from_ptr(out_dir, "out_dir")?)
+    })
+}
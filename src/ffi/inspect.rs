@@ -0,0 +1,149 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    clock::SystemClock,
+    ffi::error::{
+        FfiError,
+        cstring_from_str,
+        guard,
+        str_from_ptr,
+        SNAILCRYPT_ERROR_DECRYPTION,
+        SNAILCRYPT_ERROR_NULL_POINTER,
+        SNAILCRYPT_OK,
+    },
+    format,
+};
+
+use std::{
+    ffi::{
+        c_char,
+        CString,
+    },
+    ptr,
+};
+
+/// Information about a cipher text which is readable without the key of the lockdate. The strings are allocated by the library and must be released using `snailcrypt_inspect_info_free`.
+#[repr(C)]
+pub struct SnailcryptInspectInfo {
+    /// The version of the cipher text, e.g. 4.
+    pub version: u32,
+    /// The lockdate as seconds since the Unix epoch.
+    pub lockdate_unix: i64,
+    /// The lockdate as an RFC 3339 string.
+    pub lockdate: *mut c_char,
+    pub hint: *mut c_char,
+    pub filename: *mut c_char,
+    /// True if the lockdate has not passed yet according to the local clock.
+    pub locked: bool,
+}
+
+/// Release a C string handed out using `CString::into_raw`.
+///
+/// # Safety
+///
+/// The pointer must be NULL or handed out using `CString::into_raw` and not yet freed.
+unsafe fn free_raw_cstring(value: &mut *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(*value));
+    }
+    *value = ptr::null_mut();
+}
+
+/// Inspect a cipher text without accessing the network and fill `out_info`.
+///
+/// Returns `SNAILCRYPT_OK` on success or one of the negative `SNAILCRYPT_ERROR_*` codes. On failure `out_info` is left untouched.
+///
+/// # Safety
+///
+/// The cipher text must be NULL or a valid NUL-terminated string. `out_info` must point to a writable `SnailcryptInspectInfo`.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_inspect(cipher: *const c_char, out_info: *mut SnailcryptInspectInfo) -> i32 {
+    guard(|| {
+        let cipher = str_from_ptr(cipher, "cipher")?;
+        let out_info = out_info.as_mut()
+            .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_NULL_POINTER,
+                                         String::from("The argument \"out_info\" must not be NULL.")))?;
+
+        let inspect_result = format::inspect(cipher)
+            .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_DECRYPTION, error))?;
+
+        /**********************************************************************
+         * Convert every string before allocating any, so nothing leaks on
+         * failure
+         */
+        let lockdate = cstring_from_str(inspect_result.lockdate.to_rfc3339().as_str(), "lockdate")?;
+        let hint = cstring_from_str(inspect_result.hint.as_str(), "hint")?;
+        let filename = cstring_from_str(inspect_result.filename.as_str(), "filename")?;
+
+        *out_info = SnailcryptInspectInfo {
+            version: u32::from(&inspect_result.version),
+            lockdate_unix: inspect_result.lockdate.timestamp(),
+            lockdate: lockdate.into_raw(),
+            hint: hint.into_raw(),
+            filename: filename.into_raw(),
+            locked: inspect_result.is_locked(&SystemClock::new()),
+        };
+
+        Ok(SNAILCRYPT_OK)
+    })
+}
+
+/// Release the strings of an inspection filled by `snailcrypt_inspect`. The strings are set to NULL afterwards. Passing NULL does nothing.
+///
+/// # Safety
+///
+/// The information must be NULL or filled by `snailcrypt_inspect` and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_inspect_info_free(info: *mut SnailcryptInspectInfo) {
+    if let Some(info) = info.as_mut() {
+        free_raw_cstring(&mut info.lockdate);
+        free_raw_cstring(&mut info.hint);
+        free_raw_cstring(&mut info.filename);
+    }
+}
+
+/// Get the number of seconds until the lockdate of a cipher text according to the local clock. The result is 0 if the lockdate has passed. The network is not accessed.
+///
+/// Returns `SNAILCRYPT_OK` on success or one of the negative `SNAILCRYPT_ERROR_*` codes.
+///
+/// # Safety
+///
+/// The cipher text must be NULL or a valid NUL-terminated string. `out_seconds` must point to a writable `int64_t`.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_seconds_until_unlock(cipher: *const c_char, out_seconds: *mut i64) -> i32 {
+    guard(|| {
+        let cipher = str_from_ptr(cipher, "cipher")?;
+        let out_seconds = out_seconds.as_mut()
+            .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_NULL_POINTER,
+                                         String::from("The argument \"out_seconds\" must not be NULL.")))?;
+
+        let inspect_result = format::inspect(cipher)
+            .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_DECRYPTION, error))?;
+
+        *out_seconds = inspect_result.get_time_until_unlock(&SystemClock::new()).num_seconds();
+
+        Ok(SNAILCRYPT_OK)
+    })
+}
@@ -0,0 +1,123 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::ffi::{
+    client::{
+        EncryptRequest,
+        SnailcryptClient,
+        decrypt_cipher,
+        handle_from_ptr,
+        require_callback,
+    },
+    error::{
+        guard,
+        guard_result,
+        last_error_message,
+        str_from_ptr,
+        SNAILCRYPT_OK,
+    },
+};
+
+use std::{
+    ffi::{
+        c_char,
+        c_void,
+    },
+    ptr,
+};
+
+/// A callback receiving the result of an asynchronous encryption. On success `result` is `SNAILCRYPT_OK`, `cipher` holds the cipher text and `error_message` is NULL. On failure `result` is one of the negative `SNAILCRYPT_ERROR_*` codes, `cipher` is NULL and `error_message` describes the failure. The strings are only valid during the call. It must not be NULL.
+pub type SnailcryptAsyncEncryptionCallback = Option<unsafe extern "C" fn(user_data: *mut c_void, result: i32, cipher: *const c_char, error_message: *const c_char)>;
+/// A callback receiving the result of an asynchronous decryption. On success `result` is `SNAILCRYPT_OK` and `error_message` is NULL. On failure `result` is one of the negative `SNAILCRYPT_ERROR_*` codes, the plain text, the hint and the filename are NULL and `error_message` describes the failure. The strings are only valid during the call. It must not be NULL.
+pub type SnailcryptAsyncDecryptionCallback = Option<unsafe extern "C" fn(user_data: *mut c_void, result: i32, plaintext: *const c_char, hint: *const c_char, filename: *const c_char, error_message: *const c_char)>;
+
+/// The user data of a callback. The caller is responsible for accessing it safely from the worker thread.
+#[derive(Clone, Copy)]
+struct UserData(*mut c_void);
+
+unsafe impl Send for UserData {}
+
+/// Encrypt a plain text until a lockdate without blocking. The request runs on a worker thread of the handle and the callback is called on that thread once it completes. Requests of a handle run one after another.
+///
+/// Returns `SNAILCRYPT_OK` if the request has been queued. Otherwise the callback is never called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. All strings must be NULL or valid NUL-terminated strings. They are copied before this function returns. `user_data` must stay valid until the callback has been called.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_encrypt_async(client: *const SnailcryptClient, plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, callback: SnailcryptAsyncEncryptionCallback, user_data: *mut c_void) -> i32 {
+    guard(|| {
+        let handle = handle_from_ptr(client)?;
+        let callback = require_callback(callback)?;
+        let request = EncryptRequest::from_ptrs(plaintext, lockdate, hint, filename)?;
+        let user_data = UserData(user_data);
+
+        handle.get_worker()?.submit(Box::new(move |client| {
+            let user_data = user_data;
+            match guard_result(|| request.run(client)) {
+                Ok(cipher) => callback(user_data.0, SNAILCRYPT_OK, cipher.as_ptr(), ptr::null()),
+                Err(code) => callback(user_data.0, code, ptr::null(), last_error_message().as_ptr()),
+            }
+        }))?;
+
+        Ok(SNAILCRYPT_OK)
+    })
+}
+
+/// Decrypt a cipher text without blocking. The request runs on a worker thread of the handle and the callback is called on that thread once it completes. Requests of a handle run one after another.
+///
+/// Returns `SNAILCRYPT_OK` if the request has been queued. Otherwise the callback is never called and one of the negative `SNAILCRYPT_ERROR_*` codes is returned.
+///
+/// # Safety
+///
+/// The handle must be created by `snailcrypt_client_new`. The cipher text must be NULL or a valid NUL-terminated string. It is copied before this function returns. `user_data` must stay valid until the callback has been called.
+#[no_mangle]
+pub unsafe extern "C" fn snailcrypt_client_decrypt_async(client: *const SnailcryptClient, cipher: *const c_char, callback: SnailcryptAsyncDecryptionCallback, user_data: *mut c_void) -> i32 {
+    guard(|| {
+        let handle = handle_from_ptr(client)?;
+        let callback = require_callback(callback)?;
+        let cipher = String::from(str_from_ptr(cipher, "cipher")?);
+        let user_data = UserData(user_data);
+
+        handle.get_worker()?.submit(Box::new(move |client| {
+            let user_data = user_data;
+            match guard_result(|| decrypt_cipher(client, cipher.as_str())) {
+                Ok(result) => callback(user_data.0,
+                                       SNAILCRYPT_OK,
+                                       result.plaintext.as_ptr(),
+                                       result.hint.as_ptr(),
+                                       result.filename.as_ptr(),
+                                       ptr::null()),
+                Err(code) => callback(user_data.0,
+                                      code,
+                                      ptr::null(),
+                                      ptr::null(),
+                                      ptr::null(),
+                                      last_error_message().as_ptr()),
+            }
+        }))?;
+
+        Ok(SNAILCRYPT_OK)
+    })
+}
@@ -0,0 +1,98 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    client::Client,
+    ffi::error::{
+        FfiError,
+        SNAILCRYPT_ERROR_INTERNAL,
+    },
+};
+
+use std::{
+    panic,
+    sync::Arc,
+    sync::mpsc::{
+        self,
+        Sender,
+    },
+    thread::{
+        self,
+        JoinHandle,
+    },
+};
+
+/// A request run by a worker thread using the client of the worker.
+pub(crate) type Job = Box<dyn FnOnce(&Arc<dyn Client>) + Send>;
+
+/// A worker thread running the asynchronous requests of a client handle one after another. The worker shares the client of the handle.
+pub(crate) struct Worker {
+    sender: Option<Sender<Job>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Worker {
+    pub(crate) fn start(client: Arc<dyn Client>) -> Result<Worker, FfiError> {
+        let (sender, receiver) = mpsc::channel::<Job>();
+
+        let thread = thread::Builder::new()
+            .name(String::from("snailcrypt-worker"))
+            .spawn(move || {
+                for job in receiver {
+                    /***********************************************************
+                     * A job reports its own failures. Keep the worker alive
+                     * if it panics nevertheless.
+                     */
+                    let _ = panic::catch_unwind(panic::AssertUnwindSafe(|| job(&client)));
+                }
+            })
+            .map_err(|error| FfiError::new(SNAILCRYPT_ERROR_INTERNAL,
+                                           format!("Unable to start the worker thread: {}", error)))?;
+
+        Ok(Worker {
+            sender: Some(sender),
+            thread: Some(thread),
+        })
+    }
+
+    /// Queue a request. It is run after all requests queued before.
+    pub(crate) fn submit(&self, job: Job) -> Result<(), FfiError> {
+        self.sender
+            .as_ref()
+            .and_then(|sender| sender.send(job).ok())
+            .ok_or_else(|| FfiError::new(SNAILCRYPT_ERROR_INTERNAL,
+                                         String::from("The worker thread has stopped.")))
+    }
+}
+
+impl Drop for Worker {
+    /// Complete all queued requests and stop the thread.
+    fn drop(&mut self) {
+        drop(self.sender.take());
+
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
@@ -0,0 +1,109 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::clock::Clock;
+
+use std::fmt;
+
+use chrono::{
+    DateTime,
+    Duration,
+    FixedOffset,
+};
+
+/// The format of the lockdate within every cipher text.
+pub const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
+
+/// Enumeration for the available client versions. This can be used to identify the client object you are using.
+#[derive(Debug, PartialEq, Eq)]
+pub enum ClientVersion {
+    V1,
+    V2,
+	V3,
+	V4,
+	V5,
+	V6,
+}
+
+/// Result parameter structure after inspecting a cipher text. Inspecting does not need access to the network.
+#[derive(Debug)]
+pub struct ClientInspectResult {
+	pub version: ClientVersion,
+	pub lockdate: DateTime<FixedOffset>,
+	pub hint: String,
+	pub filename: String,
+	/// True if the cipher text can only be decrypted using a passphrase.
+	pub passphrase_required: bool,
+	/// The fingerprints of the early-unlock recipients. If this is not empty, the cipher text may be decrypted before the lockdate by the holders of these keys.
+	pub escape_recipients: Vec<String>,
+	/// The ID of the revocable message on the key server. This is empty if the cipher text cannot be revoked.
+	pub message_id: String,
+	/// The number of shares needed to decrypt the cipher text. This is 0 if the cipher text does not use a threshold time lock.
+	pub threshold: usize,
+	/// The shares of a threshold time lock. This is empty if the cipher text does not use a threshold time lock.
+	pub shares: Vec<ClientInspectShare>,
+	/// The sections of a cipher text with several sections. This is empty if the cipher text consists of a single section.
+	pub sections: Vec<ClientInspectResult>,
+}
+
+impl ClientInspectResult {
+	/// True if the lockdate has not passed yet according to a clock.
+	pub fn is_locked(&self, clock: &dyn Clock) -> bool {
+		self.lockdate > clock.now()
+	}
+
+	/// The time left until the lockdate according to a clock. This is zero if the lockdate has passed.
+	pub fn get_time_until_unlock(&self, clock: &dyn Clock) -> Duration {
+		(self.lockdate.to_utc() - clock.now()).max(Duration::zero())
+	}
+}
+
+/// Information about a single share of a cipher text using a threshold time lock.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ClientInspectShare {
+	/// The key server holding the key of the share.
+	pub api_url: String,
+	pub lockdate: DateTime<FixedOffset>,
+}
+
+/// The number of a client version as used as the first component of a cipher text.
+impl From<&ClientVersion> for u32 {
+    fn from(version: &ClientVersion) -> u32 {
+		match version {
+			ClientVersion::V1 => 1,
+			ClientVersion::V2 => 2,
+			ClientVersion::V3 => 3,
+			ClientVersion::V4 => 4,
+			ClientVersion::V5 => 5,
+			ClientVersion::V6 => 6,
+		}
+    }
+}
+
+/// This method enables stringifying the client version easily.
+impl fmt::Display for ClientVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}", u32::from(self))
+    }
+}
@@ -0,0 +1,222 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+	format::{
+		ClientInspectResult,
+		ClientInspectShare,
+		ClientVersion,
+		DATETIME_FORMAT,
+	},
+	util::{
+		Analyzer,
+		DefaultAnalyzer,
+	},
+};
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+use serde_json::Value;
+
+/// Inspect a cipher text of any version without accessing the network. This reveals the unencrypted metadata of the
+/// cipher text, i.e. the lockdate, the hint, the filename and the protections in use.
+pub fn inspect(ciphertext: &str) -> Result<ClientInspectResult, String> {
+	let version = DefaultAnalyzer::new().get_version(ciphertext)?;
+
+	match version {
+		ClientVersion::V1 => inspect_v1(ciphertext),
+		ClientVersion::V2 => inspect_v2(ciphertext),
+		ClientVersion::V3 => inspect_v3(ciphertext),
+		ClientVersion::V4 => inspect_v4(ciphertext),
+		ClientVersion::V5 => inspect_v5(ciphertext),
+		ClientVersion::V6 => inspect_v6(ciphertext),
+	}
+}
+
+/// Decode a base64 encoded component of a cipher text.
+pub(crate) fn decode_component(component: &str) -> Result<String, String> {
+    let bytes = base64::decode(component)
+        .map_err(|error| error.to_string())?;
+
+    String::from_utf8(bytes)
+        .map_err(|error| error.to_string())
+}
+
+/// Decode the base64 encoded JSON header of a cipher text.
+pub(crate) fn decode_header(component: &str) -> Result<Value, String> {
+    let header_string = decode_component(component)?;
+
+    serde_json::from_str(header_string.as_str())
+        .map_err(|error| error.to_string())
+}
+
+fn split_components(ciphertext: &str, count: usize) -> Result<Vec<&str>, String> {
+	let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
+
+	if cipher_comp_vec.len() != count {
+		return Err(format!("Cipher is invalid. It must consist of {} components separted by a colon.", count));
+	}
+
+	Ok(cipher_comp_vec)
+}
+
+fn decode_lockdate(component: &str) -> Result<DateTime<FixedOffset>, String> {
+	let lockdate_string = decode_component(component)?;
+
+	DateTime::parse_from_str(lockdate_string.as_str(), DATETIME_FORMAT)
+		.map_err(|error| error.to_string())
+}
+
+fn plain_result(version: ClientVersion, lockdate: DateTime<FixedOffset>, hint: String, filename: String) -> ClientInspectResult {
+	ClientInspectResult {
+		version,
+		lockdate,
+		hint,
+		filename,
+		passphrase_required: false,
+		escape_recipients: Vec::new(),
+		message_id: String::from(""),
+		threshold: 0,
+		shares: Vec::new(),
+		sections: Vec::new(),
+	}
+}
+
+pub(crate) fn inspect_v1(ciphertext: &str) -> Result<ClientInspectResult, String> {
+	let cipher_comp_vec = split_components(ciphertext, 3)?;
+
+	Ok(plain_result(ClientVersion::V1,
+					decode_lockdate(cipher_comp_vec[1])?,
+					String::from(""),
+					String::from("")))
+}
+
+pub(crate) fn inspect_v2(ciphertext: &str) -> Result<ClientInspectResult, String> {
+	let cipher_comp_vec = split_components(ciphertext, 4)?;
+
+	Ok(plain_result(ClientVersion::V2,
+					decode_lockdate(cipher_comp_vec[1])?,
+					decode_component(cipher_comp_vec[3])?,
+					String::from("")))
+}
+
+pub(crate) fn inspect_v3(ciphertext: &str) -> Result<ClientInspectResult, String> {
+	let cipher_comp_vec = split_components(ciphertext, 5)?;
+
+	Ok(plain_result(ClientVersion::V3,
+					decode_lockdate(cipher_comp_vec[1])?,
+					decode_component(cipher_comp_vec[3])?,
+					decode_component(cipher_comp_vec[4])?))
+}
+
+pub(crate) fn inspect_v4(ciphertext: &str) -> Result<ClientInspectResult, String> {
+	let cipher_comp_vec = split_components(ciphertext, 6)?;
+	let header = decode_header(cipher_comp_vec[5])?;
+
+	Ok(ClientInspectResult {
+		passphrase_required: header["passphrase"].is_object(),
+		escape_recipients: header["escape"]
+			.as_array()
+			.map(|escape_headers| escape_headers
+				 .iter()
+				 .map(|escape_header| String::from(escape_header["fingerprint"].as_str().unwrap_or("")))
+				 .collect())
+			.unwrap_or_default(),
+		message_id: String::from(header["message"]["id"].as_str().unwrap_or("")),
+		..plain_result(ClientVersion::V4,
+					   decode_lockdate(cipher_comp_vec[1])?,
+					   decode_component(cipher_comp_vec[3])?,
+					   decode_component(cipher_comp_vec[4])?)
+	})
+}
+
+pub(crate) fn inspect_v5(ciphertext: &str) -> Result<ClientInspectResult, String> {
+	let cipher_comp_vec = split_components(ciphertext, 6)?;
+	let header = decode_header(cipher_comp_vec[5])?;
+
+	/**********************************************************************
+	 * Extract the share layout
+	 */
+    let threshold = header["threshold"].as_u64().unwrap_or(0) as usize;
+    let share_values = header["shares"].as_array()
+        .ok_or_else(|| String::from("Cipher is invalid. The shares are missing."))?;
+
+    let mut shares: Vec<ClientInspectShare> = Vec::new();
+    for share_value in share_values {
+        let share_lockdate = DateTime::parse_from_str(share_value["lockdate"].as_str().unwrap_or(""), DATETIME_FORMAT)
+            .map_err(|error| error.to_string())?;
+
+        shares.push(ClientInspectShare {
+            api_url: String::from(share_value["api_url"].as_str().unwrap_or("")),
+            lockdate: share_lockdate,
+        });
+    }
+
+    if threshold == 0 || threshold > shares.len() || threshold > u8::MAX as usize {
+        return Err(String::from("Cipher is invalid. The threshold does not match the shares."));
+    }
+
+	Ok(ClientInspectResult {
+		threshold,
+		shares,
+		..plain_result(ClientVersion::V5,
+					   decode_lockdate(cipher_comp_vec[1])?,
+					   decode_component(cipher_comp_vec[3])?,
+					   decode_component(cipher_comp_vec[4])?)
+	})
+}
+
+pub(crate) fn inspect_v6(ciphertext: &str) -> Result<ClientInspectResult, String> {
+	let cipher_comp_vec = split_components(ciphertext, 3)?;
+	let lockdate = decode_lockdate(cipher_comp_vec[1])?;
+
+	let mut sections: Vec<ClientInspectResult> = Vec::new();
+	for section_cipher in section_ciphers(ciphertext)? {
+		sections.push(inspect_v4(section_cipher.as_str())?);
+	}
+
+	Ok(ClientInspectResult {
+		passphrase_required: sections.iter().any(|section| section.passphrase_required),
+		sections,
+		..plain_result(ClientVersion::V6, lockdate, String::from(""), String::from(""))
+	})
+}
+
+/// Extract the version 4 cipher texts of the sections of a version 6 cipher text.
+pub(crate) fn section_ciphers(ciphertext: &str) -> Result<Vec<String>, String> {
+	let cipher_comp_vec = split_components(ciphertext, 3)?;
+
+    let sections_bytes = base64::decode(cipher_comp_vec[2])
+        .map_err(|error| error.to_string())?;
+    let sections: Vec<String> = serde_json::from_slice(&sections_bytes)
+        .map_err(|error| error.to_string())?;
+
+    if sections.is_empty() {
+        return Err(String::from("Cipher is invalid. It does not contain any section."));
+    }
+
+    Ok(sections)
+}
@@ -0,0 +1,50 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+//! The network-free core of snailcrypt. It parses and inspects cipher texts of every version without a key server
+//! and is always built, even without any feature.
+
+#[allow(clippy::module_inception)]
+mod format;
+mod inspect;
+
+pub use format::{
+    ClientInspectResult,
+    ClientInspectShare,
+    ClientVersion,
+    DATETIME_FORMAT,
+};
+pub use inspect::inspect;
+#[allow(unused)]
+pub(crate) use inspect::{
+    decode_component,
+    decode_header,
+    inspect_v1,
+    inspect_v2,
+    inspect_v3,
+    inspect_v4,
+    inspect_v5,
+    inspect_v6,
+    section_ciphers,
+};
@@ -22,181 +22,225 @@
   SOFTWARE.
 *******************************************************************************/
 
+#[cfg(feature = "client")]
+pub mod api;
+#[cfg(feature = "client")]
 pub mod client;
+pub mod clock;
+#[cfg(feature = "client")]
 pub mod config;
+#[cfg(feature = "client")]
+mod crypto;
 pub mod factory;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod format;
+pub mod lockdate;
+#[cfg(feature = "python")]
+pub mod python;
+#[cfg(feature = "client")]
+pub mod transport;
 pub mod util;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-use std::{
-    ffi::{
-        c_char,
-        CStr,
-        CString,
-    },
-    str::FromStr,
-    rc::Rc,
-};
-use chrono::{
-    DateTime,
-    FixedOffset,
-};
-
-type SnailcryptEzEncryptionCallback = unsafe extern fn(cipher: *const c_char) -> i32;
-type SnailcryptEzDecryptionCallback = unsafe extern fn(plaintext: *const c_char, hint: *const c_char, filename: *const c_char) -> i32;
-
-#[no_mangle]
-pub unsafe extern fn snailcrypt_ez_encrypt(plaintext: *const c_char, lockdate: *const c_char, hint: *const c_char, filename: *const c_char, callback: SnailcryptEzEncryptionCallback) -> i32 {
-    /**************************************************************************
-     * Convert "plaintext" to "plaintext_orig"
-     */
-    let plaintext_orig_str = unsafe {
-        // TODO: use nice return value
-        CStr::from_ptr(plaintext).to_str().unwrap()
-    };
-    // TODO: use nice return value
-    let plaintext_orig = String::from_str(&plaintext_orig_str).unwrap();
-
-    /**************************************************************************
-     * Convert "lockdate" to "lockdate_orig"
-     */
-    let lockdate_orig_str = unsafe {
-        // TODO: use nice return value
-        CStr::from_ptr(lockdate).to_str().unwrap()
-    };
-    // TODO: use nice return value
-    let lockdate_orig = String::from_str(&lockdate_orig_str).unwrap();
-
-    /**************************************************************************
-     * Convert "hint" to "hint_orig"
-     */
-    let hint_orig_str = unsafe {
-        // TODO: use nice return value
-        CStr::from_ptr(hint).to_str().unwrap()
-    };
-    // TODO: use nice return value
-    let hint_orig = String::from_str(&hint_orig_str).unwrap();
-
-    /**************************************************************************
-     * Convert "filename" to "filename_orig"
-     */
-    let filename_orig_str = unsafe {
-        // TODO: use nice return value
-        CStr::from_ptr(filename).to_str().unwrap()
+#[cfg(test)]
+mod core_tests {
+    use super::*;
+
+    use chrono::{
+        DateTime,
+        Duration,
+        FixedOffset,
     };
-    // TODO: use nice return value
-    let filename_orig = String::from_str(&filename_orig_str).unwrap();
-
-    /**************************************************************************
-     * Get an "anylzer" from an "analyzer_factory"
-     */
-    let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-    let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
-
-    /**************************************************************************
-     * Get a "config" from a "config_factory"
-     */
-    let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-    let config: Rc<dyn config::Config> = config_factory.create();
-
-    /**************************************************************************
-     * Get a "client" from a "client_factory" using:
-     * 1. An "analyzer"
-     * 2. A "config"
-     */
-    let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                    Rc::clone(&config));
-    let client: Rc<dyn client::Client> = client_factory.create();
-
-    /**************************************************************************
-     * Interpret the string in "lockdate_orig" to retrieve an actual 
-     * structured object "lockdate"
-     */
-    let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str(lockdate_orig.as_str(),
-                                                                    client.get_datetime_format())
-        .unwrap_or_else(|error| {
-        panic!("Error: {:?}", error);
-    });
-
-    /**************************************************************************
-     * Perform the encryption
-     */
-    let cipher: String = client.encrypt(&client::ClientEncryptArg {
-        plaintext: plaintext_orig.clone(),
-        lockdate,
-        hint: hint_orig.clone(),
-        filename: filename_orig.clone(),
-    }).unwrap_or_else(|error| {
-        panic!("Error: {:?}", error);
-    });
-
-    /**************************************************************************
-     * Pass "cipher" to "callback"
-     */
-    // TODO: use nice return value
-    let cipher_cstring = CString::new(cipher.as_str()).unwrap();
-    callback(cipher_cstring.as_ptr())
+    use serde_json::json;
+
+    fn at(datetime: &str) -> DateTime<FixedOffset> {
+        DateTime::parse_from_rfc3339(datetime).unwrap()
+    }
+
+    fn component(text: &str) -> String {
+        base64::encode(text)
+    }
+
+    #[test]
+    fn inspect_plain_versions() {
+        let lockdate = component("2030-01-01T12:00:00+0100");
+
+        /* Version 1 only carries the lockdate */
+        let inspect_result = format::inspect(format!("1:{}:cGF5bG9hZA==", lockdate).as_str()).unwrap();
+        assert_eq!(format::ClientVersion::V1, inspect_result.version);
+        assert_eq!(1, u32::from(&inspect_result.version));
+        assert_eq!(at("2030-01-01T12:00:00+01:00"), inspect_result.lockdate);
+        assert_eq!("", inspect_result.hint);
+
+        /* Version 2 adds the hint, version 3 the filename */
+        let inspect_result = format::inspect(format!("2:{}:cGF5bG9hZA==:{}", lockdate, component("A hint")).as_str()).unwrap();
+        assert_eq!(format::ClientVersion::V2, inspect_result.version);
+        assert_eq!("A hint", inspect_result.hint);
+
+        let inspect_result = format::inspect(format!("3:{}:cGF5bG9hZA==:{}:{}", lockdate, component("A hint"),
+                                                     component("report.pdf")).as_str()).unwrap();
+        assert_eq!(format::ClientVersion::V3, inspect_result.version);
+        assert_eq!("3", inspect_result.version.to_string());
+        assert_eq!("report.pdf", inspect_result.filename);
+        assert!(!inspect_result.passphrase_required);
+        assert_eq!(0, inspect_result.threshold);
+
+        /* Malformed cipher texts */
+        assert!(format::inspect(format!("3:{}:cGF5bG9hZA==", lockdate).as_str()).is_err());
+        assert!(format::inspect("1:not*base64:cGF5bG9hZA==").is_err());
+        assert!(format::inspect("9:asdf:asdf").is_err());
+    }
+
+    #[test]
+    fn inspect_protected_versions() {
+        let lockdate = component("2030-01-01T12:00:00+0100");
+
+        /* Version 4 reveals its protections */
+        let header = json!({
+            "passphrase": { "salt": "c2FsdA==" },
+            "escape": [ { "fingerprint": "ab:cd" } ],
+            "message": { "id": "42" },
+        });
+        let cipher = format!("4:{}:cGF5bG9hZA==:{}:{}:{}", lockdate, component("A hint"), component(""),
+                             component(header.to_string().as_str()));
+        let inspect_result = format::inspect(cipher.as_str()).unwrap();
+        assert_eq!(format::ClientVersion::V4, inspect_result.version);
+        assert!(inspect_result.passphrase_required);
+        assert_eq!(vec![String::from("ab:cd")], inspect_result.escape_recipients);
+        assert_eq!("42", inspect_result.message_id);
+
+        /* Version 5 reveals its shares */
+        let header = json!({
+            "threshold": 2,
+            "shares": [
+                { "api_url": "https://a.example.com", "lockdate": "2030-01-01T12:00:00+0100" },
+                { "api_url": "https://b.example.com", "lockdate": "2030-01-02T12:00:00+0100" },
+            ],
+        });
+        let cipher = format!("5:{}:cGF5bG9hZA==:{}:{}:{}", lockdate, component(""), component(""),
+                             component(header.to_string().as_str()));
+        let inspect_result = format::inspect(cipher.as_str()).unwrap();
+        assert_eq!(2, inspect_result.threshold);
+        assert_eq!("https://b.example.com", inspect_result.shares[1].api_url);
+        assert_eq!(at("2030-01-02T12:00:00+01:00"), inspect_result.shares[1].lockdate);
+
+        /* A threshold above the number of shares is invalid */
+        let header = json!({ "threshold": 3, "shares": header["shares"] });
+        let cipher = format!("5:{}:cGF5bG9hZA==:{}:{}:{}", lockdate, component(""), component(""),
+                             component(header.to_string().as_str()));
+        assert!(format::inspect(cipher.as_str()).is_err());
+    }
+
+    #[test]
+    fn inspect_lock_state() {
+        let cipher = format!("1:{}:cGF5bG9hZA==", component("2030-01-01T12:00:00+0000"));
+        let inspect_result = format::inspect(cipher.as_str()).unwrap();
+
+        let manual_clock = clock::ManualClock::new(at("2030-01-01T10:00:00Z").to_utc());
+        assert!(inspect_result.is_locked(&manual_clock));
+        assert_eq!(Duration::hours(2), inspect_result.get_time_until_unlock(&manual_clock));
+
+        manual_clock.advance(Duration::hours(3));
+        assert!(!inspect_result.is_locked(&manual_clock));
+        assert_eq!(Duration::zero(), inspect_result.get_time_until_unlock(&manual_clock));
+    }
+
+    #[test]
+    fn lockdate_parse_core() {
+        let now = at("2030-01-01T12:00:00+01:00");
+
+        assert_eq!(at("2030-06-01T00:00:00Z"), lockdate::Lockdate::parse_at("2030-06-01T00:00:00Z", now).unwrap().get_datetime());
+        assert_eq!(at("2030-06-01T00:00:00+01:00"), lockdate::Lockdate::parse_at("2030-06-01T00:00:00+0100", now).unwrap().get_datetime());
+        assert_eq!(at("2030-01-01T13:30:00+01:00"), lockdate::Lockdate::parse_at("+90m", now).unwrap().get_datetime());
+        assert_eq!(at("2030-01-02T09:00:00+01:00"), lockdate::Lockdate::parse_at("tomorrow 09:00", now).unwrap().get_datetime());
+        assert!(lockdate::Lockdate::parse_at("someday", now).is_err());
+        assert!(lockdate::Lockdate::parse_at("+", now).is_err());
+
+        /* Digits alone are no timestamp, as a mistyped date would lock until 1970 */
+        assert_eq!(at("2033-05-18T03:33:20Z"), lockdate::Lockdate::parse_at("@2000000000", now).unwrap().get_datetime());
+        assert!(lockdate::Lockdate::parse_at("20301231", now).unwrap_err().contains("\"@20301231\""));
+
+        /* Relative lockdates follow the clock */
+        let manual_clock = clock::ManualClock::new(at("2030-01-01T11:00:00Z").to_utc());
+        let parse_with_clock = |input: &str| lockdate::Lockdate::parse_with_clock(input, &manual_clock).unwrap().get_datetime();
+        assert_eq!(at("2030-01-01T12:30:00Z"), parse_with_clock("+90m"));
+        manual_clock.advance(Duration::days(1));
+        assert_eq!(at("2030-01-02T12:30:00Z"), parse_with_clock("+90m"));
+        assert_eq!(at("2030-01-03T09:00:00Z"), parse_with_clock("tomorrow 09:00 UTC"));
+
+        /* Policies work without a client */
+        let mut policy = lockdate::LockdatePolicy::new();
+        policy.set_min_lead_time(Some(Duration::minutes(5)));
+        policy.set_granularity(Some(Duration::hours(1)));
+        assert_eq!(at("2030-01-01T14:00:00+01:00"), policy.apply(at("2030-01-01T13:30:00+01:00"), now).unwrap());
+        assert!(matches!(policy.apply(at("2030-01-01T12:00:00+01:00"), now),
+                         Err(lockdate::LockdatePolicyError::Past { .. })));
+    }
 }
 
-#[no_mangle]
-pub extern fn snailcrypt_ez_decrypt(cipher: *const c_char, callback: SnailcryptEzDecryptionCallback) -> i32 {
-    /**************************************************************************
-     * Convert "cipher" to "cipher_orig"
-     */
-    let cipher_orig_str = unsafe {
-        // TODO: use nice return value
-        CStr::from_ptr(cipher).to_str().unwrap()
-    };
-    // TODO: use nice return value
-    let cipher_orig = String::from_str(&cipher_orig_str).unwrap();
-
-    /**************************************************************************
-     * Get an "analyzer" from an "analyzer_factory"
-     */
-    let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-    let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
-
-    /**************************************************************************
-     * Get a "config" from a "config_factory"
-     */
-    let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-    let config: Rc<dyn config::Config> = config_factory.create();
-
-    /**************************************************************************
-     * Get a "client" from a "client_factory" using:
-     * 1. An "analyzer"
-     * 2. A "config"
-     */
-    let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                    Rc::clone(&config));
-    let client: Rc<dyn client::Client> = client_factory.create();
-
-
-    let result_success = client
-        .decrypt(cipher_orig.as_str())
-        .unwrap_or_else(|error| {
-            panic!("Error: {:?}", error.error_message);
-        });
-
-    /**************************************************************************
-     * Pass "result_success" to "callback"
-     */
-    // TODO: use nice return value
-    let plaintext_cstring = CString::new(result_success.plaintext.as_str()).unwrap();
-    let hint_cstring = CString::new(result_success.hint.as_str()).unwrap();
-    let filename_cstring = CString::new(result_success.filename.as_str()).unwrap();
-    unsafe { 
-        callback(plaintext_cstring.as_ptr(), hint_cstring.as_ptr(), filename_cstring.as_ptr()) 
+#[cfg(all(test, target_arch = "wasm32", feature = "wasm"))]
+mod wasm_tests {
+    use super::*;
+
+    use js_sys::Function;
+    use wasm_bindgen::JsCast;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    #[wasm_bindgen_test]
+    fn inspect_without_transport() {
+        let client = wasm::WasmClient::new(Function::new_no_args("throw new Error('The network must not be used.');"),
+                                           None,
+                                           None);
+
+        let inspect_result = client.inspect("3:Mjk5OS0xMS0xOVQxNzowMDowMCswMTAw:cGF5bG9hZA==:QSBoaW50:cmVwb3J0LnBkZg==")
+            .unwrap();
+        assert_eq!(3, inspect_result.version);
+        assert_eq!("report.pdf", inspect_result.filename);
+        assert!(inspect_result.locked);
+    }
+
+    #[wasm_bindgen_test]
+    fn transport_failures_are_thrown() {
+        /* The transport function is called synchronously and its answer is parsed like a key server response */
+        let transport = Function::new_with_args("url, body, timeoutMs",
+                                                "globalThis.snailcryptCalls = (globalThis.snailcryptCalls || 0) + 1; \
+                                                 return '{\"code\": 503, \"message\": \"unavailable\"}';");
+        let client = wasm::WasmClient::new(transport, Some(String::from("https://keys.example.com")), None);
+
+        let error = client.encrypt("Hello World", "2030-01-01T12:00:00Z", None, None, None).unwrap_err();
+        assert_eq!("EncryptionError", String::from(error.unchecked_into::<js_sys::Error>().name()));
+
+        let calls = js_sys::Reflect::get(&js_sys::global(), &"snailcryptCalls".into()).unwrap();
+        assert_eq!(Some(1.0), calls.as_f64());
+
+        /* Asynchronous transport functions are not supported */
+        let transport = Function::new_with_args("url, body, timeoutMs", "return Promise.resolve('{}');");
+        let client = wasm::WasmClient::new(transport, None, None);
+        let error = client.encrypt("Hello World", "2030-01-01T12:00:00Z", None, None, None).unwrap_err();
+        assert!(String::from(error.unchecked_into::<js_sys::Error>().message()).contains("string or an Uint8Array"));
+    }
+
+    #[wasm_bindgen_test]
+    fn lockdate_policy_is_thrown() {
+        let client = wasm::WasmClient::new(Function::new_no_args("throw new Error('The network must not be used.');"),
+                                           None,
+                                           Some(wasm::WasmLockdatePolicy::new(Some(0.0), None, None).unwrap()));
+
+        let error = client.encrypt("Hello World", "2022-11-19T17:00:00+01:00", None, None, None).unwrap_err();
+        assert_eq!("LockdatePolicyError", String::from(error.unchecked_into::<js_sys::Error>().name()));
     }
 }
 
-#[cfg(test)]
+#[cfg(all(test, feature = "client", not(target_arch = "wasm32")))]
 mod tests {
     use super::*;
     
     use std::{
         fs,
         path::Path,
-        rc::Rc,
+        sync::Arc,
     };
 
     use chrono::{
@@ -243,9 +287,9 @@ mod tests {
 							   "Unknown client version: ");
 							 
 					error_thrown = true;								
-					return client::ClientVersion::V1;
+					client::ClientVersion::V1
 				});
-		assert_eq!(error_thrown, true);
+		assert!(error_thrown);
 				
 		/* Actual cipher text */			
 		error_thrown = false;
@@ -256,9 +300,9 @@ mod tests {
 							 
 					error_thrown = true;
 					
-					return client::ClientVersion::V1;
+					client::ClientVersion::V1
 				});		
-		assert_eq!(error_thrown, true);
+		assert!(error_thrown);
 	}
 
     #[test]
@@ -266,14 +310,14 @@ mod tests {
         let plaintext_orig: String = String::from("hello world");
 
  		let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
    
 		let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
   
-		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
 
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -286,6 +330,8 @@ mod tests {
         	lockdate,
         	hint: String::from(""),
         	filename: String::from(""),
+        	passphrase: String::from(""),
+        	escape_recipients: Vec::new(),
         }).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
@@ -319,14 +365,14 @@ ultrices dolor, at vulputate neque nulla lacinia eros.  Sed id ligula
 quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
        	
        	let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
         
 		let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
           
-		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
 
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -339,6 +385,8 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         	lockdate,
         	hint: String::from(""),
         	filename: String::from(""),
+        	passphrase: String::from(""),
+        	escape_recipients: Vec::new(),
 		}).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
@@ -363,14 +411,14 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         let plaintext_orig: String = String::from("hello world");
 
 		let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
         
 		let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
                 
-		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
 
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -383,6 +431,8 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         	lockdate,
         	hint: String::from(""),
         	filename: String::from(""),
+        	passphrase: String::from(""),
+        	escape_recipients: Vec::new(),
 		}).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
@@ -438,9 +488,9 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
 							   "Unknown client version: ");
 							 
 					error_thrown = true;								
-					return client::ClientVersion::V2;
+					client::ClientVersion::V2
 				});
-		assert_eq!(error_thrown, true);
+		assert!(error_thrown);
 				
 		/* Actual cipher text */			
 		error_thrown = false;
@@ -451,9 +501,9 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
 							 
 					error_thrown = true;
 					
-					return client::ClientVersion::V2;
+					client::ClientVersion::V2
 				});		
-		assert_eq!(error_thrown, true);
+		assert!(error_thrown);
 	}
 
     
@@ -463,14 +513,14 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         let hint_orig = String::from("This is a test message");
 
  		let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
    
 		let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
         
-		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
 
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -483,6 +533,8 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         	lockdate,
         	hint: hint_orig.clone(),
             filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
         }).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
@@ -528,14 +580,14 @@ ultrices dolor, at vulputate neque nulla lacinia eros.  Sed id ligula
 quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
 
  		let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
    
 		let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
         
-		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
 
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -547,7 +599,9 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         	plaintext: plaintext_orig.clone(),
         	lockdate,
         	hint: hint_orig.clone(),
-            filename: String::from("")
+            filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
         }).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
@@ -604,9 +658,9 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
 							   "Unknown client version: ");
 							 
 					error_thrown = true;								
-					return client::ClientVersion::V3;
+					client::ClientVersion::V3
 				});
-		assert_eq!(error_thrown, true);
+		assert!(error_thrown);
 				
 		/* Actual cipher text */			
 		error_thrown = false;
@@ -617,16 +671,16 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
 							 
 					error_thrown = true;
 					
-					return client::ClientVersion::V3;
+					client::ClientVersion::V3
 				});		
-		assert_eq!(error_thrown, true);
+		assert!(error_thrown);
 	}
 
     #[test]
     fn encrypt_v3_small_str() {
         let filename_orig = String::from("encrypt_v3_small_str.txt");
 
-        let mut path_string = String::from(option_env!("CARGO_MANIFEST_DIR").unwrap());
+        let mut path_string = String::from(env!("CARGO_MANIFEST_DIR"));
         path_string.push_str("/test/");
         path_string.push_str(filename_orig.as_str());
 
@@ -637,14 +691,14 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         let hint_orig = String::from("This is a test message");
 
  		let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
    
 		let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
         
-		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
 
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -657,6 +711,8 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         	lockdate,
         	hint: hint_orig.clone(),
             filename: filename_orig.clone(),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
         }).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
@@ -683,7 +739,7 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
     fn encrypt_v3_larg_str() {
 		let filename_orig = String::from("encrypt_v3_large_str.pdf");
 
-        let mut path_string = String::from(option_env!("CARGO_MANIFEST_DIR").unwrap());
+        let mut path_string = String::from(env!("CARGO_MANIFEST_DIR"));
         path_string.push_str("/test/");
         path_string.push_str(filename_orig.as_str());
 
@@ -704,14 +760,14 @@ ultrices dolor, at vulputate neque nulla lacinia eros.  Sed id ligula
 quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
 
  		let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
    
 		let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
         
-		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+		let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
 
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -724,6 +780,8 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         	lockdate,
         	hint: hint_orig.clone(),
             filename: filename_orig.clone(),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
         }).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
@@ -751,14 +809,14 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         let plaintext_orig: String = String::from("hello world");
 
         let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
    
         let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
   
-        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
 
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -771,11 +829,13 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
             lockdate,
             hint: String::from(""),
             filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
         }).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
 
-        let client_decryption: Rc<dyn client::Client> = client_factory.create();
+        let client_decryption: Arc<dyn client::Client> = client_factory.create();
         let result_success = client_decryption
             .decrypt(cipher
                 .as_str())
@@ -805,14 +865,14 @@ ultrices dolor, at vulputate neque nulla lacinia eros.  Sed id ligula
 quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
            
            let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
-        let analyzer: Rc<dyn util::Analyzer> = analyzer_factory.create();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
         
         let config_factory: factory::ConfigFactory = factory::ConfigFactory::new();
-        let config: Rc<dyn config::Config> = config_factory.create();
+        let config: Arc<dyn config::Config> = config_factory.create();
           
-        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Rc::clone(&analyzer),
-                                        Rc::clone(&config));
-        let client: Rc<dyn client::Client> = client_factory.create();
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
         
         let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
                                                                        client.get_datetime_format())
@@ -825,11 +885,13 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
             lockdate,
             hint: String::from(""),
             filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
         }).unwrap_or_else(|error| {
             panic!("Error: {:?}", error);
         });
 
-        let client_decryption: Rc<dyn client::Client> = client_factory.create();
+        let client_decryption: Arc<dyn client::Client> = client_factory.create();
         let result_success = client_decryption
             .decrypt(cipher
                .as_str())
@@ -843,4 +905,2374 @@ quis est convallis tempor.  Curabitur lacinia pulvinar nibh.  Nam a sapien.");
         assert_eq!("",
                    result_success.hint.as_str());
     }
+
+    #[test]
+    fn version_v4_parse_ok() {
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer = analyzer_factory.create();
+
+        /* Arbitary text */
+        assert!(analyzer.get_version("4:asdf:asdf:asdf:asdf:asdf")
+                           .unwrap_or_else(|error| {
+                                panic!("Error: {:?}", error);
+                            })
+                ==
+                client::ClientVersion::V4);
+    }
+
+    #[test]
+    fn encrypt_v4_passphrase() {
+        let plaintext_orig: String = String::from("hello world");
+        let hint_orig: String = String::from("This is a test message");
+        let filename_orig: String = String::from("hello.txt");
+        let passphrase_orig: String = String::from("correct horse battery staple");
+
+        let server = util::StandInServer::start();
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
+                                                                       client.get_datetime_format())
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let cipher: String = client.encrypt(&client::ClientEncryptArg {
+            plaintext: plaintext_orig.clone(),
+            lockdate,
+            hint: hint_orig.clone(),
+            filename: filename_orig.clone(),
+            passphrase: passphrase_orig.clone(),
+            escape_recipients: Vec::new(),
+        }).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        /* Inspection works offline and reports the passphrase */
+        let inspect_result = client
+            .inspect(cipher.as_str())
+            .unwrap_or_else(|error| {
+                panic!("Error: {:?}", error);
+            });
+        assert_eq!(client::ClientVersion::V4, inspect_result.version);
+        assert_eq!(lockdate, inspect_result.lockdate);
+        assert_eq!(hint_orig.as_str(), inspect_result.hint.as_str());
+        assert_eq!(filename_orig.as_str(), inspect_result.filename.as_str());
+        assert!(inspect_result.passphrase_required);
+
+        /* Missing passphrase */
+        let result_failure = client
+            .decrypt(cipher.as_str())
+            .unwrap_err();
+        assert_eq!(client::ClientDecryptErrorKind::PassphraseRequired, result_failure.kind);
+        assert_eq!(hint_orig.as_str(), result_failure.hint.as_str());
+
+        /* Wrong passphrase */
+        let result_failure = client
+            .decrypt_with_passphrase(cipher.as_str(), "wrong passphrase")
+            .unwrap_err();
+        assert_eq!(client::ClientDecryptErrorKind::WrongPassphrase, result_failure.kind);
+
+        /* Correct passphrase */
+        let result_success = client
+            .decrypt_with_passphrase(cipher.as_str(), passphrase_orig.as_str())
+            .unwrap_or_else(|error| {
+                panic!("Error: {:?}", error.error_message);
+            });
+
+        assert_eq!(plaintext_orig.as_str(),
+                   result_success.plaintext.as_str());
+
+        assert_eq!(hint_orig.as_str(),
+                   result_success.hint.as_str());
+
+        assert_eq!(filename_orig.as_str(),
+                   result_success.filename.as_str());
+
+        /* Crafted cost parameters are rejected before deriving the key */
+        for (name, cost) in [("time_cost", 1u64 << 20), ("parallelism", 1u64 << 20), ("time_cost", 1u64 << 33)] {
+            let mut cipher_comp_vec: Vec<String> = cipher.split(':').map(String::from).collect();
+            let mut header: serde_json::Value = serde_json::from_slice(&base64::decode(cipher_comp_vec[5].as_str()).unwrap()).unwrap();
+            header["passphrase"][name] = serde_json::Value::from(cost);
+            cipher_comp_vec[5] = base64::encode(header.to_string());
+
+            let result_failure = client
+                .decrypt_with_passphrase(cipher_comp_vec.join(":").as_str(), passphrase_orig.as_str())
+                .unwrap_err();
+            assert!(result_failure.error_message.contains("too high"), "{}", result_failure.error_message);
+        }
+    }
+
+    #[test]
+    fn encrypt_v4_passphrase_locked() {
+        let plaintext_orig: String = String::from("hello world");
+        let passphrase_orig: String = String::from("correct horse battery staple");
+
+        let server = util::StandInServer::start();
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2999-11-19T17:00:00+0100",
+                                                                       client.get_datetime_format())
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let cipher: String = client.encrypt(&client::ClientEncryptArg {
+            plaintext: plaintext_orig.clone(),
+            lockdate,
+            hint: String::from(""),
+            filename: String::from(""),
+            passphrase: passphrase_orig.clone(),
+            escape_recipients: Vec::new(),
+        }).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        /* The passphrase alone is not enough before the lockdate */
+        let result_failure = client
+            .decrypt_with_passphrase(cipher.as_str(), passphrase_orig.as_str())
+            .unwrap_err();
+        assert_eq!(client::ClientDecryptErrorKind::NotYetReleased, result_failure.kind);
+    }
+
+    #[test]
+    fn encrypt_v5_threshold() {
+        use client::Client;
+
+        let plaintext_orig: String = String::from("hello world");
+        let hint_orig: String = String::from("This is a test message");
+
+        let servers = [
+            util::StandInServer::start(),
+            util::StandInServer::start(),
+            util::StandInServer::start(),
+        ];
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let configs: Vec<Arc<dyn config::Config>> = servers
+            .iter()
+            .map(|server| Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url())) as Arc<dyn config::Config>)
+            .collect();
+
+        let threshold_client = client::V5Client::new(Arc::clone(&analyzer), configs.clone(), 2);
+
+        let lockdates: Vec<DateTime<FixedOffset>> = vec![
+            DateTime::parse_from_str("2022-11-19T17:00:00+0100", threshold_client.get_datetime_format()).unwrap(),
+            DateTime::parse_from_str("2999-11-19T17:00:00+0100", threshold_client.get_datetime_format()).unwrap(),
+            DateTime::parse_from_str("2022-11-20T17:00:00+0100", threshold_client.get_datetime_format()).unwrap(),
+        ];
+
+        let cipher: String = threshold_client.encrypt_threshold(&client::V5EncryptArg {
+            plaintext: plaintext_orig.clone(),
+            hint: hint_orig.clone(),
+            filename: String::from(""),
+            threshold: 2,
+            shares: configs
+                .iter()
+                .zip(lockdates.iter())
+                .map(|(config, lockdate)| client::V5EncryptShareArg {
+                    config: Arc::clone(config),
+                    lockdate: *lockdate,
+                })
+                .collect(),
+        }).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        /* The share layout is visible without the network */
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&configs[0]));
+        let client: Arc<dyn client::Client> = client_factory.create();
+
+        let inspect_result = client
+            .inspect(cipher.as_str())
+            .unwrap_or_else(|error| {
+                panic!("Error: {:?}", error);
+            });
+        assert_eq!(client::ClientVersion::V5, inspect_result.version);
+        assert_eq!(lockdates[2], inspect_result.lockdate);
+        assert_eq!(hint_orig.as_str(), inspect_result.hint.as_str());
+        assert_eq!(2, inspect_result.threshold);
+        assert_eq!(3, inspect_result.shares.len());
+        for ((share, server), lockdate) in inspect_result.shares.iter().zip(servers.iter()).zip(lockdates.iter()) {
+            assert_eq!(server.get_api_url(), share.api_url.as_str());
+            assert_eq!(*lockdate, share.lockdate);
+        }
+
+        /* Key servers named by the cipher text are only contacted if configured or allowed */
+        let result_failure = client
+            .decrypt(cipher.as_str())
+            .unwrap_err();
+        assert!(result_failure.error_message.contains("neither configured nor allowed"));
+        assert_eq!(client::ClientDecryptErrorKind::Generic, result_failure.kind);
+
+        let mut config = config::DefaultConfig::new_with_api_url(servers[0].get_api_url());
+        config.set_allowed_api_urls(servers[1..].iter().map(|server| String::from(server.get_api_url())).collect());
+        let client: Arc<dyn client::Client> = factory::ClientFactory::new(Arc::clone(&analyzer), Arc::new(config)).create();
+
+        /* Two released shares are enough */
+        let result_success = client
+            .decrypt(cipher.as_str())
+            .unwrap_or_else(|error| {
+                panic!("Error: {:?}", error.error_message);
+            });
+
+        assert_eq!(plaintext_orig.as_str(),
+                   result_success.plaintext.as_str());
+
+        assert_eq!(hint_orig.as_str(),
+                   result_success.hint.as_str());
+    }
+
+    #[test]
+    fn encrypt_v5_threshold_locked() {
+        use client::Client;
+
+        let plaintext_orig: String = String::from("hello world");
+
+        let servers = [
+            util::StandInServer::start(),
+            util::StandInServer::start(),
+        ];
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let configs: Vec<Arc<dyn config::Config>> = servers
+            .iter()
+            .map(|server| Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url())) as Arc<dyn config::Config>)
+            .collect();
+
+        let threshold_client = client::V5Client::new(Arc::clone(&analyzer), configs.clone(), 2);
+
+        let cipher: String = threshold_client.encrypt_threshold(&client::V5EncryptArg {
+            plaintext: plaintext_orig.clone(),
+            hint: String::from(""),
+            filename: String::from(""),
+            threshold: 2,
+            shares: vec![
+                client::V5EncryptShareArg {
+                    config: Arc::clone(&configs[0]),
+                    lockdate: DateTime::parse_from_str("2022-11-19T17:00:00+0100", threshold_client.get_datetime_format()).unwrap(),
+                },
+                client::V5EncryptShareArg {
+                    config: Arc::clone(&configs[1]),
+                    lockdate: DateTime::parse_from_str("2999-11-19T17:00:00+0100", threshold_client.get_datetime_format()).unwrap(),
+                },
+            ],
+        }).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        /* A single released share does not reveal anything */
+        let result_failure = threshold_client
+            .decrypt(cipher.as_str())
+            .unwrap_err();
+        assert!(result_failure.error_message.starts_with("Only 1 of 2 required shares"));
+        assert_eq!(client::ClientDecryptErrorKind::NotYetReleased, result_failure.kind);
+
+        /* Invalid thresholds are rejected */
+        assert!(threshold_client.encrypt_threshold(&client::V5EncryptArg {
+            plaintext: plaintext_orig.clone(),
+            hint: String::from(""),
+            filename: String::from(""),
+            threshold: 3,
+            shares: vec![
+                client::V5EncryptShareArg {
+                    config: Arc::clone(&configs[0]),
+                    lockdate: DateTime::parse_from_str("2022-11-19T17:00:00+0100", threshold_client.get_datetime_format()).unwrap(),
+                },
+            ],
+        }).is_err());
+    }
+
+    #[test]
+    fn encrypt_v6_sections() {
+        use client::Client;
+
+        let server = util::StandInServer::start();
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+
+        let staged_client = client::V6Client::new(Arc::clone(&analyzer), Arc::clone(&config));
+
+        let lockdate_monday: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-14T09:00:00+0100",
+                                                                              staged_client.get_datetime_format())
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+        let lockdate_friday: DateTime<FixedOffset> = DateTime::parse_from_str("2999-11-18T09:00:00+0100",
+                                                                              staged_client.get_datetime_format())
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let cipher: String = staged_client.encrypt_sections(&[
+            client::ClientEncryptArg {
+                plaintext: String::from("Part A"),
+                lockdate: lockdate_friday,
+                hint: String::from("Opens on Friday"),
+                filename: String::from(""),
+                passphrase: String::from(""),
+                escape_recipients: Vec::new(),
+            },
+            client::ClientEncryptArg {
+                plaintext: String::from("Part B"),
+                lockdate: lockdate_monday,
+                hint: String::from("Opens on Monday"),
+                filename: String::from(""),
+                passphrase: String::from(""),
+                escape_recipients: Vec::new(),
+            },
+        ]).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        /* Inspection lists the sections */
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
+
+        let inspect_result = client
+            .inspect(cipher.as_str())
+            .unwrap_or_else(|error| {
+                panic!("Error: {:?}", error);
+            });
+        assert_eq!(client::ClientVersion::V6, inspect_result.version);
+        assert_eq!(lockdate_monday, inspect_result.lockdate);
+        assert_eq!(2, inspect_result.sections.len());
+        assert_eq!("Opens on Friday", inspect_result.sections[0].hint.as_str());
+        assert_eq!(lockdate_friday, inspect_result.sections[0].lockdate);
+
+        /* A single plain text is not available for several sections */
+        assert!(client.decrypt(cipher.as_str()).is_err());
+
+        /* Released sections are decrypted, locked ones are reported */
+        let results = staged_client
+            .decrypt_sections(cipher.as_str())
+            .unwrap_or_else(|error| {
+                panic!("Error: {:?}", error);
+            });
+        assert_eq!(2, results.len());
+
+        match &results[0] {
+            client::V6SectionResult::Locked(section) => {
+                assert_eq!(lockdate_friday, section.lockdate);
+                assert_eq!("Opens on Friday", section.hint.as_str());
+            },
+            result => panic!("Unexpected result: {:?}", result),
+        }
+
+        match &results[1] {
+            client::V6SectionResult::Released(section) => {
+                assert_eq!("Part B", section.plaintext.as_str());
+                assert_eq!("Opens on Monday", section.hint.as_str());
+            },
+            result => panic!("Unexpected result: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn encrypt_v6_sections_bound() {
+        let server = util::StandInServer::start();
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+
+        let staged_client = client::V6Client::new(Arc::clone(&analyzer), Arc::clone(&config));
+
+        let lockdate_monday: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-14T09:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+        let lockdate_tuesday: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-15T09:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let section = |plaintext: &str, lockdate: DateTime<FixedOffset>| client::ClientEncryptArg {
+            plaintext: String::from(plaintext),
+            lockdate,
+            hint: String::from(""),
+            filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
+        };
+
+        let cipher: String = staged_client.encrypt_sections(&[
+            section("Part A", lockdate_tuesday),
+            section("Part B", lockdate_monday),
+        ]).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let released = |ciphertext: &str| -> usize {
+            staged_client
+                .decrypt_sections(ciphertext)
+                .unwrap_or_else(|error| {
+                    panic!("Error: {:?}", error);
+                })
+                .iter()
+                .filter(|result| matches!(result, client::V6SectionResult::Released(_)))
+                .count()
+        };
+        assert_eq!(2, released(cipher.as_str()));
+
+        let cipher_comp_vec: Vec<&str> = cipher.split(':').collect();
+        let section_ciphers = format::section_ciphers(cipher.as_str()).unwrap();
+        let with_sections = |sections: Vec<String>| format!("{}:{}:{}",
+                                                            cipher_comp_vec[0],
+                                                            cipher_comp_vec[1],
+                                                            base64::encode(serde_json::Value::from(sections).to_string()));
+
+        /* Sections cannot be reordered */
+        let reordered = with_sections(vec![section_ciphers[1].clone(), section_ciphers[0].clone()]);
+        assert_eq!(0, released(reordered.as_str()));
+
+        /* Sections cannot be dropped */
+        let dropped = with_sections(vec![section_ciphers[0].clone()]);
+        assert_eq!(0, released(dropped.as_str()));
+        {
+            use client::Client;
+            assert!(staged_client.decrypt(dropped.as_str()).is_err());
+        }
+
+        /* The lockdate of the cipher text cannot be changed */
+        let relocked = format!("{}:{}:{}",
+                               cipher_comp_vec[0],
+                               base64::encode("2999-11-18T09:00:00+0100"),
+                               cipher_comp_vec[2]);
+        assert_eq!(0, released(relocked.as_str()));
+
+        /* A single section is decrypted as a whole */
+        {
+            use client::Client;
+            let single: String = staged_client.encrypt(&section("Part C", lockdate_monday)).unwrap_or_else(|error| {
+                panic!("Error: {:?}", error);
+            });
+            assert_eq!("Part C", staged_client.decrypt(single.as_str()).unwrap().plaintext.as_str());
+        }
+
+        /* A section cannot be decrypted on its own */
+        {
+            use client::Client;
+            let v4_client = client::V4Client::new(Arc::clone(&analyzer), Arc::clone(&config));
+            assert!(v4_client.decrypt(section_ciphers[0].as_str()).is_err());
+        }
+    }
+
+    #[test]
+    fn encrypt_v4_escape_recipient() {
+        let plaintext_orig: String = String::from("hello world");
+
+        let server = util::StandInServer::start();
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                        Arc::clone(&config));
+        let client: Arc<dyn client::Client> = client_factory.create();
+
+        let escape_key = openssl::rsa::Rsa::generate(2048).unwrap();
+        let escape_public_pem = String::from_utf8(escape_key.public_key_to_pem().unwrap()).unwrap();
+        let escape_private_pem = String::from_utf8(escape_key.private_key_to_pem().unwrap()).unwrap();
+
+        let other_key = openssl::rsa::Rsa::generate(2048).unwrap();
+        let other_private_pem = String::from_utf8(other_key.private_key_to_pem().unwrap()).unwrap();
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2999-11-19T17:00:00+0100",
+                                                                       client.get_datetime_format())
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let cipher: String = client.encrypt(&client::ClientEncryptArg {
+            plaintext: plaintext_orig.clone(),
+            lockdate,
+            hint: String::from(""),
+            filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: vec![escape_public_pem],
+        }).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let inspect_result = client.inspect(cipher.as_str()).unwrap();
+        assert_eq!(client::ClientVersion::V4, inspect_result.version);
+        assert_eq!(1, inspect_result.escape_recipients.len());
+        assert_eq!(lockdate, client.lockdate_from_snailcrypt_cipher(cipher.as_str()).unwrap());
+
+        /* Malformed cipher texts are reported instead of panicking */
+        assert!(client.lockdate_from_snailcrypt_cipher("1:cGF5bG9hZA==").is_err());
+        assert!(client.lockdate_from_snailcrypt_cipher("1:!!!:cGF5bG9hZA==").is_err());
+        assert!(client.lockdate_from_snailcrypt_cipher("2:bm90IGEgZGF0ZQ==:cGF5bG9hZA==:aGludA==").is_err());
+        assert!(client.decrypt("1:bm90IGEgZGF0ZQ==:cGF5bG9hZA==").is_err());
+
+        /* Everybody else still has to wait for the lockdate */
+        let result_failure = client.decrypt(cipher.as_str()).unwrap_err();
+        assert_eq!(client::ClientDecryptErrorKind::NotYetReleased, result_failure.kind);
+
+        let result_failure = client
+            .decrypt_with_escape_key(cipher.as_str(), other_private_pem.as_str(), "")
+            .unwrap_err();
+        assert_eq!(client::ClientDecryptErrorKind::Generic, result_failure.kind);
+
+        let result_success = client
+            .decrypt_with_escape_key(cipher.as_str(), escape_private_pem.as_str(), "")
+            .unwrap_or_else(|error| {
+                panic!("Error: {:?}", error);
+            });
+        assert_eq!(plaintext_orig, result_success.plaintext);
+    }
+
+    #[test]
+    fn encrypt_v4_revocable() {
+        use client::Client;
+
+        let plaintext_orig: String = String::from("hello world");
+
+        let server = util::StandInServer::start();
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+
+        let v4_client = client::V4Client::new(Arc::clone(&analyzer), Arc::clone(&config));
+
+        /* A released message is decrypted like any other cipher text */
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100",
+                                                                       v4_client.get_datetime_format())
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let registration = v4_client.register_message(lockdate).unwrap();
+        let cipher: String = v4_client.encrypt_revocable(&client::ClientEncryptArg {
+            plaintext: plaintext_orig.clone(),
+            lockdate,
+            hint: String::from(""),
+            filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
+        }, &registration).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        assert_eq!(registration.message_id, v4_client.inspect(cipher.as_str()).unwrap().message_id);
+        assert_eq!(client::ClientMessageStatus::Released,
+                   v4_client.get_message_status(registration.message_id.as_str()).unwrap());
+        assert!(v4_client.revoke_message(&registration).is_err());
+
+        let result_success = v4_client.decrypt(cipher.as_str()).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+        assert_eq!(plaintext_orig, result_success.plaintext);
+
+        /* A pending message can be revoked */
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2999-11-19T17:00:00+0100",
+                                                                       v4_client.get_datetime_format())
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let registration = v4_client.register_message(lockdate).unwrap();
+        let cipher: String = v4_client.encrypt_revocable(&client::ClientEncryptArg {
+            plaintext: plaintext_orig.clone(),
+            lockdate,
+            hint: String::from(""),
+            filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
+        }, &registration).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        assert_eq!(client::ClientMessageStatus::Pending,
+                   v4_client.get_message_status(registration.message_id.as_str()).unwrap());
+        assert_eq!(client::ClientDecryptErrorKind::NotYetReleased,
+                   v4_client.decrypt(cipher.as_str()).unwrap_err().kind);
+
+        let mut wrong_registration = registration.clone();
+        wrong_registration.revocation_token = String::from("wrong");
+        assert!(v4_client.revoke_message(&wrong_registration).is_err());
+
+        v4_client.revoke_message(&registration).unwrap();
+        assert_eq!(client::ClientMessageStatus::Revoked,
+                   v4_client.get_message_status(registration.message_id.as_str()).unwrap());
+        assert_eq!(client::ClientDecryptErrorKind::Revoked,
+                   v4_client.decrypt(cipher.as_str()).unwrap_err().kind);
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_ez_failure() {
+        use std::ffi::{
+            CStr,
+            CString,
+        };
+
+        unsafe extern "C" fn encrypted_callback(_cipher: *const std::ffi::c_char) -> i32 {
+            0
+        }
+
+        unsafe extern "C" fn decrypted_callback(_plaintext: *const std::ffi::c_char,
+                                                _hint: *const std::ffi::c_char,
+                                                _filename: *const std::ffi::c_char) -> i32 {
+            0
+        }
+
+        let plaintext = CString::new("hello world").unwrap();
+        let lockdate = CString::new("someday").unwrap();
+        let empty = CString::new("").unwrap();
+
+        let result = unsafe {
+            ffi::snailcrypt_ez_encrypt(plaintext.as_ptr(), lockdate.as_ptr(), empty.as_ptr(), empty.as_ptr(), Some(encrypted_callback))
+        };
+        assert_eq!(ffi::SNAILCRYPT_ERROR_INVALID_LOCKDATE, result);
+
+        let result = unsafe {
+            ffi::snailcrypt_ez_encrypt(std::ptr::null(), lockdate.as_ptr(), empty.as_ptr(), empty.as_ptr(), Some(encrypted_callback))
+        };
+        assert_eq!(ffi::SNAILCRYPT_ERROR_NULL_POINTER, result);
+
+        let result = unsafe {
+            ffi::snailcrypt_ez_decrypt(empty.as_ptr(), Some(decrypted_callback))
+        };
+        assert_eq!(ffi::SNAILCRYPT_ERROR_DECRYPTION, result);
+
+        let error_message = unsafe {
+            CStr::from_ptr(ffi::snailcrypt_last_error_message())
+        };
+        assert_eq!("Cipher is invalid. It must at least contain something.", error_message.to_str().unwrap());
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_client_handle() {
+        use std::{
+            cell::RefCell,
+            ffi::{
+                c_char,
+                CStr,
+                CString,
+            },
+        };
+
+        thread_local! {
+            static CIPHER: RefCell<String> = const { RefCell::new(String::new()) };
+            static PLAINTEXT: RefCell<String> = const { RefCell::new(String::new()) };
+            static VERSION: RefCell<u32> = const { RefCell::new(0) };
+        }
+
+        unsafe extern "C" fn encrypted_callback(cipher: *const c_char) -> i32 {
+            CIPHER.with(|value| *value.borrow_mut() = String::from(CStr::from_ptr(cipher).to_str().unwrap()));
+            0
+        }
+
+        unsafe extern "C" fn decrypted_callback(plaintext: *const c_char, _hint: *const c_char, _filename: *const c_char) -> i32 {
+            PLAINTEXT.with(|value| *value.borrow_mut() = String::from(CStr::from_ptr(plaintext).to_str().unwrap()));
+            0
+        }
+
+        unsafe extern "C" fn inspected_callback(version: u32, _lockdate: *const c_char, _hint: *const c_char, _filename: *const c_char) -> i32 {
+            VERSION.with(|value| *value.borrow_mut() = version);
+            0
+        }
+
+        let server = util::StandInServer::start();
+        let api_url = CString::new(server.get_api_url()).unwrap();
+        let config = ffi::SnailcryptConfig {
+            api_url: api_url.as_ptr(),
+            connect_timeout_ms: 1000,
+            timeout_ms: 30000,
+            cache_dir: std::ptr::null(),
+            reject_past_lockdates: false,
+            min_lead_time_s: 0,
+            lockdate_granularity_s: 0,
+            max_horizon_s: 0,
+        };
+
+        let plaintext = CString::new("hello world").unwrap();
+        let lockdate = CString::new("2022-11-19T17:00:00+0100").unwrap();
+        let hint = CString::new("This is a test message").unwrap();
+        let empty = CString::new("").unwrap();
+
+        unsafe {
+            let client = ffi::snailcrypt_client_new(&config);
+            assert!(!client.is_null());
+
+            assert_eq!(0, ffi::snailcrypt_client_encrypt(client, plaintext.as_ptr(), lockdate.as_ptr(), hint.as_ptr(), empty.as_ptr(), Some(encrypted_callback)));
+            let cipher = CString::new(CIPHER.with(|value| value.borrow().clone())).unwrap();
+
+            assert_eq!(0, ffi::snailcrypt_client_inspect(client, cipher.as_ptr(), Some(inspected_callback)));
+            assert_eq!(2, VERSION.with(|value| *value.borrow()));
+
+            assert_eq!(0, ffi::snailcrypt_client_decrypt(client, cipher.as_ptr(), Some(decrypted_callback)));
+            assert_eq!("hello world", PLAINTEXT.with(|value| value.borrow().clone()));
+
+            ffi::snailcrypt_client_free(client);
+
+            assert_eq!(ffi::SNAILCRYPT_ERROR_NULL_POINTER,
+                       ffi::snailcrypt_client_decrypt(std::ptr::null(), cipher.as_ptr(), Some(decrypted_callback)));
+        }
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_buffer_and_file() {
+        use std::ffi::CString;
+
+        let server = util::StandInServer::start();
+        let api_url = CString::new(server.get_api_url()).unwrap();
+        let config = ffi::SnailcryptConfig {
+            api_url: api_url.as_ptr(),
+            connect_timeout_ms: 0,
+            timeout_ms: 0,
+            cache_dir: std::ptr::null(),
+            reject_past_lockdates: false,
+            min_lead_time_s: 0,
+            lockdate_granularity_s: 0,
+            max_horizon_s: 0,
+        };
+
+        let data: Vec<u8> = (0..=255).collect();
+        let lockdate = CString::new("2022-11-19T17:00:00+0100").unwrap();
+        let filename = CString::new("bytes.bin").unwrap();
+
+        let work_dir = std::env::temp_dir().join(format!("snailcrypt-ffi-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&work_dir);
+        fs::create_dir_all(work_dir.join("out")).unwrap();
+
+        unsafe {
+            let client = ffi::snailcrypt_client_new(&config);
+
+            /* Binary content round trip */
+            let mut cipher = ffi::SnailcryptBuffer { data: std::ptr::null_mut(), len: 0 };
+            assert_eq!(ffi::SNAILCRYPT_OK,
+                       ffi::snailcrypt_client_encrypt_buffer(client, data.as_ptr(), data.len(), lockdate.as_ptr(), std::ptr::null(), filename.as_ptr(), &mut cipher));
+
+            let mut plaintext = ffi::SnailcryptBuffer { data: std::ptr::null_mut(), len: 0 };
+            let mut plaintext_filename = ffi::SnailcryptBuffer { data: std::ptr::null_mut(), len: 0 };
+            assert_eq!(ffi::SNAILCRYPT_OK,
+                       ffi::snailcrypt_client_decrypt_buffer(client, cipher.data, cipher.len, &mut plaintext, &mut plaintext_filename));
+            assert_eq!(data.as_slice(), std::slice::from_raw_parts(plaintext.data, plaintext.len));
+            assert_eq!(b"bytes.bin", std::slice::from_raw_parts(plaintext_filename.data, plaintext_filename.len));
+
+            ffi::snailcrypt_buffer_free(&mut cipher);
+            ffi::snailcrypt_buffer_free(&mut plaintext);
+            ffi::snailcrypt_buffer_free(&mut plaintext_filename);
+            assert!(cipher.data.is_null());
+
+            /* Binary content needs a filename */
+            assert_eq!(ffi::SNAILCRYPT_ERROR_INVALID_UTF8,
+                       ffi::snailcrypt_client_encrypt_buffer(client, data.as_ptr(), data.len(), lockdate.as_ptr(), std::ptr::null(), std::ptr::null(), &mut cipher));
+
+            /* File round trip */
+            let in_path = CString::new(work_dir.join("bytes.bin").to_str().unwrap()).unwrap();
+            let cipher_path = CString::new(work_dir.join("bytes.bin.snailcrypt").to_str().unwrap()).unwrap();
+            let out_dir = CString::new(work_dir.join("out").to_str().unwrap()).unwrap();
+            fs::write(work_dir.join("bytes.bin"), &data).unwrap();
+
+            assert_eq!(ffi::SNAILCRYPT_OK,
+                       ffi::snailcrypt_client_encrypt_file(client, in_path.as_ptr(), cipher_path.as_ptr(), lockdate.as_ptr(), std::ptr::null()));
+            assert_eq!(ffi::SNAILCRYPT_OK,
+                       ffi::snailcrypt_client_decrypt_file(client, cipher_path.as_ptr(), out_dir.as_ptr()));
+            assert_eq!(data, fs::read(work_dir.join("out").join("bytes.bin")).unwrap());
+
+            /* An existing file is never overwritten */
+            assert_eq!(ffi::SNAILCRYPT_ERROR_IO,
+                       ffi::snailcrypt_client_decrypt_file(client, cipher_path.as_ptr(), out_dir.as_ptr()));
+
+            /* An embedded path must not escape the output directory */
+            let evil_filename = CString::new("../evil.bin").unwrap();
+            assert_eq!(ffi::SNAILCRYPT_OK,
+                       ffi::snailcrypt_client_encrypt_buffer(client, data.as_ptr(), data.len(), lockdate.as_ptr(), std::ptr::null(), evil_filename.as_ptr(), &mut cipher));
+            fs::write(work_dir.join("evil.snailcrypt"), std::slice::from_raw_parts(cipher.data, cipher.len)).unwrap();
+            ffi::snailcrypt_buffer_free(&mut cipher);
+
+            let evil_path = CString::new(work_dir.join("evil.snailcrypt").to_str().unwrap()).unwrap();
+            assert_eq!(ffi::SNAILCRYPT_ERROR_INVALID_FILENAME,
+                       ffi::snailcrypt_client_decrypt_file(client, evil_path.as_ptr(), out_dir.as_ptr()));
+            assert!(!work_dir.join("evil.bin").exists());
+
+            ffi::snailcrypt_client_free(client);
+        }
+
+        fs::remove_dir_all(&work_dir).unwrap();
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_user_data_and_async() {
+        use std::{
+            ffi::{
+                c_char,
+                c_void,
+                CStr,
+                CString,
+            },
+            sync::Mutex,
+        };
+
+        #[derive(Default)]
+        struct Results {
+            cipher: String,
+            plaintext: String,
+            version: u32,
+            codes: Vec<i32>,
+        }
+
+        unsafe extern "C" fn encrypted_callback(user_data: *mut c_void, cipher: *const c_char) -> i32 {
+            let results = &*(user_data as *const Mutex<Results>);
+            results.lock().unwrap().cipher = String::from(CStr::from_ptr(cipher).to_str().unwrap());
+            0
+        }
+
+        unsafe extern "C" fn decrypted_callback(user_data: *mut c_void, plaintext: *const c_char, _hint: *const c_char, _filename: *const c_char) -> i32 {
+            let results = &*(user_data as *const Mutex<Results>);
+            results.lock().unwrap().plaintext = String::from(CStr::from_ptr(plaintext).to_str().unwrap());
+            0
+        }
+
+        unsafe extern "C" fn inspected_callback(user_data: *mut c_void, version: u32, _lockdate: *const c_char, _hint: *const c_char, _filename: *const c_char) -> i32 {
+            let results = &*(user_data as *const Mutex<Results>);
+            results.lock().unwrap().version = version;
+            0
+        }
+
+        unsafe extern "C" fn async_decrypted_callback(user_data: *mut c_void, result: i32, plaintext: *const c_char, _hint: *const c_char, _filename: *const c_char, error_message: *const c_char) {
+            let results = &*(user_data as *const Mutex<Results>);
+            let mut results = results.lock().unwrap();
+            results.codes.push(result);
+            if result == ffi::SNAILCRYPT_OK {
+                assert!(error_message.is_null());
+                results.plaintext = String::from(CStr::from_ptr(plaintext).to_str().unwrap());
+            } else {
+                assert!(plaintext.is_null());
+                assert!(!CStr::from_ptr(error_message).to_bytes().is_empty());
+            }
+        }
+
+        unsafe extern "C" fn async_encrypted_callback(user_data: *mut c_void, result: i32, cipher: *const c_char, _error_message: *const c_char) {
+            let results = &*(user_data as *const Mutex<Results>);
+            let mut results = results.lock().unwrap();
+            results.codes.push(result);
+            if result == ffi::SNAILCRYPT_OK {
+                results.cipher = String::from(CStr::from_ptr(cipher).to_str().unwrap());
+            }
+        }
+
+        let server = util::StandInServer::start();
+        let api_url = CString::new(server.get_api_url()).unwrap();
+        let config = ffi::SnailcryptConfig {
+            api_url: api_url.as_ptr(),
+            connect_timeout_ms: 0,
+            timeout_ms: 0,
+            cache_dir: std::ptr::null(),
+            reject_past_lockdates: false,
+            min_lead_time_s: 0,
+            lockdate_granularity_s: 0,
+            max_horizon_s: 0,
+        };
+
+        let plaintext = CString::new("hello world").unwrap();
+        let lockdate = CString::new("2022-11-19T17:00:00+0100").unwrap();
+        let invalid_cipher = CString::new("9:aGVsbG8=").unwrap();
+        let empty = CString::new("").unwrap();
+
+        let results: Mutex<Results> = Mutex::new(Results::default());
+        let user_data = &results as *const Mutex<Results> as *mut c_void;
+
+        unsafe {
+            /* Synchronous callbacks carrying user data */
+            let client = ffi::snailcrypt_client_new(&config);
+            assert_eq!(0, ffi::snailcrypt_client_encrypt_with_data(client, plaintext.as_ptr(), lockdate.as_ptr(), empty.as_ptr(), empty.as_ptr(), Some(encrypted_callback), user_data));
+            let cipher = CString::new(results.lock().unwrap().cipher.clone()).unwrap();
+            assert_eq!(0, ffi::snailcrypt_client_decrypt_with_data(client, cipher.as_ptr(), Some(decrypted_callback), user_data));
+            assert_eq!("hello world", results.lock().unwrap().plaintext);
+            assert_eq!(0, ffi::snailcrypt_client_inspect_with_data(client, cipher.as_ptr(), Some(inspected_callback), user_data));
+            assert_eq!(u32::from(&format::inspect(cipher.to_str().unwrap()).unwrap().version), results.lock().unwrap().version);
+            assert_eq!(ffi::SNAILCRYPT_ERROR_DECRYPTION,
+                       ffi::snailcrypt_client_inspect_with_data(client, invalid_cipher.as_ptr(), Some(inspected_callback), user_data));
+
+            /* Asynchronous requests complete before the handle is released */
+            results.lock().unwrap().plaintext.clear();
+            assert_eq!(ffi::SNAILCRYPT_OK,
+                       ffi::snailcrypt_client_encrypt_async(client, plaintext.as_ptr(), lockdate.as_ptr(), empty.as_ptr(), empty.as_ptr(), Some(async_encrypted_callback), user_data));
+            assert_eq!(ffi::SNAILCRYPT_OK,
+                       ffi::snailcrypt_client_decrypt_async(client, cipher.as_ptr(), Some(async_decrypted_callback), user_data));
+            assert_eq!(ffi::SNAILCRYPT_OK,
+                       ffi::snailcrypt_client_decrypt_async(client, invalid_cipher.as_ptr(), Some(async_decrypted_callback), user_data));
+            assert_eq!(ffi::SNAILCRYPT_ERROR_NULL_POINTER,
+                       ffi::snailcrypt_client_decrypt_async(client, cipher.as_ptr(), None, user_data));
+            ffi::snailcrypt_client_free(client);
+        }
+
+        let results = results.lock().unwrap();
+        assert_eq!(vec![ffi::SNAILCRYPT_OK, ffi::SNAILCRYPT_OK, ffi::SNAILCRYPT_ERROR_DECRYPTION], results.codes);
+        assert_eq!("hello world", results.plaintext);
+    }
+
+    #[cfg(feature = "ffi")]
+    #[test]
+    fn ffi_inspect() {
+        use std::ffi::{
+            CStr,
+            CString,
+        };
+
+        let locked_cipher = CString::new("3:Mjk5OS0xMS0xOVQxNzowMDowMCswMTAw:cGF5bG9hZA==:QSBoaW50:cmVwb3J0LnBkZg==").unwrap();
+
+        unsafe {
+            let mut info = ffi::SnailcryptInspectInfo {
+                version: 0,
+                lockdate_unix: 0,
+                lockdate: std::ptr::null_mut(),
+                hint: std::ptr::null_mut(),
+                filename: std::ptr::null_mut(),
+                locked: false,
+            };
+            assert_eq!(ffi::SNAILCRYPT_OK, ffi::snailcrypt_inspect(locked_cipher.as_ptr(), &mut info));
+            assert_eq!(3, info.version);
+            assert_eq!(32500022400, info.lockdate_unix);
+            assert_eq!("2999-11-19T17:00:00+01:00", CStr::from_ptr(info.lockdate).to_str().unwrap());
+            assert_eq!("A hint", CStr::from_ptr(info.hint).to_str().unwrap());
+            assert_eq!("report.pdf", CStr::from_ptr(info.filename).to_str().unwrap());
+            assert!(info.locked);
+
+            ffi::snailcrypt_inspect_info_free(&mut info);
+            assert!(info.lockdate.is_null());
+
+            let mut seconds: i64 = 0;
+            assert_eq!(ffi::SNAILCRYPT_OK, ffi::snailcrypt_seconds_until_unlock(locked_cipher.as_ptr(), &mut seconds));
+            assert!(seconds > 0);
+        }
+    }
+
+    #[cfg(feature = "python")]
+    #[test]
+    fn python_bindings() {
+        use pyo3::{
+            prelude::*,
+            types::PyDict,
+        };
+
+        let server = util::StandInServer::start();
+        /* Accepts connections but never answers */
+        let silent_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let silent_api_url = format!("http://{}", silent_listener.local_addr().unwrap());
+
+        pyo3::prepare_freethreaded_python();
+        Python::with_gil(|py| {
+            let module = pyo3::wrap_pymodule!(python::init_module)(py);
+            let locals = PyDict::new_bound(py);
+            locals.set_item("snailcrypt", module).unwrap();
+            locals.set_item("api_url", server.get_api_url()).unwrap();
+            locals.set_item("silent_api_url", silent_api_url.as_str()).unwrap();
+
+            py.run_bound(r#"
+import datetime
+
+config = snailcrypt.Config(api_url=api_url, connect_timeout=1.0, timeout=30.0)
+assert config.api_url == api_url
+assert config.timeout == 30.0
+client = snailcrypt.Client(config)
+
+cipher = client.encrypt("hello world", "2022-11-19T17:00:00+0100", hint="This is a test message")
+result = client.decrypt(cipher)
+assert result.plaintext == "hello world"
+assert result.hint == "This is a test message"
+assert result.data == b"hello world"
+
+lockdate = datetime.datetime(2022, 11, 19, 17, tzinfo=datetime.timezone.utc)
+cipher = client.encrypt(b"\x00\xff binary", lockdate, filename="blob.bin", passphrase="secret")
+info = client.inspect(cipher)
+assert info.version == 4
+assert info.lockdate == lockdate
+assert info.filename == "blob.bin"
+assert info.passphrase_required
+assert not info.locked
+assert client.decrypt(cipher, passphrase="secret").data == b"\x00\xff binary"
+
+try:
+    client.decrypt(cipher)
+    raise AssertionError("decrypting without a passphrase succeeded")
+except snailcrypt.PassphraseRequiredError:
+    pass
+
+try:
+    client.decrypt(cipher, passphrase="wrong")
+    raise AssertionError("decrypting using a wrong passphrase succeeded")
+except snailcrypt.DecryptionError as error:
+    assert isinstance(error, snailcrypt.WrongPassphraseError)
+
+try:
+    client.encrypt(b"\xff", lockdate)
+    raise AssertionError("encrypting binary content without a filename succeeded")
+except ValueError:
+    pass
+
+try:
+    client.inspect("")
+    raise AssertionError("inspecting an empty cipher succeeded")
+except snailcrypt.SnailcryptError:
+    pass
+
+locked = client.encrypt("hello world", datetime.datetime(2999, 11, 19, tzinfo=datetime.timezone.utc))
+assert client.inspect(locked).locked
+try:
+    client.decrypt(locked)
+    raise AssertionError("decrypting a locked cipher succeeded")
+except snailcrypt.NotYetReleasedError:
+    pass
+
+strict_config = snailcrypt.Config(api_url=api_url, min_lead_time=0, lockdate_granularity=3600)
+assert strict_config.min_lead_time == 0.0
+assert strict_config.lockdate_granularity == 3600.0
+assert strict_config.max_horizon is None
+assert snailcrypt.Config().min_lead_time is None
+try:
+    snailcrypt.Client(strict_config).encrypt("hello world", "2022-11-19T17:00:00+0100")
+    raise AssertionError("encrypting until a past lockdate succeeded")
+except snailcrypt.EncryptionError as error:
+    assert isinstance(error, snailcrypt.LockdatePolicyError)
+
+# Other threads keep running while a client waits for the key server
+import threading
+import time
+
+ticks = []
+stop = threading.Event()
+def tick(ticks=ticks, stop=stop, time=time):
+    while not stop.is_set():
+        ticks.append(None)
+        time.sleep(0.01)
+
+ticker = threading.Thread(target=tick)
+ticker.start()
+silent_client = snailcrypt.Client(snailcrypt.Config(api_url=silent_api_url, timeout=0.5))
+before = len(ticks)
+try:
+    silent_client.encrypt("hello world", "2022-11-19T17:00:00+0100")
+    raise AssertionError("encrypting using a silent key server succeeded")
+except snailcrypt.EncryptionError:
+    pass
+during = len(ticks) - before
+stop.set()
+ticker.join()
+assert during >= 10, during
+"#, None, Some(&locals)).unwrap_or_else(|error| {
+                panic!("Error: {}", error);
+            });
+        });
+    }
+
+    #[test]
+    fn shamir_split_recover() {
+        use crypto::shamir;
+
+        /* Shares of version 5 cipher texts created so far must still be recovered */
+        assert_eq!(vec![5], shamir::recover(3, &[vec![1, 4], vec![2, 13], vec![3, 12]]).unwrap());
+        assert_eq!(vec![5], shamir::recover(3, &[vec![3, 12], vec![1, 4], vec![2, 13]]).unwrap());
+        assert!(shamir::recover(3, &[vec![1, 4], vec![2, 13], vec![2, 13]]).is_err());
+        assert!(shamir::recover(1, &[vec![0, 4]]).is_err());
+        assert!(shamir::recover(1, &[vec![1, 4], vec![2, 13, 1]]).is_err());
+
+        let secret: Vec<u8> = (0..=255).collect();
+        for backend in crypto::all_backends() {
+            let shares = shamir::split(backend, &secret, 3, 5).unwrap();
+            assert_eq!(5, shares.len());
+            assert_eq!(secret, shamir::recover(3, &shares[2..5]).unwrap());
+            assert_eq!(secret, shamir::recover(3, &[shares[4].clone(), shares[0].clone(), shares[2].clone()]).unwrap());
+            assert_ne!(secret, shamir::recover(2, &shares[0..2]).unwrap());
+            assert!(shamir::recover(3, &shares[0..2]).is_err());
+
+            /* A threshold of one hands out the secret itself */
+            let shares = shamir::split(backend, &secret, 1, 2).unwrap();
+            assert_eq!(secret, shares[1][1..]);
+        }
+    }
+
+    /// Every crypto backend must decrypt the cipher texts of every other backend. Enable all crypto features, e.g.
+    /// using "--features rustcrypto", to test more than the default backend.
+    #[test]
+    fn crypto_backends_compatible() {
+        use client::Client;
+
+        let plaintext_orig: String = "hello world, ".repeat(40);
+        let hint_orig: String = String::from("This is a test message");
+        let passphrase_orig: String = String::from("correct horse battery staple");
+
+        let server = util::StandInServer::start();
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let analyzer: Arc<dyn util::Analyzer> = analyzer_factory.create();
+
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+
+        let escape_key = openssl::rsa::Rsa::generate(2048).unwrap();
+        let escape_public_pem = String::from_utf8(escape_key.public_key_to_pem().unwrap()).unwrap();
+        let escape_private_pem = String::from_utf8(escape_key.private_key_to_pem().unwrap()).unwrap();
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2022-11-19T17:00:00+01:00").unwrap();
+
+        for encrypt_backend in crypto::all_backends() {
+            for decrypt_backend in crypto::all_backends() {
+                let backends = format!("{} -> {}", encrypt_backend.get_name(), decrypt_backend.get_name());
+
+                /* Version 2 locks the plain text in several RSA chunks */
+                let encrypt_client = client::V2Client::new_with_crypto_backend(Arc::clone(&analyzer), Arc::clone(&config), encrypt_backend);
+                let decrypt_client = client::V2Client::new_with_crypto_backend(Arc::clone(&analyzer), Arc::clone(&config), decrypt_backend);
+
+                let cipher: String = encrypt_client.encrypt(&client::ClientEncryptArg {
+                    plaintext: plaintext_orig.clone(),
+                    lockdate,
+                    hint: hint_orig.clone(),
+                    filename: String::from(""),
+                    passphrase: String::from(""),
+                    escape_recipients: Vec::new(),
+                }).unwrap_or_else(|error| {
+                    panic!("Error ({}): {:?}", backends, error);
+                });
+
+                let result_success = decrypt_client.decrypt(cipher.as_str()).unwrap_or_else(|error| {
+                    panic!("Error ({}): {:?}", backends, error.error_message);
+                });
+                assert_eq!(plaintext_orig, result_success.plaintext, "{}", backends);
+                assert_eq!(hint_orig, result_success.hint, "{}", backends);
+
+                /* Version 4 uses AES-256-GCM, SHA-256 and the fingerprint of an early-unlock recipient */
+                let encrypt_client = client::V4Client::new_with_crypto_backend(Arc::clone(&analyzer), Arc::clone(&config), encrypt_backend);
+                let decrypt_client = client::V4Client::new_with_crypto_backend(Arc::clone(&analyzer), Arc::clone(&config), decrypt_backend);
+
+                let cipher: String = encrypt_client.encrypt(&client::ClientEncryptArg {
+                    plaintext: plaintext_orig.clone(),
+                    lockdate,
+                    hint: hint_orig.clone(),
+                    filename: String::from(""),
+                    passphrase: passphrase_orig.clone(),
+                    escape_recipients: vec![escape_public_pem.clone()],
+                }).unwrap_or_else(|error| {
+                    panic!("Error ({}): {:?}", backends, error);
+                });
+
+                let result_success = decrypt_client
+                    .decrypt_with_passphrase(cipher.as_str(), passphrase_orig.as_str())
+                    .unwrap_or_else(|error| {
+                        panic!("Error ({}): {:?}", backends, error.error_message);
+                    });
+                assert_eq!(plaintext_orig, result_success.plaintext, "{}", backends);
+
+                let result_success = decrypt_client
+                    .decrypt_with_escape_key(cipher.as_str(), escape_private_pem.as_str(), passphrase_orig.as_str())
+                    .unwrap_or_else(|error| {
+                        panic!("Error ({}): {:?}", backends, error.error_message);
+                    });
+                assert_eq!(plaintext_orig, result_success.plaintext, "{}", backends);
+
+                let result_failure = decrypt_client
+                    .decrypt_with_passphrase(cipher.as_str(), "wrong")
+                    .unwrap_err();
+                assert_eq!(client::ClientDecryptErrorKind::WrongPassphrase, result_failure.kind, "{}", backends);
+            }
+        }
+    }
+
+    #[test]
+    fn format_inspect_offline() {
+        use client::Client;
+
+        /* Version 3 without any key server */
+        let inspect_result = format::inspect("3:Mjk5OS0xMS0xOVQxNzowMDowMCswMTAw:cGF5bG9hZA==:QSBoaW50:cmVwb3J0LnBkZg==")
+            .unwrap_or_else(|error| {
+                panic!("Error: {:?}", error);
+            });
+        assert_eq!(format::ClientVersion::V3, inspect_result.version);
+        assert_eq!("2999-11-19T17:00:00+01:00", inspect_result.lockdate.to_rfc3339());
+        assert_eq!("A hint", inspect_result.hint);
+        assert_eq!("report.pdf", inspect_result.filename);
+
+        /* Invalid cipher texts are reported instead of panicking */
+        assert!(format::inspect("1:bm90IGEgZGF0ZQ==:cGF5bG9hZA==").is_err());
+        assert!(format::inspect("2:Mjk5OS0xMS0xOVQxNzowMDowMCswMTAw").is_err());
+        assert!(format::inspect("7:a:b").is_err());
+
+        /* The offline inspection matches the one of the clients */
+        let server = util::StandInServer::start();
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+        let v6_client = client::V6Client::new(factory::AnalyzerFactory::new().create(), Arc::clone(&config));
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2999-11-18T09:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+        let cipher = v6_client.encrypt_sections(&[
+            client::ClientEncryptArg {
+                plaintext: String::from("Part A"),
+                lockdate,
+                hint: String::from("Opens on Friday"),
+                filename: String::from(""),
+                passphrase: String::from("secret"),
+                escape_recipients: Vec::new(),
+            },
+        ]).unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let offline_result = format::inspect(cipher.as_str()).unwrap();
+        let client_result = v6_client.inspect(cipher.as_str()).unwrap();
+        assert_eq!(client_result.version, offline_result.version);
+        assert_eq!(client_result.lockdate, offline_result.lockdate);
+        assert!(offline_result.passphrase_required);
+        assert_eq!(1, offline_result.sections.len());
+        assert_eq!("Opens on Friday", offline_result.sections[0].hint);
+    }
+
+    #[test]
+    fn client_shared_between_threads() {
+        use std::thread;
+
+        fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+        assert_send_sync::<dyn client::Client>();
+        assert_send_sync::<dyn config::Config>();
+        assert_send_sync::<dyn util::Analyzer>();
+        assert_send_sync::<factory::ClientFactory>();
+
+        let server = util::StandInServer::start();
+
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(factory::AnalyzerFactory::new().create(),
+                                        config);
+        let client: Arc<dyn client::Client> = client_factory.create();
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        /* One client serves the requests of several threads */
+        let handles: Vec<thread::JoinHandle<()>> = (0..4)
+            .map(|index| {
+                let client = Arc::clone(&client);
+                thread::spawn(move || {
+                    let plaintext = format!("Message {}", index);
+                    let cipher = client.encrypt(&client::ClientEncryptArg {
+                        plaintext: plaintext.clone(),
+                        lockdate,
+                        hint: String::from(""),
+                        filename: String::from(""),
+                        passphrase: String::from(""),
+                        escape_recipients: Vec::new(),
+                    }).unwrap_or_else(|error| {
+                        panic!("Error: {:?}", error);
+                    });
+
+                    let result = client.decrypt(cipher.as_str())
+                        .unwrap_or_else(|error| {
+                            panic!("Error: {:?}", error.error_message);
+                        });
+                    assert_eq!(plaintext, result.plaintext);
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_client_concurrent() {
+        use std::{
+            sync::atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+            time::Duration,
+        };
+
+        use async_trait::async_trait;
+        use client::AsyncClient;
+
+        /* Counts the requests and refuses any blocking access */
+        struct CountingTransport {
+            inner: transport::ReqwestTransport,
+            count: AtomicUsize,
+        }
+
+        #[async_trait]
+        impl transport::AsyncTransport for CountingTransport {
+            async fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+                -> Result<Vec<u8>, String> {
+                self.count.fetch_add(1, Ordering::SeqCst);
+                self.inner.post(url, body, connect_timeout, timeout).await
+            }
+        }
+
+        struct BlockingTransport {}
+
+        impl transport::Transport for BlockingTransport {
+            fn post(&self, _url: &str, _body: &str, _connect_timeout: Option<Duration>, _timeout: Option<Duration>)
+                -> Result<Vec<u8>, String> {
+                panic!("The blocking transport must not be used.");
+            }
+        }
+
+        struct AsyncConfig {
+            api_url: String,
+            transport: Arc<CountingTransport>,
+        }
+
+        impl config::Config for AsyncConfig {
+            fn get_api_url(&self) -> &str {
+                self.api_url.as_str()
+            }
+
+            fn get_transport(&self) -> Option<Arc<dyn transport::Transport>> {
+                Some(Arc::new(BlockingTransport {}))
+            }
+
+            fn get_async_transport(&self) -> Option<Arc<dyn transport::AsyncTransport>> {
+                Some(self.transport.clone())
+            }
+        }
+
+        let server = util::StandInServer::start();
+
+        let counting_transport = Arc::new(CountingTransport {
+            inner: transport::ReqwestTransport::new(),
+            count: AtomicUsize::new(0),
+        });
+        let config: Arc<dyn config::Config> = Arc::new(AsyncConfig {
+            api_url: String::from(server.get_api_url()),
+            transport: Arc::clone(&counting_transport),
+        });
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(factory::AnalyzerFactory::new().create(),
+                                        config);
+        let client: Arc<dyn AsyncClient> = client_factory.create_async();
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+        let lockdate_locked: DateTime<FixedOffset> = DateTime::parse_from_str("2999-11-19T17:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        runtime.block_on(async {
+            /* Many requests run concurrently on a single runtime */
+            let ciphers: Vec<String> = futures::future::join_all((0..8)
+                .map(|index| {
+                    let client = Arc::clone(&client);
+                    async move {
+                        client.encrypt(&client::ClientEncryptArg {
+                            plaintext: format!("Message {}", index),
+                            lockdate,
+                            hint: String::from("A hint"),
+                            filename: String::from(""),
+                            passphrase: String::from(""),
+                            escape_recipients: Vec::new(),
+                        }).await.unwrap_or_else(|error| {
+                            panic!("Error: {:?}", error);
+                        })
+                    }
+                }))
+                .await;
+
+            let results = futures::future::join_all(ciphers
+                .iter()
+                .map(|cipher| client.decrypt(cipher.as_str())))
+                .await;
+            for (index, result) in results.into_iter().enumerate() {
+                let result = result.unwrap_or_else(|error| {
+                    panic!("Error: {:?}", error.error_message);
+                });
+                assert_eq!(format!("Message {}", index), result.plaintext);
+                assert_eq!("A hint", result.hint);
+            }
+
+            /* Failures of the key server are reported like by the synchronous clients */
+            let locked_cipher = client.encrypt(&client::ClientEncryptArg {
+                plaintext: String::from("Not yet"),
+                lockdate: lockdate_locked,
+                hint: String::from(""),
+                filename: String::from(""),
+                passphrase: String::from("secret"),
+                escape_recipients: Vec::new(),
+            }).await.unwrap();
+
+            let failure = client.decrypt_with_passphrase(locked_cipher.as_str(), "secret").await.unwrap_err();
+            assert_eq!(client::ClientDecryptErrorKind::NotYetReleased, failure.kind);
+
+            let inspect_result = client.inspect(locked_cipher.as_str()).await.unwrap();
+            assert!(inspect_result.passphrase_required);
+        });
+
+        assert!(counting_transport.count.load(Ordering::SeqCst) >= 18);
+    }
+
+    #[cfg(all(feature = "wasm", feature = "native"))]
+    #[test]
+    fn wasm_client_host_transport() {
+        use std::{
+            sync::atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+            time::Duration,
+        };
+
+        /* Counts the requests the bindings send through a Rust transport */
+        struct CountingTransport {
+            inner: transport::CurlTransport,
+            count: AtomicUsize,
+        }
+
+        impl transport::Transport for CountingTransport {
+            fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+                -> Result<Vec<u8>, String> {
+                self.count.fetch_add(1, Ordering::SeqCst);
+                self.inner.post(url, body, connect_timeout, timeout)
+            }
+        }
+
+        let server = util::StandInServer::start();
+        let counting_transport = Arc::new(CountingTransport {
+            inner: transport::CurlTransport::new(),
+            count: AtomicUsize::new(0),
+        });
+        let client = wasm::WasmClient::new_with_transport(counting_transport.clone(),
+                                                          Some(String::from(server.get_api_url())),
+                                                          None);
+
+        let cipher = client.encrypt("Hello World", "2022-11-19T17:00:00+01:00", Some(String::from("A hint")), None, None)
+            .unwrap();
+        let inspect_result = client.inspect(cipher.as_str()).unwrap();
+        assert_eq!(2, inspect_result.version);
+        assert_eq!("2022-11-19T17:00:00+01:00", inspect_result.lockdate);
+        assert!(!inspect_result.locked);
+
+        let result = client.decrypt(cipher.as_str(), None).unwrap();
+        assert_eq!("Hello World", result.plaintext);
+        assert_eq!("A hint", result.hint);
+        assert_eq!(2, counting_transport.count.load(Ordering::SeqCst));
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_client_discovers_capabilities() {
+        use chrono::{
+            Timelike,
+            Utc,
+        };
+        use client::AsyncClient;
+        use serde_json::json;
+        use std::{
+            sync::atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+            time::Duration,
+        };
+
+        /* Counts the key requests reaching the key server */
+        struct CountingAsyncTransport {
+            inner: Arc<dyn transport::AsyncTransport>,
+            key_requests: AtomicUsize,
+        }
+
+        #[async_trait::async_trait]
+        impl transport::AsyncTransport for CountingAsyncTransport {
+            async fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+                -> Result<Vec<u8>, String> {
+                if url.ends_with("/keys") {
+                    self.key_requests.fetch_add(1, Ordering::SeqCst);
+                }
+                self.inner.post(url, body, connect_timeout, timeout).await
+            }
+
+            async fn get(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+                -> Result<Vec<u8>, String> {
+                self.inner.get(url, connect_timeout, timeout).await
+            }
+        }
+
+        struct CountingConfig {
+            api_url: String,
+            transport: Arc<CountingAsyncTransport>,
+        }
+
+        impl config::Config for CountingConfig {
+            fn get_api_url(&self) -> &str {
+                self.api_url.as_str()
+            }
+
+            fn get_async_transport(&self) -> Option<Arc<dyn transport::AsyncTransport>> {
+                Some(self.transport.clone())
+            }
+        }
+
+        /* The asynchronous client asks for the metadata like the synchronous ones */
+        let server = util::StandInServer::start_with_metadata(Arc::new(clock::SystemClock::new()), Some(json!({
+            "api_version": 1,
+            "key_algorithms": [{ "name": "RSA", "sizes": [2048] }],
+            "lockdate_granularity": 3600,
+        })));
+        let counting_transport = Arc::new(CountingAsyncTransport {
+            inner: transport::default_async_transport().unwrap(),
+            key_requests: AtomicUsize::new(0),
+        });
+        let config: Arc<dyn config::Config> = Arc::new(CountingConfig {
+            api_url: String::from(server.get_api_url()),
+            transport: Arc::clone(&counting_transport),
+        });
+        let client_factory = factory::ClientFactory::new(factory::AnalyzerFactory::new().create(), config);
+        let client: Arc<dyn AsyncClient> = client_factory.create_async();
+
+        let lockdate = (Utc::now() + chrono::Duration::minutes(90)).fixed_offset();
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let cipher = runtime.block_on(client.encrypt(&client::ClientEncryptArg::new_with_lockdate("Hello World", lockdate)))
+            .unwrap();
+
+        let rounded_lockdate = format::inspect(cipher.as_str()).unwrap().lockdate;
+        assert!(rounded_lockdate > lockdate);
+        assert_eq!((0, 0), (rounded_lockdate.minute(), rounded_lockdate.second()));
+
+        /* Only the public key of the rounded lockdate is requested */
+        assert_eq!(1, counting_transport.key_requests.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn batch_coalesces_key_requests() {
+        use std::{
+            sync::atomic::{
+                AtomicUsize,
+                Ordering,
+            },
+            time::Duration,
+        };
+
+        /* Counts the requests reaching the key server */
+        struct CountingTransport {
+            inner: Arc<dyn transport::Transport>,
+            count: AtomicUsize,
+        }
+
+        impl transport::Transport for CountingTransport {
+            fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+                -> Result<Vec<u8>, String> {
+                self.count.fetch_add(1, Ordering::SeqCst);
+                self.inner.post(url, body, connect_timeout, timeout)
+            }
+        }
+
+        struct CountingConfig {
+            api_url: String,
+            transport: Arc<CountingTransport>,
+        }
+
+        impl config::Config for CountingConfig {
+            fn get_api_url(&self) -> &str {
+                self.api_url.as_str()
+            }
+
+            fn get_transport(&self) -> Option<Arc<dyn transport::Transport>> {
+                Some(self.transport.clone())
+            }
+        }
+
+        let server = util::StandInServer::start();
+
+        let counting_transport = Arc::new(CountingTransport {
+            inner: transport::default_transport().unwrap(),
+            count: AtomicUsize::new(0),
+        });
+        let config: Arc<dyn config::Config> = Arc::new(CountingConfig {
+            api_url: String::from(server.get_api_url()),
+            transport: Arc::clone(&counting_transport),
+        });
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(factory::AnalyzerFactory::new().create(),
+                                        config);
+        let mut batch_client = client_factory.create_batch();
+        batch_client.set_thread_count(4);
+        assert_eq!(4, batch_client.get_thread_count());
+
+        let lockdate_monday: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-14T09:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+        let lockdate_friday: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-18T09:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+        let lockdate_locked: DateTime<FixedOffset> = DateTime::parse_from_str("2999-11-18T09:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        /* 20 items with 2 lockdates need 2 keys */
+        let args: Vec<client::ClientEncryptArg> = (0..20)
+            .map(|index| client::ClientEncryptArg {
+                plaintext: format!("Message {}", index),
+                lockdate: if index % 2 == 0 { lockdate_monday } else { lockdate_friday },
+                hint: format!("Hint {}", index),
+                filename: String::from(""),
+                passphrase: String::from(""),
+                escape_recipients: Vec::new(),
+            })
+            .collect();
+
+        let mut ciphers: Vec<String> = batch_client
+            .encrypt_batch(&args)
+            .into_iter()
+            .map(|cipher| cipher.unwrap_or_else(|error| {
+                panic!("Error: {:?}", error);
+            }))
+            .collect();
+        assert_eq!(20, ciphers.len());
+        assert_eq!(2, counting_transport.count.load(Ordering::SeqCst));
+
+        /* One bad item does not fail the batch */
+        let locked_cipher = client_factory.create().encrypt(&client::ClientEncryptArg {
+            plaintext: String::from("Not yet"),
+            lockdate: lockdate_locked,
+            hint: String::from(""),
+            filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
+        }).unwrap();
+        ciphers.push(String::from("4:not a cipher"));
+        ciphers.push(locked_cipher);
+        /* A legacy cipher text with a valid header and a malformed payload */
+        ciphers.push(format!("1:{}:not*base64", base64::encode("2022-11-14T09:00:00+0100")));
+        counting_transport.count.store(0, Ordering::SeqCst);
+
+        let results = batch_client.decrypt_batch(&ciphers);
+        assert_eq!(23, results.len());
+        assert_eq!(3, counting_transport.count.load(Ordering::SeqCst));
+
+        for (index, result) in results.iter().take(20).enumerate() {
+            let result = result.as_ref().unwrap_or_else(|error| {
+                panic!("Error: {:?}", error.error_message);
+            });
+            assert_eq!(format!("Message {}", index), result.plaintext);
+            assert_eq!(format!("Hint {}", index), result.hint);
+        }
+        assert_eq!(client::ClientDecryptErrorKind::Generic, results[20].as_ref().unwrap_err().kind);
+        assert_eq!(client::ClientDecryptErrorKind::NotYetReleased, results[21].as_ref().unwrap_err().kind);
+        assert_eq!(client::ClientDecryptErrorKind::Generic, results[22].as_ref().unwrap_err().kind);
+
+        /* A single thread processes the batch on the calling thread */
+        batch_client.set_thread_count(1);
+        let results = batch_client.decrypt_batch(&ciphers[..4]);
+        assert_eq!(vec!["Message 0", "Message 1", "Message 2", "Message 3"],
+                   results.iter().map(|result| result.as_ref().unwrap().plaintext.as_str()).collect::<Vec<&str>>());
+    }
+
+    #[test]
+    fn legacy_chunks_parallel() {
+        use client::Client;
+
+        let server = util::StandInServer::start();
+        let analyzer: Arc<dyn util::Analyzer> = factory::AnalyzerFactory::new().create();
+
+        let mut serial_config = config::DefaultConfig::new_with_api_url(server.get_api_url());
+        serial_config.set_thread_count(Some(1));
+        let mut parallel_config = serial_config.clone();
+        parallel_config.set_thread_count(Some(4));
+
+        let serial_client = client::V3Client::new(Arc::clone(&analyzer), Arc::new(serial_config));
+        let parallel_client = client::V3Client::new(Arc::clone(&analyzer), Arc::new(parallel_config));
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+
+        /* A file of 300 chunks, which is above the threshold for parallel processing */
+        let data: Vec<u8> = (0..28000u32).map(|index| (index * 7 % 251) as u8).collect();
+        let plaintext_orig: String = base64::encode(&data);
+        let args = client::ClientEncryptArg {
+            plaintext: plaintext_orig.clone(),
+            lockdate,
+            hint: String::from("A large file"),
+            filename: String::from("large.bin"),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
+        };
+
+        let serial_cipher = serial_client.encrypt(&args).unwrap();
+        let parallel_cipher = parallel_client.encrypt(&args).unwrap();
+
+        /* The layout does not depend on the number of threads */
+        assert_eq!(serial_cipher.len(), parallel_cipher.len());
+        let payload_len = base64::decode(parallel_cipher.split(':').nth(2).unwrap()).unwrap().len();
+        assert_eq!(plaintext_orig.len().div_ceil(126) * 256, payload_len);
+
+        /* Both decrypt each other byte by byte */
+        for cipher in [&serial_cipher, &parallel_cipher] {
+            let serial_result = serial_client.decrypt(cipher.as_str()).unwrap();
+            let parallel_result = parallel_client.decrypt(cipher.as_str()).unwrap();
+
+            assert_eq!(plaintext_orig, serial_result.plaintext);
+            assert_eq!(serial_result.plaintext.as_bytes(), parallel_result.plaintext.as_bytes());
+            assert_eq!("A large file", parallel_result.hint);
+            assert_eq!("large.bin", parallel_result.filename);
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn transport_reuses_connections() {
+        use std::thread;
+
+        let server = util::StandInServer::start();
+        let analyzer: Arc<dyn util::Analyzer> = factory::AnalyzerFactory::new().create();
+
+        /* A transport of its own, so the counters are not shared with other tests */
+        let curl_transport: Arc<dyn transport::Transport> = Arc::new(transport::CurlTransport::new());
+        assert_eq!(Some(transport::TransportStats::default()), curl_transport.get_stats());
+
+        let config: Arc<dyn config::Config> = Arc::new(config::OverrideConfig::new_with_transport(
+            Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url())),
+            Arc::clone(&curl_transport)));
+        let client_factory = factory::ClientFactory::new(Arc::clone(&analyzer), config);
+        let client: Arc<dyn client::Client> = client_factory.create();
+
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_str("2022-11-19T17:00:00+0100", format::DATETIME_FORMAT)
+            .unwrap_or_else(|error| {
+            panic!("Error: {:?}", error);
+        });
+        let args = client::ClientEncryptArg {
+            plaintext: String::from("Hello World"),
+            lockdate,
+            hint: String::from(""),
+            filename: String::from(""),
+            passphrase: String::from(""),
+            escape_recipients: Vec::new(),
+        };
+
+        let cipher = client.encrypt(&args).unwrap();
+        assert_eq!("Hello World", client.decrypt(cipher.as_str()).unwrap().plaintext);
+
+        /* A clone of the client used on another thread shares the connection */
+        let cloned_client = Arc::clone(&client);
+        let cloned_cipher = thread::spawn(move || cloned_client.encrypt(&args).unwrap())
+            .join()
+            .unwrap();
+        assert_eq!("Hello World", client.decrypt(cloned_cipher.as_str()).unwrap().plaintext);
+
+        /* The first request discovers the capabilities of the key server */
+        let stats = client_factory.get_transport_stats().unwrap();
+        assert_eq!(5, stats.requests);
+        assert_eq!(1, stats.connections);
+        assert_eq!(4, stats.reused_connections);
+    }
+
+    #[test]
+    fn lockdate_parse() {
+        use lockdate::Lockdate;
+
+        /* Wednesday, so weekdays and tomorrow are resolved deterministically */
+        let now: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2030-01-02T12:00:00+01:00").unwrap();
+        let parse = |input: &str| Lockdate::parse_at(input, now).map(|lockdate| lockdate.get_datetime().to_rfc3339());
+
+        /* Absolute points in time */
+        assert_eq!(Ok(String::from("2030-01-01T11:00:00+00:00")), parse("2030-01-01T11:00:00Z"));
+        assert_eq!(Ok(String::from("2030-01-01T12:00:00+01:00")), parse("2030-01-01T12:00:00+01:00"));
+        assert_eq!(Ok(String::from("2030-01-01T12:00:00+01:00")), parse("2030-01-01T12:00:00+0100"));
+        assert_eq!(Ok(String::from("2030-01-01T11:00:00+00:00")), parse("@1893495600"));
+
+        /* Durations from now */
+        assert_eq!(Ok(String::from("2030-01-06T00:00:00+01:00")), parse("+3d12h"));
+        assert_eq!(Ok(String::from("2030-01-16T13:30:10+01:00")), parse("+2w90m10s"));
+
+        /* Phrases */
+        assert_eq!(Ok(String::from("2030-01-02T00:00:00+01:00")), parse("today"));
+        assert_eq!(Ok(String::from("2030-01-04T17:30:00+01:00")), parse("friday 17:30"));
+        assert_eq!(Ok(String::from("2030-01-09T08:00:00+01:00")), parse("wednesday 08:00"));
+        assert_eq!(Ok(String::from("2030-07-01T09:00:00+00:00")), parse("2030-07-01 09:00:00 UTC"));
+
+        /* Phrases in IANA time zones */
+        #[cfg(feature = "timezones")]
+        {
+            assert_eq!(Ok(String::from("2030-01-03T09:00:00+01:00")), parse("tomorrow 09:00 Europe/Vienna"));
+            assert_eq!(Ok(String::from("2030-01-03T09:00:00-05:00")), parse("Tomorrow 09:00 America/New_York"));
+            /* It is already the 3rd in Kiritimati */
+            assert_eq!(Ok(String::from("2030-01-04T09:00:00+14:00")), parse("tomorrow 09:00 Pacific/Kiritimati"));
+            assert_eq!(Ok(String::from("2030-07-01T09:00:00+02:00")), parse("2030-07-01 09:00 Europe/Vienna"));
+            assert!(parse("2030-03-31 02:30 Europe/Vienna").unwrap_err().contains("does not exist"));
+            assert_eq!(Err(String::from("The lockdate \"tomorrow Mars/Olympus\" is invalid.")), parse("tomorrow Mars/Olympus"));
+        }
+        #[cfg(not(feature = "timezones"))]
+        assert!(parse("tomorrow 09:00 Europe/Vienna").unwrap_err().contains("\"timezones\""));
+
+        /* Invalid lockdates */
+        for invalid in ["", "+", "+3x", "+3", "@", "@-1", "@99999999999999999999", "someday", "tomorrow 25:00"] {
+            assert_eq!(Err(format!("The lockdate \"{}\" is invalid.", invalid)), parse(invalid));
+        }
+
+        /* The constructors of the encryption arguments */
+        let args = client::ClientEncryptArg::new_at("Hello World", "+1h", now).unwrap();
+        assert_eq!("2030-01-02T13:00:00+01:00", args.lockdate.to_rfc3339());
+        assert_eq!("Hello World", args.plaintext);
+        assert!(client::ClientEncryptArg::new("Hello World", "someday").is_err());
+    }
+
+    #[test]
+    fn client_trait_defaults() {
+        /* Clients written before passphrases and early-unlock recipients only implement the original methods */
+        struct PlainClient;
+
+        impl client::Client for PlainClient {
+            fn encrypt(&self, _args: &client::ClientEncryptArg) -> Result<String, String> {
+                Err(String::from("unused"))
+            }
+
+            fn decrypt(&self, _ciphertext: &str)
+                -> Result<client::ClientDecryptResultSuccess, client::ClientDecryptResultFailure> {
+                Ok(client::ClientDecryptResultSuccess {
+                    plaintext: String::from("Hello World"),
+                    hint: String::from(""),
+                    filename: String::from(""),
+                })
+            }
+
+            fn lockdate_from_snailcrypt_cipher(&self, _ciphertext: &str) -> Result<DateTime<FixedOffset>, String> {
+                Err(String::from("unused"))
+            }
+
+            fn get_datetime_format(&self) -> &str {
+                format::DATETIME_FORMAT
+            }
+
+            fn get_client_version(&self) -> client::ClientVersion {
+                client::ClientVersion::V2
+            }
+        }
+
+        let plain_client: &dyn client::Client = &PlainClient;
+        assert_eq!("Hello World", plain_client.decrypt_with_passphrase("2:aGVsbG8=", "secret").unwrap().plaintext);
+        assert_eq!("Client version 2 does not support early-unlock recipients.",
+                   plain_client.decrypt_with_escape_key("2:aGVsbG8=", "", "").unwrap_err().error_message);
+        let cipher = format!("2:{}:cGF5bG9hZA==:{}", base64::encode("2030-01-01T12:00:00+0100"), base64::encode("A hint"));
+        assert_eq!("A hint", plain_client.inspect(cipher.as_str()).unwrap().hint);
+    }
+
+    #[test]
+    fn lockdate_policy() {
+        use chrono::{
+            Duration,
+            Timelike,
+            Utc,
+        };
+        use lockdate::{
+            LockdatePolicy,
+            LockdatePolicyError,
+        };
+
+        let mut policy = LockdatePolicy::new();
+        let now: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2030-01-01T12:00:00+01:00").unwrap();
+        let at = |lockdate: &str| DateTime::parse_from_rfc3339(lockdate).unwrap();
+
+        /* A new policy accepts everything */
+        assert_eq!(Ok(at("2022-11-19T17:00:00+01:00")), policy.apply(at("2022-11-19T17:00:00+01:00"), now));
+
+        policy.set_min_lead_time(Some(Duration::minutes(20)));
+        policy.set_granularity(Some(Duration::minutes(15)));
+        policy.set_max_horizon(Some(Duration::days(365)));
+
+        /* Rounded up to the next slot keeping the offset */
+        assert_eq!(Ok(at("2030-01-01T13:15:00+01:00")), policy.apply(at("2030-01-01T13:00:01+01:00"), now));
+        assert_eq!(Ok(at("2030-01-02T13:15:00+05:30")), policy.apply(at("2030-01-02T13:07:00+05:30"), now));
+        assert_eq!(Ok(at("2030-01-01T13:00:00+01:00")), policy.apply(at("2030-01-01T13:00:00+01:00"), now));
+        assert_eq!(Ok(at("2030-01-01T13:15:00+01:00")), policy.apply(at("2030-01-01T13:00:00.5+01:00"), now));
+
+        /* Violations */
+        assert!(matches!(policy.apply(at("2030-01-01T11:00:00+01:00"), now), Err(LockdatePolicyError::Past { .. })));
+        assert_eq!(Err(LockdatePolicyError::TooNear {
+                       lockdate: at("2030-01-01T11:15:00Z"),
+                       earliest: at("2030-01-01T12:20:00+01:00"),
+                   }),
+                   policy.apply(at("2030-01-01T11:01:00Z"), now));
+        assert!(matches!(policy.apply(at("2031-06-01T12:00:00+01:00"), now),
+                         Err(LockdatePolicyError::BeyondHorizon { .. })));
+        assert!(policy.apply(at("2030-01-01T11:00:00+01:00"), now).unwrap_err().to_string().contains("already passed"));
+
+        /* Clients reject violations and encrypt until the rounded lockdate */
+        let server = util::StandInServer::start();
+        let analyzer: Arc<dyn util::Analyzer> = factory::AnalyzerFactory::new().create();
+        let mut config = config::DefaultConfig::new_with_api_url(server.get_api_url());
+        let mut policy = LockdatePolicy::new();
+        policy.set_min_lead_time(Some(Duration::zero()));
+        policy.set_granularity(Some(Duration::hours(1)));
+        config.set_lockdate_policy(Some(policy));
+        let config: Arc<dyn config::Config> = Arc::new(config);
+        let client = factory::ClientFactory::new(Arc::clone(&analyzer), Arc::clone(&config)).create();
+
+        let past = client::ClientEncryptArg::new_with_lockdate("Hello World", at("2022-11-19T17:00:00+01:00"));
+        assert!(client.encrypt(&past).unwrap_err().contains("already passed"));
+        assert!(matches!(client.check_lockdate(past.lockdate), Err(LockdatePolicyError::Past { .. })));
+
+        let future = client::ClientEncryptArg::new_with_lockdate("Hello World",
+                                                                 (Utc::now() + Duration::minutes(90)).fixed_offset());
+        let cipher = client.encrypt(&future).unwrap();
+        let lockdate = format::inspect(cipher.as_str()).unwrap().lockdate;
+        assert!(lockdate > future.lockdate);
+        assert_eq!((0, 0), (lockdate.minute(), lockdate.second()));
+
+        /* Revocable messages are registered for the rounded lockdate */
+        let v4_client = client::V4Client::new(Arc::clone(&analyzer), Arc::clone(&config));
+        let registration = v4_client.register_message(future.lockdate).unwrap();
+        assert_eq!(lockdate, registration.lockdate);
+        assert!(v4_client.encrypt_revocable(&future, &registration).is_ok());
+        assert_eq!(Ok(lockdate), client.check_lockdate(future.lockdate));
+
+        /* The C interface reports violations using their own code */
+        #[cfg(feature = "ffi")]
+        {
+            use std::ffi::CString;
+
+            let api_url = CString::new(server.get_api_url()).unwrap();
+            let ffi_config = ffi::SnailcryptConfig {
+                api_url: api_url.as_ptr(),
+                connect_timeout_ms: 0,
+                timeout_ms: 0,
+                cache_dir: std::ptr::null(),
+                reject_past_lockdates: true,
+                min_lead_time_s: 0,
+                lockdate_granularity_s: 0,
+                max_horizon_s: 0,
+            };
+
+            unsafe extern "C" fn on_cipher(_cipher: *const std::os::raw::c_char) -> i32 {
+                0
+            }
+
+            let plaintext = CString::new("Hello World").unwrap();
+            let past = CString::new("2022-11-19T17:00:00+0100").unwrap();
+            let empty = CString::new("").unwrap();
+            unsafe {
+                let ffi_client = ffi::snailcrypt_client_new(&ffi_config);
+                assert!(!ffi_client.is_null());
+                assert_eq!(ffi::SNAILCRYPT_ERROR_LOCKDATE_POLICY,
+                           ffi::snailcrypt_client_encrypt(ffi_client, plaintext.as_ptr(), past.as_ptr(),
+                                                          empty.as_ptr(), empty.as_ptr(), Some(on_cipher)));
+                ffi::snailcrypt_client_free(ffi_client);
+            }
+        }
+    }
+
+    #[test]
+    fn clock_controls_unlock() {
+        use chrono::Duration;
+
+        let start: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2030-01-01T10:00:00Z").unwrap();
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2030-01-01T12:00:00Z").unwrap();
+
+        /* The key server and the client share a manual clock */
+        let manual_clock = Arc::new(clock::ManualClock::new(start.to_utc()));
+        let server = util::StandInServer::start_with_clock(manual_clock.clone());
+
+        let mut config = config::DefaultConfig::new_with_api_url(server.get_api_url());
+        let mut policy = lockdate::LockdatePolicy::new();
+        policy.set_min_lead_time(Some(Duration::zero()));
+        config.set_lockdate_policy(Some(policy));
+        config.set_clock(Some(manual_clock.clone()));
+
+        let client_factory = factory::ClientFactory::new(factory::AnalyzerFactory::new().create(), Arc::new(config));
+        let client: Arc<dyn client::Client> = client_factory.create();
+
+        let args = client::ClientEncryptArg::new_with_lockdate("Hello World", lockdate);
+        let cipher = client.encrypt(&args).unwrap();
+
+        /* Locked until the lockdate */
+        let inspect_result = client.inspect(cipher.as_str()).unwrap();
+        assert!(inspect_result.is_locked(manual_clock.as_ref()));
+        assert_eq!(Duration::hours(2), inspect_result.get_time_until_unlock(manual_clock.as_ref()));
+        assert_eq!(client::ClientDecryptErrorKind::NotYetReleased,
+                   client.decrypt(cipher.as_str()).unwrap_err().kind);
+
+        manual_clock.advance(Duration::minutes(119));
+        assert!(inspect_result.is_locked(manual_clock.as_ref()));
+        assert_eq!(client::ClientDecryptErrorKind::NotYetReleased,
+                   client.decrypt(cipher.as_str()).unwrap_err().kind);
+
+        /* Unlocked after the lockdate */
+        manual_clock.advance(Duration::minutes(1));
+        assert!(!inspect_result.is_locked(manual_clock.as_ref()));
+        assert_eq!(Duration::zero(), inspect_result.get_time_until_unlock(manual_clock.as_ref()));
+        assert_eq!("Hello World", client.decrypt(cipher.as_str()).unwrap().plaintext);
+
+        /* The lockdate policy follows the clock as well */
+        assert!(client.encrypt(&args).unwrap_err().contains("already passed"));
+
+        /* So do relative lockdates of the C interface, the bindings and the command line tool */
+        let relative_lockdate = lockdate::Lockdate::parse_with_clock("+1h", client.get_clock().as_ref()).unwrap();
+        assert_eq!(lockdate + Duration::hours(1), relative_lockdate.get_datetime());
+        let cipher = client.encrypt(&client::ClientEncryptArg::new_with_lockdate("Hello World", relative_lockdate.get_datetime())).unwrap();
+        assert_eq!(lockdate + Duration::hours(1), client.inspect(cipher.as_str()).unwrap().lockdate);
+    }
+
+    #[test]
+    fn trusted_clock_follows_key_server() {
+        use chrono::Duration;
+        use clock::Clock;
+
+        let at = |instant: &str| DateTime::parse_from_rfc3339(instant).unwrap().to_utc();
+
+        /* The clock of the device is years behind the key server */
+        let server_clock = Arc::new(clock::ManualClock::new(at("2030-01-01T12:00:00Z")));
+        let server = util::StandInServer::start_with_clock(server_clock.clone());
+        let local_clock = Arc::new(clock::ManualClock::new(at("2025-06-01T00:00:00Z")));
+
+        let mut config = config::DefaultConfig::new_with_api_url(server.get_api_url());
+        config.set_clock(Some(local_clock.clone()));
+        let client_factory = factory::ClientFactory::new(factory::AnalyzerFactory::new().create(), Arc::new(config));
+        let client = client_factory.create();
+        let trusted_clock = client_factory.create_trusted_clock();
+
+        assert_eq!(None, trusted_clock.get_skew());
+        assert_eq!(Ok(at("2030-01-01T12:00:00.5Z")), trusted_clock.trusted_now());
+        assert_eq!(Some(at("2030-01-01T12:00:00.5Z") - at("2025-06-01T00:00:00Z")), trusted_clock.get_skew());
+
+        /* Countdowns use the time of the key server */
+        let lockdate: DateTime<FixedOffset> = DateTime::parse_from_rfc3339("2030-01-01T13:00:00Z").unwrap();
+        let cipher = client.encrypt(&client::ClientEncryptArg::new_with_lockdate("Hello World", lockdate)).unwrap();
+        let inspect_result = client.inspect(cipher.as_str()).unwrap();
+        assert_eq!(3599, inspect_result.get_time_until_unlock(trusted_clock.as_ref()).num_seconds());
+        assert!(inspect_result.get_time_until_unlock(local_clock.as_ref()) > Duration::days(365));
+
+        /* Waiting moves the local clock until the trusted time reaches the lockdate */
+        trusted_clock.sleep_until(lockdate.to_utc());
+        assert!(!inspect_result.is_locked(trusted_clock.as_ref()));
+        assert!(local_clock.now() < at("2026-01-01T00:00:00Z"));
+
+        server_clock.set(lockdate.to_utc());
+        assert_eq!("Hello World", client.decrypt(cipher.as_str()).unwrap().plaintext);
+
+        /* An unreachable key server is an error, as a clock the local time is used */
+        let unreachable_config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url("http://127.0.0.1:1"));
+        let unreachable_clock = clock::TrustedClock::new(Arc::new(clock::KeyServerTimeSource::new(unreachable_config)),
+                                                         local_clock.clone());
+        assert!(unreachable_clock.trusted_now().is_err());
+        assert_eq!(local_clock.now(), unreachable_clock.now());
+        assert_eq!(Err(local_clock.now()), unreachable_clock.now_or_local());
+
+        /* A key server which never answers delays reading the time by the probe timeout at most */
+        let silent_listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let silent_api_url = format!("http://{}", silent_listener.local_addr().unwrap());
+        let silent_config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(silent_api_url.as_str()));
+        let mut silent_source = clock::KeyServerTimeSource::new(silent_config);
+        assert_eq!(clock::DEFAULT_PROBE_TIMEOUT, silent_source.get_probe_timeout());
+        silent_source.set_probe_timeout(std::time::Duration::from_millis(200));
+        let silent_clock = clock::TrustedClock::new(Arc::new(silent_source), local_clock.clone());
+        let read_start = std::time::Instant::now();
+        assert_eq!(Err(local_clock.now()), silent_clock.now_or_local());
+        assert!(read_start.elapsed() < std::time::Duration::from_secs(3));
+
+        /* The C interface counts down using the key server of the handle */
+        #[cfg(feature = "ffi")]
+        {
+            use std::ffi::CString;
+
+            let api_url = CString::new(server.get_api_url()).unwrap();
+            let ffi_config = ffi::SnailcryptConfig {
+                api_url: api_url.as_ptr(),
+                connect_timeout_ms: 0,
+                timeout_ms: 0,
+                cache_dir: std::ptr::null(),
+                reject_past_lockdates: false,
+                min_lead_time_s: 0,
+                lockdate_granularity_s: 0,
+                max_horizon_s: 0,
+            };
+            server_clock.set(at("2030-01-01T12:00:00Z"));
+
+            let c_cipher = CString::new(cipher.as_str()).unwrap();
+            let mut seconds: i64 = -1;
+            unsafe {
+                let handle = ffi::snailcrypt_client_new(&ffi_config);
+                assert_eq!(ffi::SNAILCRYPT_OK, ffi::snailcrypt_client_seconds_until_unlock(handle, c_cipher.as_ptr(), &mut seconds));
+                ffi::snailcrypt_client_free(handle);
+            }
+            assert!((3598..=3600).contains(&seconds));
+
+            /* Without timeouts a silent key server delays the countdown by the default probe timeout and the
+             * fallback to the local clock is reported */
+            let silent_api_url = CString::new(silent_api_url).unwrap();
+            let silent_ffi_config = ffi::SnailcryptConfig {
+                api_url: silent_api_url.as_ptr(),
+                ..ffi_config
+            };
+            let read_start = std::time::Instant::now();
+            unsafe {
+                let handle = ffi::snailcrypt_client_new(&silent_ffi_config);
+                assert_eq!(ffi::SNAILCRYPT_WARNING_LOCAL_CLOCK, ffi::snailcrypt_client_seconds_until_unlock(handle, c_cipher.as_ptr(), &mut seconds));
+                ffi::snailcrypt_client_free(handle);
+            }
+            assert!(read_start.elapsed() < clock::DEFAULT_PROBE_TIMEOUT * 2);
+            assert!(seconds > 0);
+        }
+    }
+
+    #[test]
+    fn server_capabilities_discovery() {
+        use api::{
+            ServerCapabilities,
+            ServerKeyAlgorithm,
+        };
+        use chrono::{
+            Duration,
+            Timelike,
+            Utc,
+        };
+        use clock::Clock;
+        use lockdate::LockdatePolicy;
+        use serde_json::json;
+
+        /* Parsing leaves unknown attributes unknown */
+        let capabilities = ServerCapabilities::from_json(&json!({
+            "api_version": 2,
+            "key_algorithms": [{ "name": "RSA", "sizes": [2048, 4096] }],
+            "lockdate_granularity": 900,
+            "max_horizon": -1,
+            "server_time": "2030-01-01T12:00:00+01:00",
+        })).unwrap();
+        assert!(capabilities.is_discovered());
+        assert_eq!(2, capabilities.get_api_version());
+        assert_eq!(&[ServerKeyAlgorithm { name: String::from("RSA"), sizes: vec![2048, 4096] }],
+                   capabilities.get_key_algorithms());
+        assert_eq!(Some(Duration::minutes(15)), capabilities.get_lockdate_granularity());
+        assert_eq!(None, capabilities.get_max_horizon());
+        assert_eq!(Some(DateTime::parse_from_rfc3339("2030-01-01T11:00:00Z").unwrap().to_utc()),
+                   capabilities.get_server_time());
+        assert_eq!(None, ServerCapabilities::from_json(&json!({ "code": 404, "message": "Not found" })));
+
+        /* Attributes of an unexpected type only leave themselves unknown */
+        let capabilities_with_invalid = ServerCapabilities::from_json(&json!({
+            "api_version": 1,
+            "key_algorithms": [{ "name": "RSA", "sizes": [2048, "big"] }, { "sizes": [4096] }],
+            "lockdate_granularity": "hourly",
+            "server_time": 42,
+        })).unwrap();
+        assert_eq!(&[ServerKeyAlgorithm { name: String::from("RSA"), sizes: vec![2048] }],
+                   capabilities_with_invalid.get_key_algorithms());
+        assert_eq!(None, capabilities_with_invalid.get_lockdate_granularity());
+        assert_eq!(None, capabilities_with_invalid.get_server_time());
+
+        /* Keys and policies are checked against the capabilities */
+        assert!(capabilities.check_key("rsa", 4096).is_ok());
+        assert!(capabilities.check_key("RSA", 1024).unwrap_err().contains("does not advertise"));
+        assert!(capabilities.check_key("EC", 256).unwrap_err().contains("does not offer EC keys"));
+        assert!(ServerCapabilities::fallback().check_key("EC", 256).is_ok());
+        assert_eq!(None, ServerCapabilities::fallback().restrict_policy(None));
+
+        let mut policy = LockdatePolicy::new();
+        policy.set_granularity(Some(Duration::hours(1)));
+        policy.set_max_horizon(Some(Duration::days(7)));
+        let restricted_policy = capabilities.restrict_policy(Some(&policy)).unwrap();
+        assert_eq!(Some(Duration::hours(1)), restricted_policy.get_granularity());
+        assert_eq!(Some(Duration::days(7)), restricted_policy.get_max_horizon());
+        assert_eq!(Some(Duration::minutes(15)), capabilities.restrict_policy(None).unwrap().get_granularity());
+
+        /* Granularities which are no multiples of each other meet at their least common multiple */
+        policy.set_granularity(Some(Duration::minutes(40)));
+        assert_eq!(Some(Duration::hours(2)), capabilities.restrict_policy(Some(&policy)).unwrap().get_granularity());
+
+        /* Only known API versions are spoken */
+        assert!(capabilities.check_api_version().unwrap_err().contains("API version 2"));
+        assert!(ServerCapabilities::fallback().check_api_version().is_ok());
+
+        /* Clients follow the limits of the key server even without a policy of their own */
+        let manual_clock = Arc::new(clock::ManualClock::new(Utc::now()));
+        let server = util::StandInServer::start_with_metadata(manual_clock.clone(), Some(json!({
+            "api_version": 1,
+            "lockdate_granularity": 3600,
+            "max_horizon": 30 * 24 * 3600,
+        })));
+        let analyzer: Arc<dyn util::Analyzer> = factory::AnalyzerFactory::new().create();
+        let mut config = config::DefaultConfig::new_with_api_url(server.get_api_url());
+        config.set_clock(Some(manual_clock.clone()));
+        let config: Arc<dyn config::Config> = Arc::new(config);
+
+        let capabilities = ServerCapabilities::discover(&api::KeyServerApi::new(Arc::clone(&config))).unwrap();
+        assert!(capabilities.is_discovered());
+        assert_eq!(0, (manual_clock.now() - capabilities.get_server_time().unwrap()).num_seconds());
+
+        let client = factory::ClientFactory::new(Arc::clone(&analyzer), Arc::clone(&config)).create();
+        let future = client::ClientEncryptArg::new_with_lockdate("Hello World",
+                                                                 (manual_clock.now() + Duration::minutes(90)).fixed_offset());
+        let lockdate = format::inspect(client.encrypt(&future).unwrap().as_str()).unwrap().lockdate;
+        assert!(lockdate > future.lockdate);
+        assert_eq!((0, 0), (lockdate.minute(), lockdate.second()));
+
+        let distant = client::ClientEncryptArg::new_with_lockdate("Hello World",
+                                                                  (manual_clock.now() + Duration::days(60)).fixed_offset());
+        assert!(client.encrypt(&distant).unwrap_err().contains("too far away"));
+
+        /* Keys the key server does not advertise are rejected */
+        let server = util::StandInServer::start_with_metadata(manual_clock.clone(), Some(json!({
+            "api_version": 1,
+            "key_algorithms": [{ "name": "RSA", "sizes": [4096] }],
+        })));
+        let client = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                                 Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url())))
+            .create();
+        assert!(client.encrypt(&future).unwrap_err().contains("does not advertise"));
+
+        /* Key servers of a newer API version are not accessed */
+        let server = util::StandInServer::start_with_metadata(manual_clock.clone(), Some(json!({
+            "api_version": api::SUPPORTED_API_VERSION + 1,
+        })));
+        let client = factory::ClientFactory::new(Arc::clone(&analyzer),
+                                                 Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url())))
+            .create();
+        assert!(client.encrypt(&future).unwrap_err().contains("API version"));
+        let cipher = format!("1:{}:cGF5bG9hZA==", base64::encode("2022-11-19T17:00:00+0100"));
+        assert!(client.decrypt(cipher.as_str()).unwrap_err().error_message.contains("API version"));
+
+        /* Key servers without metadata get the fallback capabilities */
+        let server = util::StandInServer::start_with_metadata(manual_clock.clone(), None);
+        let config: Arc<dyn config::Config> = Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url()));
+        assert_eq!(ServerCapabilities::fallback(), ServerCapabilities::discover(&api::KeyServerApi::new(Arc::clone(&config))).unwrap());
+        let client = factory::ClientFactory::new(Arc::clone(&analyzer), config).create();
+        assert!(client.encrypt(&distant).is_ok());
+
+        /* Only an unreachable key server fails the discovery */
+        let mut config = config::DefaultConfig::new_with_api_url("http://127.0.0.1:1");
+        config.set_connect_timeout(Some(std::time::Duration::from_millis(1000)));
+        assert!(ServerCapabilities::discover(&api::KeyServerApi::new(Arc::new(config))).unwrap_err().is_unreachable());
+    }
+
+    #[test]
+    fn key_server_api_typed() {
+        use api::{
+            ApiError,
+            KeyResponse,
+            KeyServerApi,
+        };
+        use chrono::{
+            Duration,
+            Utc,
+        };
+        use serde_json::{
+            json,
+            Value,
+        };
+
+        let server = util::StandInServer::start();
+        let api = KeyServerApi::new(Arc::new(config::DefaultConfig::new_with_api_url(server.get_api_url())));
+
+        /* Keys are fetched as PEM, the private key only once released */
+        let future = (Utc::now() + Duration::hours(1)).fixed_offset();
+        assert!(api.get_public_key_pem(future).unwrap().starts_with("-----BEGIN PUBLIC KEY-----"));
+        assert_eq!(None, api.get_private_key_pem(future).unwrap());
+        let past = (Utc::now() - Duration::hours(1)).fixed_offset();
+        assert!(api.get_private_key_pem(past).unwrap().unwrap().contains("PRIVATE KEY"));
+
+        /* Error statuses keep the code and the message of the key server */
+        let error = api.post::<Value, KeyResponse>("/keys", &json!({ "lock_date": "someday" })).unwrap_err();
+        assert_eq!(ApiError { code: String::from("400"), message: String::from("Invalid lock date"), status: Some(400) },
+                   error);
+        assert_eq!("Invalid lock date", error.to_string());
+        assert_eq!(Some(404), api.post::<Value, Value>("/unknown", &json!({})).unwrap_err().status);
+
+        /* Messages are registered, queried and revoked using typed requests */
+        let registration = api.register_message(&api::MessageRequest::new(future)).unwrap();
+        assert!(registration.public_key.contains("PUBLIC KEY"));
+        let message_id_request = api::MessageIdRequest { message_id: registration.message_id.clone() };
+        assert_eq!("pending", api.get_message_status(&message_id_request).unwrap().status);
+        assert_eq!(None, api.get_message_key(&message_id_request).unwrap().private_key);
+        api.revoke_message(&api::MessageRevocationRequest {
+            message_id: registration.message_id.clone(),
+            revocation_token: registration.revocation_token.clone(),
+        }).unwrap();
+        assert_eq!("revoked", api.get_message_key(&message_id_request).unwrap().status);
+
+        /* The metadata and the time of the key server are fetched using the same transport */
+        assert!(api.get_metadata().unwrap().is_some());
+        assert!((Utc::now() - api.get_server_date(None, None).unwrap()).num_seconds().abs() <= 2);
+
+        /* Codes are accepted as number or as string */
+        assert_eq!("E42", serde_json::from_value::<ApiError>(json!({ "code": "E42", "message": "" })).unwrap().code);
+        assert_eq!("7", serde_json::from_value::<ApiError>(json!({ "code": 7 })).unwrap().code);
+
+        /* Transports without a status are handled by the error code alone */
+        struct FixedTransport {
+            body: &'static str,
+        }
+
+        impl transport::Transport for FixedTransport {
+            fn post(&self, _url: &str, _body: &str, _connect_timeout: Option<std::time::Duration>,
+                    _timeout: Option<std::time::Duration>) -> Result<Vec<u8>, String> {
+                Ok(self.body.as_bytes().to_vec())
+            }
+
+            fn get(&self, _url: &str, _connect_timeout: Option<std::time::Duration>,
+                   _timeout: Option<std::time::Duration>) -> Result<Vec<u8>, String> {
+                Ok(self.body.as_bytes().to_vec())
+            }
+        }
+
+        let fixed_api = |body: &'static str| KeyServerApi::new(Arc::new(config::OverrideConfig::new_with_transport(
+            Arc::new(config::DefaultConfig::new_with_api_url("http://127.0.0.1:1")),
+            Arc::new(FixedTransport { body }))));
+
+        let error = fixed_api(r#"{"code":"500","message":"Internal error"}"#).get_public_key_pem(future).unwrap_err();
+        assert_eq!(("500", None), (error.code.as_str(), error.status));
+        assert_eq!(api::API_ERROR_INVALID_RESPONSE,
+                   fixed_api(r#"{"private_key":"x"}"#).get_public_key_pem(future).unwrap_err().code);
+        assert_eq!(api::API_ERROR_INVALID_RESPONSE,
+                   fixed_api("<html></html>").get_public_key_pem(future).unwrap_err().code);
+        assert_eq!("-----BEGIN PUBLIC KEY-----",
+                   fixed_api(r#"{"public_key":"'-----BEGIN PUBLIC KEY-----'"}"#).get_public_key_pem(future).unwrap());
+
+        /* Clients report malformed answers instead of panicking */
+        let analyzer: Arc<dyn util::Analyzer> = factory::AnalyzerFactory::new().create();
+        let client = factory::ClientFactory::new(analyzer, Arc::new(config::OverrideConfig::new_with_transport(
+            Arc::new(config::DefaultConfig::new_with_api_url("http://127.0.0.1:1")),
+            Arc::new(FixedTransport { body: r#"{"public_key":"no key"}"# })))).create();
+        let args = client::ClientEncryptArg::new_with_lockdate("Hello World", future);
+        assert!(client.encrypt(&args).unwrap_err().contains("unable to parse the public key"));
+
+        /* Only missing answers are unreachable */
+        let mut config = config::DefaultConfig::new_with_api_url("http://127.0.0.1:1");
+        config.set_connect_timeout(Some(std::time::Duration::from_millis(1000)));
+        let unreachable_api = KeyServerApi::new(Arc::new(config));
+        assert!(unreachable_api.get_public_key_pem(future).unwrap_err().is_unreachable());
+        assert!(unreachable_api.get_metadata().unwrap_err().is_unreachable());
+
+        /* Answers without metadata are no error */
+        assert_eq!(None, fixed_api(r#"{"code":404,"message":"Not found"}"#).get_metadata().unwrap());
+    }
 }
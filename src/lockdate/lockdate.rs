@@ -0,0 +1,283 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    clock::{
+        self,
+        Clock,
+    },
+    format::DATETIME_FORMAT,
+};
+
+use std::{
+    fmt,
+    str::FromStr,
+};
+
+use chrono::{
+    DateTime,
+    Datelike,
+    Duration,
+    FixedOffset,
+    Local,
+    LocalResult,
+    NaiveDate,
+    NaiveDateTime,
+    NaiveTime,
+    TimeZone,
+    Weekday,
+};
+#[cfg(feature = "timezones")]
+use chrono_tz::Tz;
+#[cfg(not(feature = "timezones"))]
+use chrono::Utc as Tz;
+
+/// A lockdate given by a user. Besides the format within cipher texts it accepts
+/// - RFC 3339 with `Z` or a colon offset, e.g. `2030-01-01T12:00:00Z` or `2030-01-01T12:00:00+01:00`,
+/// - Unix timestamps in seconds prefixed by `@`, e.g. `@1893499200`. Digits alone are rejected, so a mistyped date
+///   like `20301231` is not taken for a point in 1970,
+/// - durations from now of weeks, days, hours, minutes and seconds, e.g. `+3d12h` or `+90m`,
+/// - a day followed by an optional time and an optional IANA time zone, e.g. `tomorrow 09:00 Europe/Vienna`,
+///   `friday 17:30` or `2030-01-01 12:00 UTC`. The day is `today`, `tomorrow`, a weekday meaning its next occurrence
+///   after today, or a date. Without a time zone the offset of now is used. Time zones other than `UTC` need the
+///   feature `timezones`.
+///
+/// Relative lockdates are resolved against an explicit point in time using `parse_at` or against a clock using
+/// `parse_with_clock`, so parsing is deterministic.
+///
+/// # Examples
+///
+/// ```
+/// use snailcrypt::lockdate::Lockdate;
+///
+/// use chrono::DateTime;
+///
+/// let now = DateTime::parse_from_rfc3339("2030-01-01T12:00:00+01:00").unwrap();
+///
+/// let lockdate = Lockdate::parse_at("tomorrow 09:00", now).unwrap();
+/// assert_eq!("2030-01-02T09:00:00+01:00", lockdate.get_datetime().to_rfc3339());
+///
+/// let lockdate = Lockdate::parse_at("+3d12h", now).unwrap();
+/// assert_eq!("2030-01-05T00:00:00+01:00", lockdate.get_datetime().to_rfc3339());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Lockdate {
+    datetime: DateTime<FixedOffset>,
+}
+
+impl Lockdate {
+    /// Parse a lockdate. Relative lockdates are resolved against the current local time.
+    pub fn parse(input: &str) -> Result<Lockdate, String> {
+        Lockdate::parse_with_clock(input, clock::default_clock().as_ref())
+    }
+
+    /// Parse a lockdate. Relative lockdates are resolved against the time of `clock` using the local offset, e.g.
+    /// the clock of the configuration of a client.
+    pub fn parse_with_clock(input: &str, clock: &dyn Clock) -> Result<Lockdate, String> {
+        Lockdate::parse_at(input, clock.now().with_timezone(&Local).fixed_offset())
+    }
+
+    /// Parse a lockdate. Relative lockdates are resolved against `now`.
+    pub fn parse_at(input: &str, now: DateTime<FixedOffset>) -> Result<Lockdate, String> {
+        let input = input.trim();
+        let invalid = || format!("The lockdate \"{}\" is invalid.", input);
+
+        /***********************************************************************
+         * Absolute points in time
+         */
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(input)
+            .or_else(|_| DateTime::parse_from_str(input, DATETIME_FORMAT)) {
+            return Ok(Lockdate { datetime });
+        }
+
+        if let Some(timestamp) = input.strip_prefix('@') {
+            let datetime = Some(timestamp)
+                .filter(|timestamp| !timestamp.is_empty() && timestamp.bytes().all(|byte| byte.is_ascii_digit()))
+                .and_then(|timestamp| timestamp.parse::<i64>().ok())
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp, 0))
+                .ok_or_else(invalid)?;
+            return Ok(Lockdate { datetime: datetime.fixed_offset() });
+        }
+
+        if !input.is_empty() && input.bytes().all(|byte| byte.is_ascii_digit()) {
+            return Err(format!("The lockdate \"{}\" is ambiguous. Prefix Unix timestamps with \"@\", e.g. \"@{}\".",
+                               input,
+                               input));
+        }
+
+        /***********************************************************************
+         * Relative points in time
+         */
+        if let Some(duration) = input.strip_prefix('+') {
+            let duration = parse_duration(duration).ok_or_else(invalid)?;
+            let datetime = now.checked_add_signed(duration).ok_or_else(invalid)?;
+            return Ok(Lockdate { datetime });
+        }
+
+        parse_phrase(input, now)
+            .and_then(|datetime| datetime.ok_or_else(invalid))
+            .map(|datetime| Lockdate { datetime })
+    }
+
+    /// Get the point in time of the lockdate.
+    pub fn get_datetime(&self) -> DateTime<FixedOffset> {
+        self.datetime
+    }
+}
+
+/// Parse a lockdate resolving relative lockdates against the current local time.
+impl FromStr for Lockdate {
+    type Err = String;
+
+    fn from_str(input: &str) -> Result<Lockdate, String> {
+        Lockdate::parse(input)
+    }
+}
+
+/// This method will print the lockdate using the format within cipher texts.
+impl fmt::Display for Lockdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.datetime.format(DATETIME_FORMAT))
+    }
+}
+
+impl From<Lockdate> for DateTime<FixedOffset> {
+    fn from(lockdate: Lockdate) -> DateTime<FixedOffset> {
+        lockdate.datetime
+    }
+}
+
+/// Parse a duration like `3d12h`. Returns `None` if the duration is invalid.
+fn parse_duration(input: &str) -> Option<Duration> {
+    let mut duration = Duration::zero();
+    let mut number = String::new();
+
+    for character in input.chars() {
+        if character.is_ascii_digit() {
+            number.push(character);
+            continue;
+        }
+
+        let value: i64 = number.parse().ok()?;
+        number.clear();
+        let part = match character {
+            'w' => Duration::try_weeks(value)?,
+            'd' => Duration::try_days(value)?,
+            'h' => Duration::try_hours(value)?,
+            'm' => Duration::try_minutes(value)?,
+            's' => Duration::try_seconds(value)?,
+            _ => return None,
+        };
+        duration = duration.checked_add(&part)?;
+    }
+
+    if !number.is_empty() || input.is_empty() {
+        return None;
+    }
+
+    Some(duration)
+}
+
+/// Parse a phrase like `tomorrow 09:00 Europe/Vienna`. Returns `Ok(None)` if the phrase is invalid and an error if it
+/// names a time which does not exist in its time zone.
+fn parse_phrase(input: &str, now: DateTime<FixedOffset>) -> Result<Option<DateTime<FixedOffset>>, String> {
+    let mut words = input.split_whitespace();
+
+    let day = match words.next() {
+        Some(day) => day.to_lowercase(),
+        None => return Ok(None),
+    };
+
+    let mut time = NaiveTime::MIN;
+    let mut timezone: Option<Tz> = None;
+    for word in words {
+        if let Ok(word_time) = NaiveTime::parse_from_str(word, "%H:%M:%S")
+            .or_else(|_| NaiveTime::parse_from_str(word, "%H:%M")) {
+            time = word_time;
+        } else if let (None, Some(word_timezone)) = (timezone, parse_timezone(word)) {
+            timezone = Some(word_timezone);
+        } else if cfg!(not(feature = "timezones")) && word.contains('/') {
+            return Err(format!("The lockdate \"{}\" names a time zone, which needs the feature \"timezones\".", input));
+        } else {
+            return Ok(None);
+        }
+    }
+
+    /***************************************************************************
+     * Resolve the day relative to today within the time zone of the lockdate
+     */
+    let today: NaiveDate = match timezone {
+        Some(timezone) => now.with_timezone(&timezone).date_naive(),
+        None => now.date_naive(),
+    };
+    let date: NaiveDate = match day.as_str() {
+        "today" => today,
+        "tomorrow" => match today.succ_opt() {
+            Some(date) => date,
+            None => return Ok(None),
+        },
+        _ => match (day.parse::<Weekday>(), NaiveDate::parse_from_str(day.as_str(), "%Y-%m-%d")) {
+            (Ok(weekday), _) => {
+                let days_ahead = (weekday.num_days_from_monday() + 7 - today.weekday().num_days_from_monday()) % 7;
+                match today.checked_add_signed(Duration::days(if days_ahead == 0 { 7 } else { days_ahead as i64 })) {
+                    Some(date) => date,
+                    None => return Ok(None),
+                }
+            },
+            (_, Ok(date)) => date,
+            _ => return Ok(None),
+        },
+    };
+
+    /***************************************************************************
+     * Attach the offset valid at that day and time
+     */
+    let naive_datetime = NaiveDateTime::new(date, time);
+    let datetime = match timezone {
+        Some(timezone) => match timezone.from_local_datetime(&naive_datetime) {
+            LocalResult::Single(datetime) | LocalResult::Ambiguous(datetime, _) => datetime.fixed_offset(),
+            LocalResult::None => return Err(format!("The lockdate \"{}\" does not exist in the time zone {}.",
+                                                    input,
+                                                    timezone)),
+        },
+        None => match now.offset().from_local_datetime(&naive_datetime) {
+            LocalResult::Single(datetime) => datetime,
+            _ => return Ok(None),
+        },
+    };
+
+    Ok(Some(datetime))
+}
+
+/// Parse an IANA time zone name like `Europe/Vienna`.
+#[cfg(feature = "timezones")]
+fn parse_timezone(input: &str) -> Option<Tz> {
+    input.parse::<Tz>().ok()
+}
+
+/// Parse a time zone name. Only `UTC` is known without the time zone database of the feature `timezones`.
+#[cfg(not(feature = "timezones"))]
+fn parse_timezone(input: &str) -> Option<Tz> {
+    if input == "UTC" { Some(Tz) } else { None }
+}
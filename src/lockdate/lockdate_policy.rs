@@ -0,0 +1,204 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::format::DATETIME_FORMAT;
+
+use std::{
+    error,
+    fmt,
+};
+
+use chrono::{
+    DateTime,
+    Duration,
+    FixedOffset,
+};
+
+/// Enumeration for the lockdates rejected by a lockdate policy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LockdatePolicyError {
+    /// The lockdate has already passed, so anybody could decrypt the cipher text right away.
+    Past {
+        lockdate: DateTime<FixedOffset>,
+        now: DateTime<FixedOffset>,
+    },
+    /// The lockdate is nearer than the minimum lead time.
+    TooNear {
+        lockdate: DateTime<FixedOffset>,
+        earliest: DateTime<FixedOffset>,
+    },
+    /// The lockdate is beyond the maximum horizon.
+    BeyondHorizon {
+        lockdate: DateTime<FixedOffset>,
+        latest: DateTime<FixedOffset>,
+    },
+}
+
+/// This method will print the reason of the rejection.
+impl fmt::Display for LockdatePolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockdatePolicyError::Past { lockdate, now } =>
+                write!(f, "The lockdate {} has already passed at {}.",
+                       lockdate.format(DATETIME_FORMAT),
+                       now.format(DATETIME_FORMAT)),
+            LockdatePolicyError::TooNear { lockdate, earliest } =>
+                write!(f, "The lockdate {} is too near, the earliest allowed lockdate is {}.",
+                       lockdate.format(DATETIME_FORMAT),
+                       earliest.format(DATETIME_FORMAT)),
+            LockdatePolicyError::BeyondHorizon { lockdate, latest } =>
+                write!(f, "The lockdate {} is too far away, the latest allowed lockdate is {}.",
+                       lockdate.format(DATETIME_FORMAT),
+                       latest.format(DATETIME_FORMAT)),
+        }
+    }
+}
+
+impl error::Error for LockdatePolicyError {}
+
+/// The rules a lockdate has to follow before anything is encrypted until it. A new policy accepts every lockdate.
+///
+/// # Examples
+///
+/// ```
+/// use snailcrypt::lockdate::{
+///     LockdatePolicy,
+///     LockdatePolicyError,
+/// };
+///
+/// use chrono::{
+///     DateTime,
+///     Duration,
+/// };
+///
+/// let mut policy = LockdatePolicy::new();
+/// policy.set_min_lead_time(Some(Duration::minutes(5)));
+/// policy.set_granularity(Some(Duration::hours(1)));
+///
+/// let now = DateTime::parse_from_rfc3339("2030-01-01T12:00:00+01:00").unwrap();
+/// let lockdate = DateTime::parse_from_rfc3339("2030-01-01T17:20:00+01:00").unwrap();
+/// assert_eq!("2030-01-01T18:00:00+01:00", policy.apply(lockdate, now).unwrap().to_rfc3339());
+///
+/// let lockdate = DateTime::parse_from_rfc3339("2029-12-31T12:00:00+01:00").unwrap();
+/// assert!(matches!(policy.apply(lockdate, now), Err(LockdatePolicyError::Past { .. })));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LockdatePolicy {
+    min_lead_time: Option<Duration>,
+    granularity: Option<Duration>,
+    max_horizon: Option<Duration>,
+}
+
+impl LockdatePolicy {
+    pub fn new() -> LockdatePolicy {
+        LockdatePolicy {
+            min_lead_time: None,
+            granularity: None,
+            max_horizon: None,
+        }
+    }
+
+    /// Set the minimum time between now and the lockdate. Zero rejects lockdates in the past. `None` accepts them.
+    pub fn set_min_lead_time(&mut self, min_lead_time: Option<Duration>) {
+        self.min_lead_time = min_lead_time;
+    }
+
+    /// Set the slot granularity of the key server. Lockdates are rounded up to the next slot, counted from the Unix
+    /// epoch. `None` keeps lockdates as they are.
+    pub fn set_granularity(&mut self, granularity: Option<Duration>) {
+        self.granularity = granularity;
+    }
+
+    /// Set the maximum time between now and the lockdate. `None` means no limit.
+    pub fn set_max_horizon(&mut self, max_horizon: Option<Duration>) {
+        self.max_horizon = max_horizon;
+    }
+
+    pub fn get_min_lead_time(&self) -> Option<Duration> {
+        self.min_lead_time
+    }
+
+    pub fn get_granularity(&self) -> Option<Duration> {
+        self.granularity
+    }
+
+    pub fn get_max_horizon(&self) -> Option<Duration> {
+        self.max_horizon
+    }
+
+    /// Check a lockdate against this policy at the point in time `now`. Returns the lockdate rounded to the slot
+    /// granularity, keeping its offset.
+    pub fn apply(&self, lockdate: DateTime<FixedOffset>, now: DateTime<FixedOffset>)
+        -> Result<DateTime<FixedOffset>, LockdatePolicyError> {
+        /***********************************************************************
+         * Round up to the next slot
+         */
+        let lockdate = match self.granularity {
+            Some(granularity) => round_up(lockdate, granularity),
+            None => lockdate,
+        };
+
+        /***********************************************************************
+         * Check the distance to now
+         */
+        if let Some(min_lead_time) = self.min_lead_time {
+            if lockdate <= now {
+                return Err(LockdatePolicyError::Past { lockdate, now });
+            }
+
+            let earliest = now + min_lead_time;
+            if lockdate < earliest {
+                return Err(LockdatePolicyError::TooNear { lockdate, earliest });
+            }
+        }
+
+        if let Some(max_horizon) = self.max_horizon {
+            let latest = now + max_horizon;
+            if lockdate > latest {
+                return Err(LockdatePolicyError::BeyondHorizon { lockdate, latest });
+            }
+        }
+
+        Ok(lockdate)
+    }
+}
+
+/// Round a point in time up to the next multiple of the granularity since the Unix epoch.
+fn round_up(lockdate: DateTime<FixedOffset>, granularity: Duration) -> DateTime<FixedOffset> {
+    let granularity = granularity.num_seconds();
+    if granularity <= 0 {
+        return lockdate;
+    }
+
+    let mut timestamp = lockdate.timestamp();
+    if lockdate.timestamp_subsec_nanos() > 0 {
+        timestamp += 1;
+    }
+    let remainder = timestamp.rem_euclid(granularity);
+    let rounded = if remainder == 0 { timestamp } else { timestamp - remainder + granularity };
+
+    DateTime::from_timestamp(rounded, 0)
+        .map(|rounded| rounded.with_timezone(lockdate.offset()))
+        .unwrap_or(lockdate)
+}
@@ -0,0 +1,212 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    client::{
+        Client,
+        ClientEncryptArg,
+    },
+    clock::SystemClock,
+    factory,
+    lockdate,
+    python::{
+        config::PyConfig,
+        error::{
+            decrypt_error,
+            EncryptionError,
+            InvalidCipherError,
+            LockdatePolicyError,
+        },
+    },
+};
+
+use std::sync::Arc;
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+    types::PyBytes,
+};
+
+/// A plain text passed from Python. Binary content is locked like a file.
+#[derive(FromPyObject)]
+enum Plaintext {
+    Text(String),
+    Bytes(Vec<u8>),
+}
+
+/// A lockdate passed from Python, either as a timezone aware `datetime` or as a string.
+#[derive(FromPyObject)]
+enum Lockdate {
+    DateTime(DateTime<FixedOffset>),
+    Text(String),
+}
+
+/// A client for encrypting and decrypting snailcrypt cipher texts. A client can be shared between Python threads. The GIL
+/// is released while encrypting and decrypting, so other threads keep running while the key server is accessed.
+#[pyclass(name = "Client", module = "snailcrypt")]
+pub struct PyClient {
+    client: Arc<dyn Client>,
+}
+
+/// The result of a successful decryption. `data` holds the decoded content of a file or the UTF-8 encoded plain text otherwise.
+#[pyclass(name = "DecryptResult", module = "snailcrypt", get_all)]
+pub struct PyDecryptResult {
+    plaintext: String,
+    hint: String,
+    filename: String,
+}
+
+#[pymethods]
+impl PyDecryptResult {
+    #[getter]
+    fn data<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyBytes>> {
+        if self.filename.is_empty() {
+            return Ok(PyBytes::new_bound(py, self.plaintext.as_bytes()));
+        }
+
+        let data = base64::decode(self.plaintext.as_str())
+            .map_err(|error| PyValueError::new_err(format!("The content of the file is invalid: {}", error)))?;
+
+        Ok(PyBytes::new_bound(py, &data))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("DecryptResult(hint={:?}, filename={:?})", self.hint, self.filename)
+    }
+}
+
+/// Everything which is readable from a cipher text without the key of the lockdate.
+#[pyclass(name = "InspectResult", module = "snailcrypt", get_all)]
+pub struct PyInspectResult {
+    version: u32,
+    lockdate: DateTime<FixedOffset>,
+    hint: String,
+    filename: String,
+    passphrase_required: bool,
+    /// True if the lockdate has not passed yet according to the local clock.
+    locked: bool,
+}
+
+#[pymethods]
+impl PyInspectResult {
+    fn __repr__(&self) -> String {
+        format!("InspectResult(version={}, lockdate={:?}, hint={:?}, filename={:?})",
+                       self.version,
+                       self.lockdate.to_rfc3339(),
+                       self.hint,
+                       self.filename)
+    }
+}
+
+#[pymethods]
+impl PyClient {
+    #[new]
+    #[pyo3(signature = (config = None))]
+    fn new(config: Option<PyRef<'_, PyConfig>>) -> PyClient {
+        let config = config
+            .map(|config| config.get_config().clone())
+            .unwrap_or_default();
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(analyzer_factory.create(),
+                                        Arc::new(config));
+
+        PyClient {
+            client: client_factory.create(),
+        }
+    }
+
+    /// Encrypt a plain text until a lockdate. Bytes need a filename, as they are locked like a file.
+    #[pyo3(signature = (plaintext, lockdate, hint = "", filename = "", passphrase = ""))]
+    fn encrypt(&self, py: Python<'_>, plaintext: Plaintext, lockdate: Lockdate, hint: &str, filename: &str, passphrase: &str) -> PyResult<String> {
+        let plaintext = match plaintext {
+            Plaintext::Text(text) => text,
+            Plaintext::Bytes(bytes) if !filename.is_empty() => base64::encode(bytes),
+            Plaintext::Bytes(bytes) => String::from_utf8(bytes)
+                .map_err(|error| PyValueError::new_err(format!("Binary content needs a filename: {}", error)))?,
+        };
+
+        let lockdate = match lockdate {
+            Lockdate::DateTime(lockdate) => lockdate,
+            Lockdate::Text(lockdate) => lockdate::Lockdate::parse_with_clock(lockdate.as_str(), self.client.get_clock().as_ref())
+                .map_err(PyValueError::new_err)?
+                .get_datetime(),
+        };
+
+        /***********************************************************************
+         * Release the GIL while waiting for the key server and encrypting, so
+         * other Python threads keep running
+         */
+        py.allow_threads(|| {
+            let lockdate = self.client.check_lockdate(lockdate)
+                .map_err(|error| LockdatePolicyError::new_err(error.to_string()))?;
+
+            self.client.encrypt(&ClientEncryptArg {
+                plaintext,
+                lockdate,
+                hint: String::from(hint),
+                filename: String::from(filename),
+                passphrase: String::from(passphrase),
+                escape_recipients: Vec::new(),
+            }).map_err(EncryptionError::new_err)
+        })
+    }
+
+    /// Decrypt a cipher text. The passphrase is only needed if the cipher text is protected by one.
+    #[pyo3(signature = (cipher, passphrase = ""))]
+    fn decrypt(&self, py: Python<'_>, cipher: &str, passphrase: &str) -> PyResult<PyDecryptResult> {
+        let result_success = py
+            .allow_threads(|| self.client.decrypt_with_passphrase(cipher, passphrase))
+            .map_err(decrypt_error)?;
+
+        Ok(PyDecryptResult {
+            plaintext: result_success.plaintext,
+            hint: result_success.hint,
+            filename: result_success.filename,
+        })
+    }
+
+    /// Inspect a cipher text without accessing the network.
+    fn inspect(&self, cipher: &str) -> PyResult<PyInspectResult> {
+        let inspect_result = self.client
+            .inspect(cipher)
+            .map_err(InvalidCipherError::new_err)?;
+
+        let locked = inspect_result.is_locked(&SystemClock::new());
+
+        Ok(PyInspectResult {
+            version: u32::from(&inspect_result.version),
+            lockdate: inspect_result.lockdate,
+            hint: inspect_result.hint,
+            filename: inspect_result.filename,
+            passphrase_required: inspect_result.passphrase_required,
+            locked,
+        })
+    }
+}
@@ -0,0 +1,140 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    config::{
+        Config,
+        DefaultConfig,
+    },
+    lockdate::LockdatePolicy,
+};
+
+use std::{
+    path::PathBuf,
+    time::Duration,
+};
+
+use pyo3::{
+    exceptions::PyValueError,
+    prelude::*,
+};
+
+/// The configuration of a client. Timeouts and the rules of the lockdate policy are given in seconds. A minimum lead
+/// time of 0 rejects lockdates in the past. Without any rule every lockdate is accepted.
+#[pyclass(name = "Config", module = "snailcrypt")]
+#[derive(Clone)]
+pub struct PyConfig {
+    config: DefaultConfig,
+}
+
+impl PyConfig {
+    pub(crate) fn get_config(&self) -> &DefaultConfig {
+        &self.config
+    }
+}
+
+fn duration_from_secs(name: &str, seconds: Option<f64>) -> PyResult<Option<Duration>> {
+    seconds
+        .map(|seconds| Duration::try_from_secs_f64(seconds)
+             .map_err(|error| PyValueError::new_err(format!("The {} is invalid: {}", name, error))))
+        .transpose()
+}
+
+fn policy_duration_from_secs(name: &str, seconds: Option<f64>) -> PyResult<Option<chrono::Duration>> {
+    duration_from_secs(name, seconds)?
+        .map(|duration| chrono::Duration::from_std(duration)
+             .map_err(|error| PyValueError::new_err(format!("The {} is invalid: {}", name, error))))
+        .transpose()
+}
+
+fn policy_duration_as_secs(duration: Option<chrono::Duration>) -> Option<f64> {
+    duration.map(|duration| duration.num_milliseconds() as f64 / 1000.0)
+}
+
+#[pymethods]
+impl PyConfig {
+    #[new]
+    #[pyo3(signature = (api_url = None, connect_timeout = None, timeout = None, cache_dir = None,
+                        min_lead_time = None, lockdate_granularity = None, max_horizon = None))]
+    fn new(api_url: Option<&str>, connect_timeout: Option<f64>, timeout: Option<f64>, cache_dir: Option<PathBuf>,
+           min_lead_time: Option<f64>, lockdate_granularity: Option<f64>, max_horizon: Option<f64>) -> PyResult<PyConfig> {
+        let mut config = DefaultConfig::new();
+
+        if let Some(api_url) = api_url {
+            config.set_api_url(api_url);
+        }
+        config.set_connect_timeout(duration_from_secs("connect timeout", connect_timeout)?);
+        config.set_timeout(duration_from_secs("timeout", timeout)?);
+        config.set_cache_dir(cache_dir);
+
+        if min_lead_time.is_some() || lockdate_granularity.is_some() || max_horizon.is_some() {
+            let mut lockdate_policy = LockdatePolicy::new();
+            lockdate_policy.set_min_lead_time(policy_duration_from_secs("minimum lead time", min_lead_time)?);
+            lockdate_policy.set_granularity(policy_duration_from_secs("lockdate granularity", lockdate_granularity)?);
+            lockdate_policy.set_max_horizon(policy_duration_from_secs("maximum horizon", max_horizon)?);
+            config.set_lockdate_policy(Some(lockdate_policy));
+        }
+
+        Ok(PyConfig { config })
+    }
+
+    #[getter]
+    fn api_url(&self) -> &str {
+        self.config.get_api_url()
+    }
+
+    #[getter]
+    fn connect_timeout(&self) -> Option<f64> {
+        self.config.get_connect_timeout().map(|timeout| timeout.as_secs_f64())
+    }
+
+    #[getter]
+    fn timeout(&self) -> Option<f64> {
+        self.config.get_timeout().map(|timeout| timeout.as_secs_f64())
+    }
+
+    #[getter]
+    fn cache_dir(&self) -> Option<PathBuf> {
+        self.config.get_cache_dir().map(PathBuf::from)
+    }
+
+    #[getter]
+    fn min_lead_time(&self) -> Option<f64> {
+        policy_duration_as_secs(self.config.get_lockdate_policy().and_then(LockdatePolicy::get_min_lead_time))
+    }
+
+    #[getter]
+    fn lockdate_granularity(&self) -> Option<f64> {
+        policy_duration_as_secs(self.config.get_lockdate_policy().and_then(LockdatePolicy::get_granularity))
+    }
+
+    #[getter]
+    fn max_horizon(&self) -> Option<f64> {
+        policy_duration_as_secs(self.config.get_lockdate_policy().and_then(LockdatePolicy::get_max_horizon))
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Config(api_url={:?})", self.config.get_api_url())
+    }
+}
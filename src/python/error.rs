@@ -0,0 +1,55 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::client::{
+    ClientDecryptErrorKind,
+    ClientDecryptResultFailure,
+};
+
+use pyo3::{
+    create_exception,
+    exceptions::PyException,
+    PyErr,
+};
+
+create_exception!(snailcrypt, SnailcryptError, PyException, "The base class of all errors raised by snailcrypt.");
+create_exception!(snailcrypt, EncryptionError, SnailcryptError, "The encryption failed, e.g. because the key server is not reachable.");
+create_exception!(snailcrypt, LockdatePolicyError, EncryptionError, "The lockdate violates the lockdate policy of the configuration or the limits of the key server.");
+create_exception!(snailcrypt, DecryptionError, SnailcryptError, "The decryption failed.");
+create_exception!(snailcrypt, InvalidCipherError, SnailcryptError, "The cipher text is invalid.");
+create_exception!(snailcrypt, PassphraseRequiredError, DecryptionError, "The cipher text is protected by a passphrase but none has been supplied.");
+create_exception!(snailcrypt, WrongPassphraseError, DecryptionError, "The supplied passphrase is wrong.");
+create_exception!(snailcrypt, NotYetReleasedError, DecryptionError, "The key server has not released the key of the lockdate yet.");
+create_exception!(snailcrypt, RevokedError, DecryptionError, "The message has been revoked by its sender.");
+
+/// Map a failed decryption to the exception of its kind.
+pub(crate) fn decrypt_error(failure: ClientDecryptResultFailure) -> PyErr {
+    match failure.kind {
+        ClientDecryptErrorKind::Generic => DecryptionError::new_err(failure.error_message),
+        ClientDecryptErrorKind::PassphraseRequired => PassphraseRequiredError::new_err(failure.error_message),
+        ClientDecryptErrorKind::WrongPassphrase => WrongPassphraseError::new_err(failure.error_message),
+        ClientDecryptErrorKind::NotYetReleased => NotYetReleasedError::new_err(failure.error_message),
+        ClientDecryptErrorKind::Revoked => RevokedError::new_err(failure.error_message),
+    }
+}
@@ -0,0 +1,66 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+//! Python bindings of snailcrypt. They are built using the feature "python".
+
+// The macros of pyo3 0.22 check its feature "gil-refs" in this crate and
+// convert every returned PyErr into itself.
+#![allow(unexpected_cfgs, clippy::useless_conversion)]
+
+mod client;
+mod config;
+mod error;
+
+pub use client::{
+    PyClient,
+    PyDecryptResult,
+    PyInspectResult,
+};
+pub use config::PyConfig;
+
+use pyo3::prelude::*;
+
+/// The Python module "snailcrypt".
+#[pymodule]
+#[pyo3(name = "snailcrypt")]
+pub fn init_module(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    let py = module.py();
+
+    module.add_class::<PyConfig>()?;
+    module.add_class::<PyClient>()?;
+    module.add_class::<PyDecryptResult>()?;
+    module.add_class::<PyInspectResult>()?;
+
+    module.add("SnailcryptError", py.get_type_bound::<error::SnailcryptError>())?;
+    module.add("EncryptionError", py.get_type_bound::<error::EncryptionError>())?;
+    module.add("LockdatePolicyError", py.get_type_bound::<error::LockdatePolicyError>())?;
+    module.add("DecryptionError", py.get_type_bound::<error::DecryptionError>())?;
+    module.add("InvalidCipherError", py.get_type_bound::<error::InvalidCipherError>())?;
+    module.add("PassphraseRequiredError", py.get_type_bound::<error::PassphraseRequiredError>())?;
+    module.add("WrongPassphraseError", py.get_type_bound::<error::WrongPassphraseError>())?;
+    module.add("NotYetReleasedError", py.get_type_bound::<error::NotYetReleasedError>())?;
+    module.add("RevokedError", py.get_type_bound::<error::RevokedError>())?;
+
+    Ok(())
+}
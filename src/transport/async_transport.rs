@@ -0,0 +1,67 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::transport::TransportResponse;
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+/// This trait supplies the asynchronous HTTP access to a key server used by asynchronous clients. Implement it to use a
+/// different HTTP client.
+#[async_trait]
+pub trait AsyncTransport: Send + Sync {
+    /// Send a JSON body to an URL using HTTP POST and return the body of the response. Errors are only returned if no
+    /// response has been received at all.
+    async fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<Vec<u8>, String>;
+
+    /// Send a JSON body to an URL using HTTP POST and return the status and the body of the response. The default
+    /// uses `post` and leaves the status unknown.
+    async fn post_with_status(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<TransportResponse, String> {
+        Ok(TransportResponse {
+            status: None,
+            body: self.post(url, body, connect_timeout, timeout).await?,
+        })
+    }
+
+    /// Request an URL using HTTP GET and return the body of the response, whatever its status. Errors are only
+    /// returned if no response has been received at all or if the transport does not support HTTP GET.
+    async fn get(&self, _url: &str, _connect_timeout: Option<Duration>, _timeout: Option<Duration>)
+        -> Result<Vec<u8>, String> {
+        Err(String::from("Error: the transport does not support HTTP GET"))
+    }
+
+    /// Request an URL using HTTP GET and return the point in time of the `Date` header of the response, whatever its
+    /// status. Returns an error if the transport does not supply response headers.
+    async fn get_date(&self, _url: &str, _connect_timeout: Option<Duration>, _timeout: Option<Duration>)
+        -> Result<DateTime<Utc>, String> {
+        Err(String::from("Error: the transport does not supply the Date header"))
+    }
+}
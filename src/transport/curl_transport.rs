@@ -0,0 +1,299 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::transport::{
+    Transport,
+    TransportResponse,
+    TransportStats,
+};
+
+use std::{
+    io::Read,
+    sync::{
+        atomic::{
+            AtomicU64,
+            Ordering,
+        },
+        Mutex,
+    },
+    time::Duration,
+};
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+use curl::{
+    easy::{
+        Easy,
+        HttpVersion,
+    },
+    Version,
+};
+
+/// The maximum number of idle curl handles kept for later requests.
+const MAX_IDLE_HANDLES: usize = 16;
+
+/// The default transport using curl. Every curl handle keeps its connections open after a request, so the handles are
+/// pooled and reused by later requests. HTTP/2 is used if both libcurl and the key server support it.
+pub struct CurlTransport {
+    idle_handles: Mutex<Vec<Easy>>,
+    requests: AtomicU64,
+    connections: AtomicU64,
+    reused_connections: AtomicU64,
+}
+
+impl CurlTransport {
+    pub fn new() -> CurlTransport {
+        CurlTransport {
+            idle_handles: Mutex::new(Vec::new()),
+            requests: AtomicU64::new(0),
+            connections: AtomicU64::new(0),
+            reused_connections: AtomicU64::new(0),
+        }
+    }
+
+    /// Take an idle handle from the pool or create a new one if there is none.
+    fn take_handle(&self) -> Easy {
+        self.idle_handles
+            .lock()
+            .ok()
+            .and_then(|mut idle_handles| idle_handles.pop())
+            .unwrap_or_else(Easy::new)
+    }
+
+    /// Perform a request using a pooled handle and count whether it opened a connection or reused one.
+    fn perform<T, F>(&self, request: F) -> Result<T, String>
+        where F: FnOnce(&mut Easy) -> Result<T, String> {
+        let mut handle = self.take_handle();
+        let result = request(&mut handle);
+
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        if let Ok(connects) = handle.num_connects() {
+            if connects > 0 {
+                self.connections.fetch_add(connects, Ordering::Relaxed);
+            } else if result.is_ok() {
+                self.reused_connections.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        self.put_handle(handle);
+
+        result
+    }
+
+    /// Return a handle to the pool, keeping its open connections for the next request.
+    fn put_handle(&self, handle: Easy) {
+        if let Ok(mut idle_handles) = self.idle_handles.lock() {
+            if idle_handles.len() < MAX_IDLE_HANDLES {
+                idle_handles.push(handle);
+            }
+        }
+    }
+}
+
+impl Default for CurlTransport {
+    fn default() -> CurlTransport {
+        CurlTransport::new()
+    }
+}
+
+impl Transport for CurlTransport {
+    fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<Vec<u8>, String> {
+        self.perform(|handle| post_with_handle(handle, url, body, connect_timeout, timeout))
+    }
+
+    fn post_with_status(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<TransportResponse, String> {
+        self.perform(|handle| {
+            let body = post_with_handle(handle, url, body, connect_timeout, timeout)?;
+            let status = handle.response_code()
+                .map_err(|error| error.to_string())?;
+
+            Ok(TransportResponse {
+                status: u16::try_from(status).ok(),
+                body,
+            })
+        })
+    }
+
+    fn get(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<Vec<u8>, String> {
+        self.perform(|handle| get_with_handle(handle, url, connect_timeout, timeout))
+    }
+
+    fn get_date(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<DateTime<Utc>, String> {
+        self.perform(|handle| get_date_with_handle(handle, url, connect_timeout, timeout))
+    }
+
+    fn get_stats(&self) -> Option<TransportStats> {
+        Some(TransportStats {
+            requests: self.requests.load(Ordering::Relaxed),
+            connections: self.connections.load(Ordering::Relaxed),
+            reused_connections: self.reused_connections.load(Ordering::Relaxed),
+        })
+    }
+}
+
+/// Apply the settings shared by every request to a handle. Resetting the handle keeps its open connections.
+fn setup_handle(handle: &mut Easy, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+    -> Result<(), String> {
+    handle.reset();
+    handle.url(url)
+        .map_err(|error| error.to_string())?;
+    handle.tcp_keepalive(true)
+        .map_err(|error| error.to_string())?;
+    if Version::get().feature_http2() {
+        handle.http_version(HttpVersion::V2TLS)
+            .map_err(|error| error.to_string())?;
+    }
+    if let Some(connect_timeout) = connect_timeout {
+        handle.connect_timeout(connect_timeout)
+            .map_err(|error| error.to_string())?;
+    }
+    if let Some(timeout) = timeout {
+        handle.timeout(timeout)
+            .map_err(|error| error.to_string())?;
+    }
+
+    Ok(())
+}
+
+fn post_with_handle(handle: &mut Easy, url: &str, body: &str, connect_timeout: Option<Duration>,
+                    timeout: Option<Duration>) -> Result<Vec<u8>, String> {
+    let mut input_str = body.as_bytes();
+
+    /***************************************************************************
+     * Setup HTTP post output data
+     */
+    let mut output_vector: Vec<u8> = Vec::with_capacity(512);
+
+    /***************************************************************************
+     * Basic setup of curl
+     */
+    setup_handle(handle, url, connect_timeout, timeout)?;
+    handle.post(true)
+        .map_err(|error| error.to_string())?;
+    handle.post_field_size(input_str.len() as u64)
+        .map_err(|error| error.to_string())?;
+
+    /***************************************************************************
+     * Perform HTTP POST which will eventually fill output_vector
+     */
+    {
+        let mut transfer = handle.transfer();
+
+        /***********************************************************************
+         * Set function to send data
+         */
+        transfer.read_function(|buffer| {
+            Ok(input_str.read(buffer).unwrap_or(0))
+        }).map_err(|error| error.to_string())?;
+
+        /***********************************************************************
+         * Set function to receive data
+         */
+        transfer.write_function(|buffer| {
+            output_vector.extend_from_slice(buffer);
+
+            Ok(buffer.len())
+        }).map_err(|error| error.to_string())?;
+
+        /***********************************************************************
+         * Perform POST
+         */
+        transfer.perform()
+            .map_err(|error| error.to_string())?;
+    }
+
+    Ok(output_vector)
+}
+
+fn get_with_handle(handle: &mut Easy, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+    -> Result<Vec<u8>, String> {
+    let mut output_vector: Vec<u8> = Vec::with_capacity(512);
+
+    setup_handle(handle, url, connect_timeout, timeout)?;
+    handle.get(true)
+        .map_err(|error| error.to_string())?;
+
+    /***************************************************************************
+     * Perform HTTP GET which will eventually fill output_vector
+     */
+    {
+        let mut transfer = handle.transfer();
+
+        transfer.write_function(|buffer| {
+            output_vector.extend_from_slice(buffer);
+
+            Ok(buffer.len())
+        }).map_err(|error| error.to_string())?;
+
+        transfer.perform()
+            .map_err(|error| error.to_string())?;
+    }
+
+    Ok(output_vector)
+}
+
+fn get_date_with_handle(handle: &mut Easy, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+    -> Result<DateTime<Utc>, String> {
+    let mut date: Option<String> = None;
+
+    setup_handle(handle, url, connect_timeout, timeout)?;
+    handle.get(true)
+        .map_err(|error| error.to_string())?;
+
+    /***************************************************************************
+     * Perform HTTP GET keeping the Date header and dropping the body
+     */
+    {
+        let mut transfer = handle.transfer();
+
+        transfer.header_function(|header| {
+            if let Some((name, value)) = String::from_utf8_lossy(header).split_once(':') {
+                if name.trim().eq_ignore_ascii_case("date") {
+                    date = Some(String::from(value.trim()));
+                }
+            }
+
+            true
+        }).map_err(|error| error.to_string())?;
+
+        transfer.write_function(|buffer| {
+            Ok(buffer.len())
+        }).map_err(|error| error.to_string())?;
+
+        transfer.perform()
+            .map_err(|error| error.to_string())?;
+    }
+
+    let date = date.ok_or_else(|| String::from("Error: the response has no Date header"))?;
+
+    DateTime::parse_from_rfc2822(date.as_str())
+        .map(|date| date.to_utc())
+        .map_err(|error| format!("Error: unable to parse the Date header \"{}\": {}", date, error))
+}
@@ -0,0 +1,69 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+#[cfg(feature = "async")]
+mod async_transport;
+#[cfg(feature = "native")]
+mod curl_transport;
+#[cfg(feature = "async")]
+mod reqwest_transport;
+#[allow(clippy::module_inception)]
+mod transport;
+
+#[cfg(feature = "async")]
+pub use async_transport::AsyncTransport;
+#[cfg(feature = "native")]
+pub use curl_transport::CurlTransport;
+#[cfg(feature = "async")]
+pub use reqwest_transport::ReqwestTransport;
+pub use transport::{
+    Transport,
+    TransportResponse,
+    TransportStats,
+};
+
+use std::sync::Arc;
+#[cfg(feature = "native")]
+use std::sync::OnceLock;
+
+/// The transport used if the configuration does not supply one. Returns `None` if the library has been built without
+/// an HTTP client, e.g. for WebAssembly. The transport is shared by the whole process, so all clients reuse the same
+/// connections.
+pub fn default_transport() -> Option<Arc<dyn Transport>> {
+    #[cfg(feature = "native")]
+    {
+        static DEFAULT_TRANSPORT: OnceLock<Arc<CurlTransport>> = OnceLock::new();
+
+        Some(DEFAULT_TRANSPORT.get_or_init(|| Arc::new(CurlTransport::new())).clone())
+    }
+
+    #[cfg(not(feature = "native"))]
+    return None;
+}
+
+/// The asynchronous transport used if the configuration does not supply one.
+#[cfg(feature = "async")]
+pub fn default_async_transport() -> Option<Arc<dyn AsyncTransport>> {
+    Some(Arc::new(ReqwestTransport::new()))
+}
@@ -0,0 +1,150 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::transport::{
+    AsyncTransport,
+    TransportResponse,
+};
+
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+/// The default asynchronous transport using reqwest. It must be used within a tokio runtime. Connections are reused
+/// between requests.
+pub struct ReqwestTransport {
+    /// The HTTP clients by their connect timeout, as reqwest only supports a connect timeout per HTTP client.
+    clients: Mutex<HashMap<Option<Duration>, reqwest::Client>>,
+}
+
+impl ReqwestTransport {
+    pub fn new() -> ReqwestTransport {
+        ReqwestTransport {
+            clients: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get_client(&self, connect_timeout: Option<Duration>) -> Result<reqwest::Client, String> {
+        let mut clients = self.clients
+            .lock()
+            .map_err(|error| error.to_string())?;
+
+        if let Some(client) = clients.get(&connect_timeout) {
+            return Ok(client.clone());
+        }
+
+        let mut builder = reqwest::Client::builder();
+        if let Some(connect_timeout) = connect_timeout {
+            builder = builder.connect_timeout(connect_timeout);
+        }
+        let client = builder
+            .build()
+            .map_err(|error| error.to_string())?;
+
+        clients.insert(connect_timeout, client.clone());
+
+        Ok(client)
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> ReqwestTransport {
+        ReqwestTransport::new()
+    }
+}
+
+/// Send a request and wait for the response. The key server reports failures within the JSON body, so the response is
+/// returned regardless of its status.
+async fn send(mut request: reqwest::RequestBuilder, timeout: Option<Duration>) -> Result<reqwest::Response, String> {
+    if let Some(timeout) = timeout {
+        request = request.timeout(timeout);
+    }
+
+    request
+        .send()
+        .await
+        .map_err(|error| error.to_string())
+}
+
+async fn read_body(response: reqwest::Response) -> Result<Vec<u8>, String> {
+    let output_bytes = response
+        .bytes()
+        .await
+        .map_err(|error| error.to_string())?;
+
+    Ok(output_bytes.to_vec())
+}
+
+#[async_trait]
+impl AsyncTransport for ReqwestTransport {
+    async fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<Vec<u8>, String> {
+        Ok(self.post_with_status(url, body, connect_timeout, timeout).await?.body)
+    }
+
+    async fn post_with_status(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<TransportResponse, String> {
+        let request = self.get_client(connect_timeout)?
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(String::from(body));
+
+        let response = send(request, timeout).await?;
+        let status = response.status().as_u16();
+
+        Ok(TransportResponse {
+            status: Some(status),
+            body: read_body(response).await?,
+        })
+    }
+
+    async fn get(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<Vec<u8>, String> {
+        let response = send(self.get_client(connect_timeout)?.get(url), timeout).await?;
+
+        read_body(response).await
+    }
+
+    async fn get_date(&self, url: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<DateTime<Utc>, String> {
+        let response = send(self.get_client(connect_timeout)?.get(url), timeout).await?;
+
+        let date = response.headers()
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .ok_or_else(|| String::from("Error: the response has no Date header"))?;
+
+        DateTime::parse_from_rfc2822(date)
+            .map(|date| date.to_utc())
+            .map_err(|error| format!("Error: unable to parse the Date header \"{}\": {}", date, error))
+    }
+}
@@ -0,0 +1,88 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use std::time::Duration;
+
+use chrono::{
+    DateTime,
+    Utc,
+};
+
+/// This trait supplies the HTTP access to a key server. Implement it to use a different HTTP client, e.g. the one of a
+/// JavaScript host.
+pub trait Transport: Send + Sync {
+    /// Send a JSON body to an URL using HTTP POST and return the body of the response. Errors are only returned if no
+    /// response has been received at all.
+    fn post(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<Vec<u8>, String>;
+
+    /// Send a JSON body to an URL using HTTP POST and return the status and the body of the response. The default
+    /// uses `post` and leaves the status unknown.
+    fn post_with_status(&self, url: &str, body: &str, connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<TransportResponse, String> {
+        Ok(TransportResponse {
+            status: None,
+            body: self.post(url, body, connect_timeout, timeout)?,
+        })
+    }
+
+    /// Request an URL using HTTP GET and return the body of the response, whatever its status. Errors are only
+    /// returned if no response has been received at all or if the transport does not support HTTP GET.
+    fn get(&self, _url: &str, _connect_timeout: Option<Duration>, _timeout: Option<Duration>)
+        -> Result<Vec<u8>, String> {
+        Err(String::from("Error: the transport does not support HTTP GET"))
+    }
+
+    /// Request an URL using HTTP GET and return the point in time of the `Date` header of the response, whatever its
+    /// status. This allows deriving the time from the key server. Returns an error if the transport does not supply
+    /// response headers.
+    fn get_date(&self, _url: &str, _connect_timeout: Option<Duration>, _timeout: Option<Duration>)
+        -> Result<DateTime<Utc>, String> {
+        Err(String::from("Error: the transport does not supply the Date header"))
+    }
+
+    /// Get the counters about the reuse of connections. `None` if the transport does not count them.
+    fn get_stats(&self) -> Option<TransportStats> {
+        None
+    }
+}
+
+/// A response received by a transport.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TransportResponse {
+    /// The HTTP status code. `None` if the transport does not supply it.
+    pub status: Option<u16>,
+    pub body: Vec<u8>,
+}
+
+/// Counters of a transport about the reuse of its connections.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct TransportStats {
+    /// The number of requests sent.
+    pub requests: u64,
+    /// The number of connections opened.
+    pub connections: u64,
+    /// The number of requests sent over an already open connection.
+    pub reused_connections: u64,
+}
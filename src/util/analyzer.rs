@@ -22,10 +22,10 @@
   SOFTWARE.
 *******************************************************************************/
 
-use crate::client::ClientVersion;
+use crate::format::ClientVersion;
 
 /// This trait supplies the function to use something as an analyzer. The main function of an analzyer object is to determine which client version a cipher text is using.
-pub trait Analyzer {
+pub trait Analyzer: Send + Sync {
     /// Get the client version a cipher text is using.
     fn get_version(&self, ciphertext: &str) -> Result<ClientVersion, String>;
             
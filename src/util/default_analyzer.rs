@@ -23,7 +23,7 @@
 *******************************************************************************/
 
 use crate::{
-    client::ClientVersion,
+    format::ClientVersion,
     util::Analyzer,
 };
 
@@ -35,14 +35,20 @@ pub struct DefaultAnalyzer {
 impl DefaultAnalyzer {
     #[allow(unused)]
     pub fn new() -> DefaultAnalyzer {
-        return DefaultAnalyzer { };
+        DefaultAnalyzer { }
+    }
+}
+
+impl Default for DefaultAnalyzer {
+    fn default() -> DefaultAnalyzer {
+        DefaultAnalyzer::new()
     }
 }
 
 impl Analyzer for DefaultAnalyzer {
     fn get_version(&self, ciphertext: &str) -> Result<ClientVersion, String> {
 		if ciphertext.is_empty() {
-			panic!("Cipher is invalid. It must at least contain something.");
+			return Err(String::from("Cipher is invalid. It must at least contain something."));
 		}	
 	
 		let cipher_comp_vec: Vec<&str> = ciphertext.split_terminator(':').collect();
@@ -60,6 +66,9 @@ impl Analyzer for DefaultAnalyzer {
 			"1" => Ok(ClientVersion::V1),
 			"2" => Ok(ClientVersion::V2),			
 			"3" => Ok(ClientVersion::V3),			
+			"4" => Ok(ClientVersion::V4),
+			"5" => Ok(ClientVersion::V5),
+			"6" => Ok(ClientVersion::V6),
 			_   => Err(format!("Unknown client version: {}", client_version)),
 		}
 	}
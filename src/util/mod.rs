@@ -24,8 +24,12 @@
 
 mod analyzer;
 mod default_analyzer;
+#[cfg(all(test, feature = "client", not(target_arch = "wasm32")))]
+mod stand_in_server;
 
 pub use analyzer::Analyzer;
 pub use default_analyzer::DefaultAnalyzer;
+#[cfg(all(test, feature = "client", not(target_arch = "wasm32")))]
+pub use stand_in_server::StandInServer;
 
 
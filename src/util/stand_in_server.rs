@@ -0,0 +1,368 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::clock::{
+    self,
+    Clock,
+};
+
+use std::{
+    collections::HashMap,
+    io::{
+        Read,
+        Write,
+    },
+    net::{
+        TcpListener,
+        TcpStream,
+    },
+    sync::{
+        Arc,
+        Mutex,
+    },
+    thread,
+};
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+    Utc,
+};
+use openssl::{
+    pkey::Private,
+    rand::rand_bytes,
+    rsa::Rsa,
+};
+use serde_json::{
+    json,
+    Value,
+};
+
+const DATETIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S%z";
+const KEY_SIZE: u32 = 2048;
+
+/// A revocable message registered on a stand-in server.
+struct StandInMessage {
+    lockdate: DateTime<FixedOffset>,
+    key: Rsa<Private>,
+    revocation_token: String,
+    revoked: bool,
+}
+
+impl StandInMessage {
+    fn get_status(&self, now: DateTime<Utc>) -> &str {
+        if self.revoked {
+            return "revoked";
+        }
+
+        if self.lockdate <= now {
+            return "released";
+        }
+
+        "pending"
+    }
+}
+
+/// The state shared between all connections of a stand-in server.
+struct StandInState {
+    keys: Mutex<HashMap<i64, Rsa<Private>>>,
+    messages: Mutex<HashMap<String, StandInMessage>>,
+    clock: Arc<dyn Clock>,
+    /// The answer of the metadata endpoint without the server time. `None` if the endpoint is absent.
+    metadata: Option<Value>,
+}
+
+/// A minimal stand-in for the snailcrypt key server listening on localhost. It issues one key pair per lockdate and releases the private key once the lockdate has passed. Revocable messages get a key pair of their own. This allows running the tests without access to api.snailcrypt.com.
+pub struct StandInServer {
+    api_url: String,
+}
+
+impl StandInServer {
+    /// Start a new stand-in server on a random port. The server runs until the process ends.
+    pub fn start() -> StandInServer {
+        StandInServer::start_with_clock(clock::default_clock())
+    }
+
+    /// Start a new stand-in server releasing keys according to a clock, e.g. a manual one shared with the clients.
+    pub fn start_with_clock(clock: Arc<dyn Clock>) -> StandInServer {
+        StandInServer::start_with_metadata(clock, Some(json!({
+            "api_version": 1,
+            "key_algorithms": [{ "name": "RSA", "sizes": [KEY_SIZE] }],
+        })))
+    }
+
+    /// Start a new stand-in server answering its metadata endpoint using custom capabilities, e.g. a lockdate
+    /// granularity. `None` removes the metadata endpoint like on older key servers.
+    pub fn start_with_metadata(clock: Arc<dyn Clock>, metadata: Option<Value>) -> StandInServer {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let api_url = format!("http://{}", listener.local_addr().unwrap());
+
+        let state = Arc::new(StandInState {
+            keys: Mutex::new(HashMap::new()),
+            messages: Mutex::new(HashMap::new()),
+            clock,
+            metadata,
+        });
+
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let stream = match stream {
+                    Ok(stream) => stream,
+                    Err(_) => continue,
+                };
+
+                let state = Arc::clone(&state);
+                thread::spawn(move || {
+                    handle_connection(stream, &state);
+                });
+            }
+        });
+
+        StandInServer { api_url }
+    }
+
+    pub fn get_api_url(&self) -> &str {
+        self.api_url.as_str()
+    }
+}
+
+/// A parsed HTTP request.
+struct StandInRequest {
+    method: String,
+    path: String,
+    body: Vec<u8>,
+    /// Whether the client asked to close the connection after the response.
+    close: bool,
+}
+
+fn read_request(stream: &mut TcpStream) -> Option<StandInRequest> {
+    /***************************************************************************
+     * Read until the end of the header section
+     */
+    let mut buffer: Vec<u8> = Vec::new();
+    let mut chunk = [0u8; 1024];
+    let header_end = loop {
+        if let Some(pos) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            break pos + 4;
+        }
+
+        let read = stream.read(&mut chunk).ok()?;
+        if read == 0 {
+            return None;
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    };
+
+    let header_section = String::from_utf8_lossy(&buffer[..header_end]).to_string();
+    let mut lines = header_section.split("\r\n");
+    let mut request_line = lines.next()?.split(' ');
+    let method = String::from(request_line.next()?);
+    let path = String::from(request_line.next()?);
+
+    let headers: Vec<(&str, &str)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .collect();
+    let content_length: usize = headers
+        .iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse().ok())
+        .unwrap_or(0);
+    let close = headers
+        .iter()
+        .any(|(name, value)| name.eq_ignore_ascii_case("connection") && value.trim().eq_ignore_ascii_case("close"));
+
+    /***************************************************************************
+     * Read the remaining body
+     */
+    let mut body: Vec<u8> = buffer[header_end..].to_vec();
+    while body.len() < content_length {
+        let read = stream.read(&mut chunk).ok()?;
+        if read == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..read]);
+    }
+    body.truncate(content_length);
+
+    Some(StandInRequest { method, path, body, close })
+}
+
+fn write_response(stream: &mut TcpStream, status: u16, body: &Value, close: bool, date: DateTime<Utc>) -> bool {
+    let body_string = body.to_string();
+    let response = format!("HTTP/1.1 {} {}\r\nDate: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: {}\r\n\r\n{}",
+                           status,
+                           if status == 200 { "OK" } else { "Error" },
+                           date.format("%a, %d %b %Y %H:%M:%S GMT"),
+                           body_string.len(),
+                           if close { "close" } else { "keep-alive" },
+                           body_string);
+    stream.write_all(response.as_bytes()).is_ok()
+}
+
+/// Answer the requests of a connection until the client closes it, as clients reuse their connections.
+fn handle_connection(mut stream: TcpStream, state: &StandInState) {
+    while let Some(request) = read_request(&mut stream) {
+        let (status, body) = match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/metadata") => handle_metadata(state),
+            ("POST", "/keys") => handle_keys(&request, state),
+            ("POST", "/messages") => handle_message_register(&request, state),
+            ("POST", "/messages/key") => handle_message_key(&request, state),
+            ("POST", "/messages/revoke") => handle_message_revoke(&request, state),
+            ("POST", "/messages/status") => handle_message_status(&request, state),
+            _ => (404, json!({ "code": 404, "message": "Not found" })),
+        };
+
+        if !write_response(&mut stream, status, &body, request.close, state.clock.now()) || request.close {
+            return;
+        }
+    }
+}
+
+fn handle_metadata(state: &StandInState) -> (u16, Value) {
+    let mut output = match &state.metadata {
+        Some(metadata) => metadata.clone(),
+        None => return (404, json!({ "code": 404, "message": "Not found" })),
+    };
+
+    output["server_time"] = Value::from(state.clock.now().format(DATETIME_FORMAT).to_string());
+
+    (200, output)
+}
+
+fn handle_keys(request: &StandInRequest, state: &StandInState) -> (u16, Value) {
+    let input: Value = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+    let lockdate = match input["lock_date"]
+        .as_str()
+        .and_then(|lock_date| DateTime::parse_from_str(lock_date, DATETIME_FORMAT).ok()) {
+        Some(lockdate) => lockdate,
+        None => return (400, json!({ "code": 400, "message": "Invalid lock date" })),
+    };
+
+    let mut keys = state.keys.lock().unwrap();
+    let key = keys
+        .entry(lockdate.timestamp())
+        .or_insert_with(|| Rsa::generate(KEY_SIZE).unwrap());
+
+    let public_key = String::from_utf8(key.public_key_to_pem().unwrap()).unwrap();
+    let mut output = json!({ "public_key": public_key });
+
+    if lockdate <= state.clock.now() {
+        output["private_key"] = Value::from(String::from_utf8(key.private_key_to_pem().unwrap()).unwrap());
+    }
+
+    (200, output)
+}
+
+fn random_hex(size: usize) -> String {
+    let mut bytes: Vec<u8> = vec![0; size];
+    rand_bytes(&mut bytes).unwrap();
+
+    bytes
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn handle_message_register(request: &StandInRequest, state: &StandInState) -> (u16, Value) {
+    let input: Value = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+    let lockdate = match input["lock_date"]
+        .as_str()
+        .and_then(|lock_date| DateTime::parse_from_str(lock_date, DATETIME_FORMAT).ok()) {
+        Some(lockdate) => lockdate,
+        None => return (400, json!({ "code": 400, "message": "Invalid lock date" })),
+    };
+
+    let message = StandInMessage {
+        lockdate,
+        key: Rsa::generate(KEY_SIZE).unwrap(),
+        revocation_token: random_hex(32),
+        revoked: false,
+    };
+    let message_id = random_hex(16);
+
+    let output = json!({
+        "message_id": message_id,
+        "public_key": String::from_utf8(message.key.public_key_to_pem().unwrap()).unwrap(),
+        "revocation_token": message.revocation_token,
+    });
+
+    state.messages.lock().unwrap().insert(message_id, message);
+
+    (200, output)
+}
+
+fn handle_message_key(request: &StandInRequest, state: &StandInState) -> (u16, Value) {
+    let input: Value = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+    let messages = state.messages.lock().unwrap();
+    let message = match messages.get(input["message_id"].as_str().unwrap_or("")) {
+        Some(message) => message,
+        None => return (404, json!({ "code": 404, "message": "Unknown message" })),
+    };
+
+    let mut output = json!({
+        "status": message.get_status(state.clock.now()),
+        "public_key": String::from_utf8(message.key.public_key_to_pem().unwrap()).unwrap(),
+    });
+
+    if message.get_status(state.clock.now()) == "released" {
+        output["private_key"] = Value::from(String::from_utf8(message.key.private_key_to_pem().unwrap()).unwrap());
+    }
+
+    (200, output)
+}
+
+fn handle_message_revoke(request: &StandInRequest, state: &StandInState) -> (u16, Value) {
+    let input: Value = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+    let mut messages = state.messages.lock().unwrap();
+    let message = match messages.get_mut(input["message_id"].as_str().unwrap_or("")) {
+        Some(message) => message,
+        None => return (404, json!({ "code": 404, "message": "Unknown message" })),
+    };
+
+    if input["revocation_token"].as_str() != Some(message.revocation_token.as_str()) {
+        return (403, json!({ "code": 403, "message": "Invalid revocation token" }));
+    }
+
+    if message.get_status(state.clock.now()) == "released" {
+        return (409, json!({ "code": 409, "message": "The message has already been released" }));
+    }
+
+    message.revoked = true;
+
+    (200, json!({ "status": message.get_status(state.clock.now()) }))
+}
+
+fn handle_message_status(request: &StandInRequest, state: &StandInState) -> (u16, Value) {
+    let input: Value = serde_json::from_slice(&request.body).unwrap_or(Value::Null);
+    let messages = state.messages.lock().unwrap();
+    let message = match messages.get(input["message_id"].as_str().unwrap_or("")) {
+        Some(message) => message,
+        None => return (404, json!({ "code": 404, "message": "Unknown message" })),
+    };
+
+    (200, json!({
+        "status": message.get_status(state.clock.now()),
+        "lock_date": message.lockdate.format(DATETIME_FORMAT).to_string(),
+    }))
+}
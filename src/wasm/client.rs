@@ -0,0 +1,234 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::{
+    client::{
+        Client,
+        ClientDecryptErrorKind,
+        ClientDecryptResultFailure,
+        ClientEncryptArg,
+    },
+    clock::SystemClock,
+    config::Config,
+    factory,
+    lockdate::{
+        self,
+        LockdatePolicy,
+    },
+    transport::Transport,
+    wasm::JsTransport,
+};
+
+use std::sync::Arc;
+
+use chrono::{
+    DateTime,
+    FixedOffset,
+};
+use js_sys::Function;
+use wasm_bindgen::prelude::*;
+
+/// The configuration of a client created from JavaScript.
+struct WasmConfig {
+    api_url: String,
+    transport: Arc<dyn Transport>,
+    lockdate_policy: Option<LockdatePolicy>,
+}
+
+impl Config for WasmConfig {
+    fn get_api_url(&self) -> &str {
+        self.api_url.as_str()
+    }
+
+    fn get_lockdate_policy(&self) -> Option<&LockdatePolicy> {
+        self.lockdate_policy.as_ref()
+    }
+
+    fn get_transport(&self) -> Option<Arc<dyn Transport>> {
+        Some(self.transport.clone())
+    }
+}
+
+/// Create a JavaScript error. Its name tells the kind of a failed decryption, e.g. "NotYetReleasedError".
+fn js_error(name: &str, message: &str) -> JsValue {
+    let error = js_sys::Error::new(message);
+    error.set_name(name);
+
+    error.into()
+}
+
+fn decrypt_error(failure: ClientDecryptResultFailure) -> JsValue {
+    let name = match failure.kind {
+        ClientDecryptErrorKind::Generic => "DecryptionError",
+        ClientDecryptErrorKind::PassphraseRequired => "PassphraseRequiredError",
+        ClientDecryptErrorKind::WrongPassphrase => "WrongPassphraseError",
+        ClientDecryptErrorKind::NotYetReleased => "NotYetReleasedError",
+        ClientDecryptErrorKind::Revoked => "RevokedError",
+    };
+
+    js_error(name, failure.error_message.as_str())
+}
+
+/// The rules a lockdate has to follow before anything is encrypted until it. All durations are given in seconds. A
+/// minimum lead time of 0 rejects lockdates in the past. Omitted rules are not checked.
+#[wasm_bindgen(js_name = LockdatePolicy)]
+pub struct WasmLockdatePolicy {
+    lockdate_policy: LockdatePolicy,
+}
+
+fn policy_duration_from_secs(name: &str, seconds: Option<f64>) -> Result<Option<chrono::Duration>, JsValue> {
+    seconds
+        .map(|seconds| std::time::Duration::try_from_secs_f64(seconds)
+             .ok()
+             .and_then(|duration| chrono::Duration::from_std(duration).ok())
+             .ok_or_else(|| js_error("RangeError", format!("The {} is invalid.", name).as_str())))
+        .transpose()
+}
+
+#[wasm_bindgen(js_class = LockdatePolicy)]
+impl WasmLockdatePolicy {
+    #[wasm_bindgen(constructor)]
+    pub fn new(min_lead_time: Option<f64>, granularity: Option<f64>, max_horizon: Option<f64>) -> Result<WasmLockdatePolicy, JsValue> {
+        let mut lockdate_policy = LockdatePolicy::new();
+        lockdate_policy.set_min_lead_time(policy_duration_from_secs("minimum lead time", min_lead_time)?);
+        lockdate_policy.set_granularity(policy_duration_from_secs("granularity", granularity)?);
+        lockdate_policy.set_max_horizon(policy_duration_from_secs("maximum horizon", max_horizon)?);
+
+        Ok(WasmLockdatePolicy { lockdate_policy })
+    }
+}
+
+/// A client for encrypting and decrypting snailcrypt cipher texts from JavaScript.
+#[wasm_bindgen(js_name = Client)]
+pub struct WasmClient {
+    client: Arc<dyn Client>,
+}
+
+impl WasmClient {
+    /// Create a client accessing the key server using a transport implemented in Rust. This is not available from
+    /// JavaScript, but allows embedding and testing the bindings outside of a JavaScript host.
+    pub fn new_with_transport(transport: Arc<dyn Transport>, api_url: Option<String>, lockdate_policy: Option<LockdatePolicy>) -> WasmClient {
+        let config = WasmConfig {
+            api_url: api_url.unwrap_or_else(|| String::from("https://api.snailcrypt.com")),
+            transport,
+            lockdate_policy,
+        };
+
+        let analyzer_factory: factory::AnalyzerFactory = factory::AnalyzerFactory::new();
+        let client_factory: factory::ClientFactory = factory::ClientFactory::new(analyzer_factory.create(),
+                                        Arc::new(config));
+
+        WasmClient {
+            client: client_factory.create(),
+        }
+    }
+}
+
+/// The result of a successful decryption.
+#[wasm_bindgen(js_name = DecryptResult, getter_with_clone)]
+pub struct WasmDecryptResult {
+    pub plaintext: String,
+    pub hint: String,
+    pub filename: String,
+}
+
+/// Everything which is readable from a cipher text without the key of the lockdate.
+#[wasm_bindgen(js_name = InspectResult, getter_with_clone)]
+pub struct WasmInspectResult {
+    pub version: u32,
+    /// The lockdate formatted according to RFC 3339.
+    pub lockdate: String,
+    pub hint: String,
+    pub filename: String,
+    #[wasm_bindgen(js_name = passphraseRequired)]
+    pub passphrase_required: bool,
+    /// True if the lockdate has not passed yet according to the local clock.
+    pub locked: bool,
+}
+
+#[wasm_bindgen(js_class = Client)]
+impl WasmClient {
+    /// Create a client accessing the key server using a transport function. See `JsTransport` for its signature.
+    /// Without a lockdate policy every lockdate is accepted.
+    #[wasm_bindgen(constructor)]
+    pub fn new(transport: Function, api_url: Option<String>, lockdate_policy: Option<WasmLockdatePolicy>) -> WasmClient {
+        WasmClient::new_with_transport(Arc::new(JsTransport::new(transport)),
+                                       api_url,
+                                       lockdate_policy.map(|lockdate_policy| lockdate_policy.lockdate_policy))
+    }
+
+    /// Encrypt a plain text until a lockdate. The lockdate is parsed using `Lockdate`, e.g. `2030-01-01T12:00:00Z`,
+    /// `+3d12h` or `tomorrow 09:00 Europe/Vienna`.
+    pub fn encrypt(&self, plaintext: &str, lockdate: &str, hint: Option<String>, filename: Option<String>, passphrase: Option<String>)
+        -> Result<String, JsValue> {
+        let lockdate: DateTime<FixedOffset> = lockdate::Lockdate::parse_with_clock(lockdate, self.client.get_clock().as_ref())
+            .map_err(|error| js_error("RangeError", error.as_str()))?
+            .get_datetime();
+        let lockdate = self.client
+            .check_lockdate(lockdate)
+            .map_err(|error| js_error("LockdatePolicyError", error.to_string().as_str()))?;
+
+        self.client
+            .encrypt(&ClientEncryptArg {
+                plaintext: String::from(plaintext),
+                lockdate,
+                hint: hint.unwrap_or_default(),
+                filename: filename.unwrap_or_default(),
+                passphrase: passphrase.unwrap_or_default(),
+                escape_recipients: Vec::new(),
+            })
+            .map_err(|error| js_error("EncryptionError", error.as_str()))
+    }
+
+    /// Decrypt a cipher text. The passphrase is only needed if the cipher text is protected by one.
+    pub fn decrypt(&self, cipher: &str, passphrase: Option<String>) -> Result<WasmDecryptResult, JsValue> {
+        let result_success = self.client
+            .decrypt_with_passphrase(cipher, passphrase.unwrap_or_default().as_str())
+            .map_err(decrypt_error)?;
+
+        Ok(WasmDecryptResult {
+            plaintext: result_success.plaintext,
+            hint: result_success.hint,
+            filename: result_success.filename,
+        })
+    }
+
+    /// Inspect a cipher text without accessing the network.
+    pub fn inspect(&self, cipher: &str) -> Result<WasmInspectResult, JsValue> {
+        let inspect_result = self.client
+            .inspect(cipher)
+            .map_err(|error| js_error("InvalidCipherError", error.as_str()))?;
+
+        let locked = inspect_result.is_locked(&SystemClock::new());
+
+        Ok(WasmInspectResult {
+            version: u32::from(&inspect_result.version),
+            lockdate: inspect_result.lockdate.to_rfc3339(),
+            hint: inspect_result.hint,
+            filename: inspect_result.filename,
+            passphrase_required: inspect_result.passphrase_required,
+            locked,
+        })
+    }
+}
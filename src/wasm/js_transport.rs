@@ -0,0 +1,81 @@
+/******************************************************************************
+  This file is part of snailcrypt.
+
+  Copyright 2023 Richard Bäck <richard.baeck@snailcrypt.com>
+
+  Permission is hereby granted, free of charge, to any person obtaining a copy
+  of this software and associated documentation files (the "Software"), to deal
+  in the Software without restriction, including without limitation the rights
+  to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+  copies of the Software, and to permit persons to whom the Software is
+  furnished to do so, subject to the following conditions:
+
+  The above copyright notice and this permission notice shall be included in all
+  copies or substantial portions of the Software.
+
+  THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+  IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+  FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+  AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+  LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+  OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+  SOFTWARE.
+*******************************************************************************/
+
+use crate::transport::Transport;
+
+use std::time::Duration;
+
+use js_sys::{
+    Function,
+    Uint8Array,
+};
+use wasm_bindgen::{
+    JsCast,
+    JsValue,
+};
+
+/// A transport calling a JavaScript function as `transport(url, body, timeoutMs)`. The function has to send the body
+/// using HTTP POST and return the body of the response either as a string or as an `Uint8Array`. `timeoutMs` is
+/// `undefined` if no timeout is configured. The function is called synchronously, e.g. it may use a synchronous
+/// `XMLHttpRequest` within a web worker.
+pub struct JsTransport {
+    function: Function,
+}
+
+impl JsTransport {
+    pub fn new(function: Function) -> JsTransport {
+        JsTransport { function }
+    }
+}
+
+/// Get the message of a value thrown by JavaScript.
+fn js_error_message(error: JsValue) -> String {
+    error
+        .as_string()
+        .or_else(|| js_sys::Error::from(error.clone()).message().as_string())
+        .unwrap_or_else(|| format!("{:?}", error))
+}
+
+impl Transport for JsTransport {
+    fn post(&self, url: &str, body: &str, _connect_timeout: Option<Duration>, timeout: Option<Duration>)
+        -> Result<Vec<u8>, String> {
+        let timeout_ms = timeout
+            .map(|timeout| JsValue::from_f64(timeout.as_millis() as f64))
+            .unwrap_or(JsValue::UNDEFINED);
+
+        let response = self.function
+            .call3(&JsValue::NULL, &JsValue::from_str(url), &JsValue::from_str(body), &timeout_ms)
+            .map_err(js_error_message)?;
+
+        if let Some(response) = response.as_string() {
+            return Ok(response.into_bytes());
+        }
+
+        if response.is_instance_of::<Uint8Array>() {
+            return Ok(Uint8Array::from(response).to_vec());
+        }
+
+        Err(String::from("The transport must return a string or an Uint8Array."))
+    }
+}